@@ -0,0 +1,582 @@
+//! Geometric bounding boxes of paths, shapes and documents.
+
+use crate::children::{Child, CommonKVs, Curves, LineCap, LineJoin, Opacity, PathData, Segment};
+use crate::length::{Axis, LengthContext};
+use crate::paint::{Color, FillRule, Paint};
+use crate::transform::Matrix;
+use crate::Svg;
+
+type Point = (f32, f32);
+
+/// An axis aligned rectangle, from `(min_x, min_y)` to `(max_x, max_y)`.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::Svg;
+///
+/// let svg: Svg = r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="10"/></svg>"#.parse().unwrap();
+/// let bbox = svg.bbox().unwrap();
+/// assert_eq!((bbox.min_x, bbox.max_y), (2.0, 22.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BBox {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl BBox {
+    pub fn new(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Self {
+        BBox {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+    /// the empty box at a point
+    pub fn from_point((x, y): Point) -> Self {
+        BBox::new(x, y, x, y)
+    }
+    pub fn width(&self) -> f32 {
+        self.max_x - self.min_x
+    }
+    pub fn height(&self) -> f32 {
+        self.max_y - self.min_y
+    }
+    pub fn center(&self) -> Point {
+        (
+            (self.min_x + self.max_x) / 2_f32,
+            (self.min_y + self.max_y) / 2_f32,
+        )
+    }
+    /// grow to contain `point`
+    pub fn include(&mut self, (x, y): Point) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+    pub fn union(&self, other: &BBox) -> BBox {
+        BBox::new(
+            self.min_x.min(other.min_x),
+            self.min_y.min(other.min_y),
+            self.max_x.max(other.max_x),
+            self.max_y.max(other.max_y),
+        )
+    }
+    /// grow by `d` on every side
+    pub fn expand(&self, d: f32) -> BBox {
+        BBox::new(self.min_x - d, self.min_y - d, self.max_x + d, self.max_y + d)
+    }
+    /// the box around the transformed corners
+    pub fn transform(&self, matrix: &Matrix) -> BBox {
+        let mut bbox = BBox::from_point(matrix.apply(self.min_x, self.min_y));
+        bbox.include(matrix.apply(self.max_x, self.min_y));
+        bbox.include(matrix.apply(self.min_x, self.max_y));
+        bbox.include(matrix.apply(self.max_x, self.max_y));
+        bbox
+    }
+}
+
+/// extend an optional box with `point`
+fn include(bbox: &mut Option<BBox>, point: Point) {
+    match bbox {
+        Some(bbox) => bbox.include(point),
+        None => *bbox = Some(BBox::from_point(point)),
+    }
+}
+
+/// roots of `a t² + b t + c` strictly inside `0..1`
+fn roots(a: f32, b: f32, c: f32, roots: &mut Vec<f32>) {
+    let candidates = if a.abs() < 1e-9 {
+        if b.abs() < 1e-9 {
+            return;
+        }
+        [-c / b, f32::NAN]
+    } else {
+        let discriminant = b * b - 4_f32 * a * c;
+        if discriminant < 0_f32 {
+            return;
+        }
+        let sqrt = discriminant.sqrt();
+        [(-b + sqrt) / (2_f32 * a), (-b - sqrt) / (2_f32 * a)]
+    };
+    roots.extend(candidates.into_iter().filter(|t| *t > 0_f32 && *t < 1_f32));
+}
+
+impl Segment {
+    /// the parameters in `0..1` where the segment is the furthest on one axis
+    /// (where its derivative is vertical or horizontal)
+    pub fn extrema(&self) -> Vec<f32> {
+        let mut result = vec![];
+        match *self {
+            Segment::Move { .. } | Segment::Line { .. } | Segment::Close { .. } => {}
+            Segment::Quadratic { from, control, to } => {
+                for (p0, p1, p2) in [(from.0, control.0, to.0), (from.1, control.1, to.1)] {
+                    roots(0_f32, p0 - 2_f32 * p1 + p2, p1 - p0, &mut result);
+                }
+            }
+            Segment::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                for (p0, p1, p2, p3) in [
+                    (from.0, control1.0, control2.0, to.0),
+                    (from.1, control1.1, control2.1, to.1),
+                ] {
+                    roots(
+                        -p0 + 3_f32 * p1 - 3_f32 * p2 + p3,
+                        2_f32 * (p0 - 2_f32 * p1 + p2),
+                        p1 - p0,
+                        &mut result,
+                    );
+                }
+            }
+            Segment::Arc { arc, .. } => {
+                let (sin, cos) = arc.angle.sin_cos();
+                let x = (-arc.ry * sin).atan2(arc.rx * cos);
+                let y = (arc.ry * cos).atan2(arc.rx * sin);
+                for theta in [x, x + std::f32::consts::PI, y, y + std::f32::consts::PI] {
+                    // the position of `theta` in the swept range, whichever turn it is on
+                    let turn = std::f32::consts::TAU * arc.sweep.signum();
+                    let t = ((theta - arc.start) / turn).rem_euclid(1_f32) * turn / arc.sweep;
+                    if t > 0_f32 && t < 1_f32 {
+                        result.push(t);
+                    }
+                }
+            }
+        }
+        result
+    }
+    /// the exact box around the segment, `None` for a moveto
+    pub fn bbox(&self) -> Option<BBox> {
+        if let Segment::Move { .. } = self {
+            return None;
+        }
+        let mut bbox = BBox::from_point(self.from());
+        bbox.include(self.to());
+        for t in self.extrema() {
+            bbox.include(self.point(t));
+        }
+        Some(bbox)
+    }
+}
+
+/// The stroke properties which change the extent of a path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    pub width: f32,
+    pub linecap: LineCap,
+    pub linejoin: LineJoin,
+    pub miterlimit: f32,
+}
+
+impl Default for Stroke {
+    /// the initial values of svg
+    fn default() -> Self {
+        Stroke {
+            width: 1_f32,
+            linecap: LineCap::Butt,
+            linejoin: LineJoin::Miter,
+            miterlimit: 4_f32,
+        }
+    }
+}
+
+impl Stroke {
+    /// the points on both sides of the stroke at `point`, across `tangent`
+    fn sides(&self, bbox: &mut Option<BBox>, (x, y): Point, (tx, ty): Point) {
+        let r = self.width / 2_f32;
+        include(bbox, (x - ty * r, y + tx * r));
+        include(bbox, (x + ty * r, y - tx * r));
+    }
+    /// the square around `point` which contains a round cap or join
+    fn round(&self, bbox: &mut Option<BBox>, (x, y): Point) {
+        let r = self.width / 2_f32;
+        include(bbox, (x - r, y - r));
+        include(bbox, (x + r, y + r));
+    }
+    /// a cap at the end of an open subpath, `tangent` points out of the path
+    fn cap(&self, bbox: &mut Option<BBox>, point: Point, tangent: Point) {
+        let r = self.width / 2_f32;
+        match self.linecap {
+            LineCap::Butt => {}
+            LineCap::Round => self.round(bbox, point),
+            LineCap::Square => {
+                self.sides(bbox, (point.0 + tangent.0 * r, point.1 + tangent.1 * r), tangent)
+            }
+        }
+    }
+    /// the join at `point` from the direction `before` into the direction `after`
+    fn join(&self, bbox: &mut Option<BBox>, point: Point, before: Point, after: Point) {
+        let r = self.width / 2_f32;
+        let dot = before.0 * after.0 + before.1 * after.1;
+        match self.linejoin {
+            LineJoin::Bevel => {}
+            LineJoin::Round => self.round(bbox, point),
+            LineJoin::Miter | LineJoin::MiterClip | LineJoin::Arcs => {
+                // the ratio of the miter length to the stroke width is 1 / sin(θ / 2),
+                // θ being the angle between both segments
+                let sin = ((1_f32 + dot) / 2_f32).max(0_f32).sqrt();
+                let (dx, dy) = (before.0 - after.0, before.1 - after.1);
+                let length = dx.hypot(dy);
+                if length < 1e-6 {
+                    // no turn, no miter
+                    return;
+                }
+                let tip = if sin > 0_f32 && 1_f32 / sin <= self.miterlimit {
+                    r / sin
+                } else if self.linejoin == LineJoin::Miter {
+                    // a bevel
+                    return;
+                } else {
+                    // clipped at half of the miter limit times the stroke width
+                    r * self.miterlimit
+                };
+                include(bbox, (point.0 + dx / length * tip, point.1 + dy / length * tip));
+            }
+        }
+    }
+}
+
+impl PathData {
+    /// the exact box around the filled area of the path, `None` if it draws nothing
+    pub fn bbox(&self) -> Option<BBox> {
+        self.segments()
+            .filter_map(|segment| segment.bbox())
+            .reduce(|a, b| a.union(&b))
+    }
+    /// the box around the stroke of the path, with its caps and joins
+    pub fn stroke_bbox(&self, stroke: &Stroke) -> Option<BBox> {
+        let mut bbox = None;
+        let mut subpath: Vec<Segment> = vec![];
+        let mut segments = self.segments().peekable();
+        while let Some(segment) = segments.next() {
+            if !matches!(segment, Segment::Move { .. }) {
+                subpath.push(segment);
+            }
+            let closed = matches!(segment, Segment::Close { .. });
+            let end = closed || matches!(segments.peek(), None | Some(Segment::Move { .. }));
+            if end && !subpath.is_empty() {
+                stroke_subpath(&mut bbox, &subpath, closed, stroke);
+                subpath.clear();
+            }
+        }
+        bbox
+    }
+}
+
+fn stroke_subpath(bbox: &mut Option<BBox>, subpath: &[Segment], closed: bool, stroke: &Stroke) {
+    // the segments with a direction, a zero length segment is only drawn as a dot by caps
+    let drawn: Vec<(Segment, Point, Point)> = subpath
+        .iter()
+        .filter_map(|s| Some((*s, s.tangent(false)?, s.tangent(true)?)))
+        .collect();
+    let (Some(first), Some(last)) = (drawn.first(), drawn.last()) else {
+        if !closed && stroke.linecap != LineCap::Butt {
+            stroke.round(bbox, subpath[0].from());
+        }
+        return;
+    };
+    for (segment, start, end) in &drawn {
+        stroke.sides(bbox, segment.from(), *start);
+        stroke.sides(bbox, segment.to(), *end);
+        for t in segment.extrema() {
+            stroke.sides(bbox, segment.point(t), segment.tangent_at(t).unwrap_or(*start));
+        }
+    }
+    for pair in drawn.windows(2) {
+        stroke.join(bbox, pair[0].0.to(), pair[0].2, pair[1].1);
+    }
+    if closed {
+        stroke.join(bbox, first.0.from(), last.2, first.1);
+    } else {
+        stroke.cap(bbox, first.0.from(), (-first.1 .0, -first.1 .1));
+        stroke.cap(bbox, last.0.to(), last.2);
+    }
+}
+
+/// what a child inherits from its ancestors, lengths in user units of the element
+#[derive(Debug, Clone)]
+pub(crate) struct Inherited {
+    pub(crate) matrix: Matrix,
+    /// the value of `currentColor`
+    pub(crate) color: Color,
+    pub(crate) fill: Paint,
+    pub(crate) fill_rule: FillRule,
+    pub(crate) fill_opacity: f32,
+    pub(crate) stroke_paint: Paint,
+    pub(crate) stroke_opacity: f32,
+    pub(crate) stroke: Stroke,
+    /// `stroke-dasharray` and `stroke-dashoffset`
+    pub(crate) dashes: Vec<f32>,
+    pub(crate) dash_offset: f32,
+    /// whether `marker-start`, `marker-mid` and `marker-end` draw a marker
+    pub(crate) markers: [bool; 3],
+    /// the first `url(#id)` which applies to the element or one of its ancestors
+    pub(crate) reference: Option<String>,
+}
+
+impl Default for Inherited {
+    fn default() -> Self {
+        Inherited {
+            matrix: Matrix::IDENTITY,
+            color: Color::BLACK,
+            fill: Paint::Color(Color::BLACK),
+            fill_rule: FillRule::NonZero,
+            fill_opacity: 1_f32,
+            stroke_paint: Paint::None,
+            stroke_opacity: 1_f32,
+            stroke: Stroke::default(),
+            dashes: vec![],
+            dash_offset: 0_f32,
+            markers: [false; 3],
+            reference: None,
+        }
+    }
+}
+
+/// the first value of `common` with a `url(#id)` reference
+fn reference(common: &CommonKVs) -> Option<String> {
+    let typed = common.attributes().into_iter().map(|(_, v)| v);
+    let others = common.others.iter().map(|(_, v)| v.clone());
+    typed.chain(others).find(|v| v.contains("url("))
+}
+
+impl Inherited {
+    /// the values of an element with `common`, invalid values are ignored
+    pub(crate) fn of(&self, common: &CommonKVs, context: &LengthContext) -> Inherited {
+        let mut inherited = self.clone();
+        if let Some(transform) = &common.transform {
+            inherited.matrix = self.matrix.multiply(&transform.matrix());
+        }
+        if let Some(color) = common.other("color").and_then(|c| c.parse().ok()) {
+            inherited.color = color;
+        }
+        if let Some(fill) = &common.fill {
+            inherited.fill = fill.clone();
+        }
+        if let Some(rule) = common.other("fill-rule").and_then(|r| r.parse().ok()) {
+            inherited.fill_rule = rule;
+        }
+        if let Some(Opacity(opacity)) = common.other("fill-opacity").and_then(|o| o.parse().ok()) {
+            inherited.fill_opacity = opacity;
+        }
+        if let Some(stroke) = &common.stroke {
+            inherited.stroke_paint = stroke.clone();
+        }
+        if let Some(opacity) = common.stroke_opacity {
+            inherited.stroke_opacity = opacity.0;
+        }
+        if let Some(width) = common.stroke_width {
+            inherited.stroke.width = width.resolve(Axis::Other, context);
+        }
+        if let Some(linecap) = common.stroke_linecap {
+            inherited.stroke.linecap = linecap;
+        }
+        if let Some(linejoin) = common.stroke_linejoin {
+            inherited.stroke.linejoin = linejoin;
+        }
+        if let Some(miterlimit) = common.stroke_miterlimit {
+            inherited.stroke.miterlimit = miterlimit.0;
+        }
+        if let Some(dashes) = &common.stroke_dasharray {
+            inherited.dashes = dashes
+                .0
+                .iter()
+                .map(|dash| dash.resolve(Axis::Other, context))
+                .collect();
+        }
+        if let Some(offset) = common.stroke_dashoffset {
+            inherited.dash_offset = offset.0.resolve(Axis::Other, context);
+        }
+        let marker = |value: &str| value.trim() != "none";
+        if let Some(value) = common.other("marker") {
+            inherited.markers = [marker(value); 3];
+        }
+        for (index, name) in ["marker-start", "marker-mid", "marker-end"].into_iter().enumerate() {
+            if let Some(value) = common.other(name) {
+                inherited.markers[index] = marker(value);
+            }
+        }
+        if inherited.reference.is_none() {
+            inherited.reference = reference(common);
+        }
+        inherited
+    }
+    /// whether the element has a stroke
+    pub(crate) fn stroked(&self) -> bool {
+        self.stroke_paint != Paint::None
+    }
+}
+
+fn children_bbox(
+    children: &[Child],
+    parent: &Inherited,
+    context: &LengthContext,
+    with_stroke: bool,
+) -> Option<BBox> {
+    let mut bbox: Option<BBox> = None;
+    for child in children {
+        let Some(common) = child.common() else {
+            // unknown elements (`<defs>`, `<title>`...) and text are not measured
+            continue;
+        };
+        let inherited = parent.of(common, context);
+        let child_bbox = match child {
+            Child::Group(group) => children_bbox(&group.children, &inherited, context, with_stroke),
+            child => child.to_path(context, Curves::Arcs).and_then(|path| {
+                let mut d = path.d?;
+                d.transform(&inherited.matrix);
+                match with_stroke && inherited.stroked() {
+                    true => {
+                        let stroke = Stroke {
+                            width: inherited.stroke.width * inherited.matrix.scale_factor(),
+                            ..inherited.stroke
+                        };
+                        d.stroke_bbox(&stroke)
+                    }
+                    false => d.bbox(),
+                }
+            }),
+        };
+        if let Some(child_bbox) = child_bbox {
+            bbox = Some(bbox.map_or(child_bbox, |bbox| bbox.union(&child_bbox)));
+        }
+    }
+    bbox
+}
+
+impl Child {
+    /// the box around the filled area of the element (and of its children) in its user space:
+    /// its own transform is not applied, the transforms of its children are
+    pub fn bbox(&self, context: &LengthContext) -> Option<BBox> {
+        match self {
+            Child::Group(group) => {
+                children_bbox(&group.children, &Inherited::default(), context, false)
+            }
+            child => child.to_path(context, Curves::Arcs)?.d?.bbox(),
+        }
+    }
+}
+
+impl Svg {
+    /// the box around the filled areas of all elements, transforms included,
+    /// in the coordinates of the `viewBox`
+    pub fn bbox(&self) -> Option<BBox> {
+        self.bbox_of(false)
+    }
+    /// the box around the filled areas and the strokes of all elements
+    pub fn stroke_bbox(&self) -> Option<BBox> {
+        self.bbox_of(true)
+    }
+    fn bbox_of(&self, with_stroke: bool) -> Option<BBox> {
+        let context = self.length_context();
+        let root = Inherited::default().of(&self.common, &context);
+        children_bbox(&self.children, &root, &context, with_stroke)
+    }
+}
+
+#[cfg(test)]
+mod test_bbox {
+    use super::*;
+
+    fn path(s: &str) -> PathData {
+        let (rest, d) = PathData::from_str(s).unwrap();
+        assert_eq!(rest, "");
+        d
+    }
+
+    fn close(bbox: BBox, expected: (f32, f32, f32, f32)) -> bool {
+        let BBox {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        } = bbox;
+        [min_x - expected.0, min_y - expected.1, max_x - expected.2, max_y - expected.3]
+            .iter()
+            .all(|d| d.abs() < 1e-3)
+    }
+
+    #[test]
+    fn test_path_bbox() {
+        assert_eq!(path("M1 2 h3 v4").bbox(), Some(BBox::new(1.0, 2.0, 4.0, 6.0)));
+        assert_eq!(path("M1 2").bbox(), None);
+        // the extrema of curves, not their control points
+        let bbox = path("M0 0 C0 4 4 4 4 0").bbox().unwrap();
+        assert!(close(bbox, (0.0, 0.0, 4.0, 3.0)));
+        let bbox = path("M0 0 Q2 4 4 0 T8 0").bbox().unwrap();
+        assert!(close(bbox, (0.0, -2.0, 8.0, 2.0)));
+        // half of a circle, and a small arc of a rotated ellipse
+        let bbox = path("M0 0 A1 1 0 0 1 2 0").bbox().unwrap();
+        assert!(close(bbox, (0.0, -1.0, 2.0, 0.0)));
+        let bbox = path("M0 0 A2 1 90 0 1 0 4").bbox().unwrap();
+        assert!(close(bbox, (0.0, 0.0, 1.0, 4.0)));
+        let bbox = path("M1 0 A1 1 0 0 1 0 1").bbox().unwrap();
+        assert!(close(bbox, (0.0, 0.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_stroke_bbox() {
+        let stroke = Stroke {
+            width: 2.0,
+            ..Default::default()
+        };
+        let d = path("M0 0 H10");
+        assert_eq!(d.stroke_bbox(&stroke), Some(BBox::new(0.0, -1.0, 10.0, 1.0)));
+        let square = Stroke {
+            linecap: LineCap::Square,
+            ..stroke
+        };
+        assert_eq!(d.stroke_bbox(&square), Some(BBox::new(-1.0, -1.0, 11.0, 1.0)));
+        // a dot is drawn by round and square caps only
+        assert_eq!(path("M5 5 z").stroke_bbox(&stroke), None);
+        let round = Stroke {
+            linecap: LineCap::Round,
+            ..stroke
+        };
+        assert_eq!(path("M5 5 h0").stroke_bbox(&round), Some(BBox::new(4.0, 4.0, 6.0, 6.0)));
+
+        // the miter of a right angle is sqrt(2) times half of the width away from the corner
+        let corner = path("M0 0 H10 V10");
+        let bbox = corner.stroke_bbox(&stroke).unwrap();
+        assert!(close(bbox, (0.0, -1.0, 11.0, 10.0)));
+        // a sharp turn is beveled over the miter limit
+        let sharp = path("M0 0 L10 1 L0 2");
+        let bevel = sharp.stroke_bbox(&stroke).unwrap();
+        assert!(bevel.max_x < 11.0);
+        let miter = Stroke {
+            miterlimit: 100.0,
+            ..stroke
+        };
+        assert!(sharp.stroke_bbox(&miter).unwrap().max_x > 19.0);
+        // closed paths have no caps, but a join at the start
+        let triangle = path("M0 0 H10 V10 Z");
+        let bbox = triangle.stroke_bbox(&square).unwrap();
+        assert!(bbox.min_x < -2.0 && bbox.max_y > 12.0);
+    }
+
+    #[test]
+    fn test_svg_bbox() {
+        let svg: Svg = r#"<svg viewBox="0 0 24 24" stroke="black" stroke-width="2"><g transform="translate(10 10)"><rect width="4" height="2" transform="scale(2)"/><circle r="1" stroke="none"/></g><line x1="1" y1="1" x2="3" y2="1" stroke-linecap="round"/><defs><rect width="100" height="100"/></defs></svg>"#
+            .parse()
+            .unwrap();
+        assert_eq!(svg.bbox(), Some(BBox::new(1.0, 1.0, 18.0, 14.0)));
+        // the stroke is scaled with the rect
+        assert_eq!(svg.stroke_bbox(), Some(BBox::new(0.0, 0.0, 20.0, 16.0)));
+        let Child::Group(group) = &svg.children[0] else {
+            panic!("expect a group");
+        };
+        assert_eq!(
+            svg.children[0].bbox(&svg.length_context()),
+            Some(BBox::new(-1.0, -1.0, 8.0, 4.0))
+        );
+        assert_eq!(group.children[0].bbox(&svg.length_context()), Some(BBox::new(0.0, 0.0, 4.0, 2.0)));
+        assert_eq!(Svg::default().bbox(), None);
+    }
+}
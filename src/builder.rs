@@ -0,0 +1,429 @@
+//! Build icons in code, with typed values instead of attribute strings.
+//!
+//! ## Example
+//! ```rust
+//! use svg_icon::builder::PathBuilder;
+//! use svg_icon::children::{Circle, Path};
+//! use svg_icon::paint::Color;
+//! use svg_icon::Svg;
+//!
+//! let svg = Svg::builder()
+//!     .view_box(0., 0., 24., 24.)
+//!     .child(Circle::new(12., 12., 10.).stroke(Color::BLACK).stroke_width(2.))
+//!     .child(Path::new(PathBuilder::new().move_to(12., 6.).line_to(12., 12.)).id("hand"))
+//!     .build();
+//! assert_eq!(
+//!     svg.to_string(),
+//!     r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><circle cx="12" cy="12" r="10" stroke="#000000" stroke-width="2"/><path d="M 12,6 L 12,12" id="hand"/></svg>"##
+//! );
+//! ```
+
+use crate::children::{
+    Child, Circle, Command, CommonKVs, DashArray, DashOffset, Ellipse, Group, Line, LineCap, LineJoin, MiterLimit,
+    Opacity, Path, PathData, Points, Polygon, Polyline, Rect, A, C, L, M, Q, Z,
+};
+use crate::length::Length;
+use crate::paint::Paint;
+use crate::transform::Transform;
+use crate::value::Auto;
+use crate::view_box::{PreserveAspectRatio, ViewBox};
+use crate::Svg;
+
+/// the namespace of svg elements, written on the `<svg>` tag
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// set an attribute which has no typed field
+fn set_other(common: &mut CommonKVs, name: &str, value: String) {
+    match common.others.iter_mut().find(|(k, _)| k == name) {
+        Some((_, v)) => *v = value,
+        None => common.others.push((name.to_string(), value)),
+    }
+}
+
+/// chained setters of the presentation attributes, for every element with a `CommonKVs`
+macro_rules! impl_presentation {
+    ($($t:ty => $($common:ident).+),*) => {
+        $(
+            impl $t {
+                pub fn fill(mut self, paint: impl Into<Paint>) -> Self {
+                    self.$($common).+.fill = Some(paint.into());
+                    self
+                }
+                pub fn stroke(mut self, paint: impl Into<Paint>) -> Self {
+                    self.$($common).+.stroke = Some(paint.into());
+                    self
+                }
+                pub fn stroke_width(mut self, width: impl Into<Length>) -> Self {
+                    self.$($common).+.stroke_width = Some(width.into());
+                    self
+                }
+                pub fn stroke_linecap(mut self, cap: LineCap) -> Self {
+                    self.$($common).+.stroke_linecap = Some(cap);
+                    self
+                }
+                pub fn stroke_linejoin(mut self, join: LineJoin) -> Self {
+                    self.$($common).+.stroke_linejoin = Some(join);
+                    self
+                }
+                pub fn stroke_dasharray(mut self, dashes: impl IntoIterator<Item = f32>) -> Self {
+                    self.$($common).+.stroke_dasharray = Some(DashArray(dashes.into_iter().map(Length::from).collect()));
+                    self
+                }
+                pub fn stroke_dashoffset(mut self, offset: impl Into<Length>) -> Self {
+                    self.$($common).+.stroke_dashoffset = Some(DashOffset(offset.into()));
+                    self
+                }
+                /// clamped to `0..=1`
+                pub fn stroke_opacity(mut self, opacity: f32) -> Self {
+                    self.$($common).+.stroke_opacity = Some(Opacity(opacity.clamp(0_f32, 1_f32)));
+                    self
+                }
+                pub fn stroke_miterlimit(mut self, limit: f32) -> Self {
+                    self.$($common).+.stroke_miterlimit = Some(MiterLimit(limit));
+                    self
+                }
+                pub fn transform(mut self, transform: impl Into<Transform>) -> Self {
+                    self.$($common).+.transform = Some(transform.into());
+                    self
+                }
+                /// clamped to `0..=1`
+                pub fn opacity(mut self, opacity: f32) -> Self {
+                    set_other(&mut self.$($common).+, "opacity", opacity.clamp(0_f32, 1_f32).to_string());
+                    self
+                }
+                /// clamped to `0..=1`
+                pub fn fill_opacity(mut self, opacity: f32) -> Self {
+                    set_other(&mut self.$($common).+, "fill-opacity", opacity.clamp(0_f32, 1_f32).to_string());
+                    self
+                }
+                pub fn id(mut self, id: &str) -> Self {
+                    set_other(&mut self.$($common).+, "id", id.to_string());
+                    self
+                }
+                pub fn class(mut self, class: &str) -> Self {
+                    set_other(&mut self.$($common).+, "class", class.to_string());
+                    self
+                }
+                /// an attribute without a typed setter: `fill-rule`, `data-*`...
+                pub fn attr(mut self, name: &str, value: impl ToString) -> Self {
+                    set_other(&mut self.$($common).+, name, value.to_string());
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_presentation!(
+    SvgBuilder => svg.common,
+    Circle => common,
+    Ellipse => common,
+    Group => common,
+    Line => common,
+    Path => common,
+    Polygon => common,
+    Polyline => common,
+    Rect => common
+);
+
+macro_rules! impl_into_child {
+    ($($t:ident),*) => {
+        $(
+            impl From<$t> for Child {
+                fn from(v: $t) -> Self {
+                    Child::$t(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_child!(Circle, Ellipse, Group, Line, Path, Polygon, Polyline, Rect);
+
+/// A `<svg>` document built with chained calls, see [`Svg::builder`].
+#[derive(Debug, Clone)]
+pub struct SvgBuilder {
+    svg: Svg,
+}
+
+impl Svg {
+    /// an empty document in the svg namespace
+    pub fn builder() -> SvgBuilder {
+        SvgBuilder {
+            svg: Svg {
+                xmlns: SVG_NAMESPACE.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl SvgBuilder {
+    pub fn view_box(mut self, min_x: f32, min_y: f32, width: f32, height: f32) -> Self {
+        self.svg.view_box = Some(ViewBox::new(min_x, min_y, width, height));
+        self
+    }
+    pub fn preserve_aspect_ratio(mut self, aspect: PreserveAspectRatio) -> Self {
+        self.svg.preserve_aspect_ratio = Some(aspect);
+        self
+    }
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.svg.width = Auto(Some(width.into()));
+        self
+    }
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.svg.height = Auto(Some(height.into()));
+        self
+    }
+    pub fn size(self, width: impl Into<Length>, height: impl Into<Length>) -> Self {
+        self.width(width).height(height)
+    }
+    pub fn child(mut self, child: impl Into<Child>) -> Self {
+        self.svg.children.push(child.into());
+        self
+    }
+    pub fn children<C: Into<Child>>(mut self, children: impl IntoIterator<Item = C>) -> Self {
+        self.svg.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+    pub fn build(self) -> Svg {
+        self.svg
+    }
+}
+
+impl Circle {
+    pub fn new(cx: impl Into<Length>, cy: impl Into<Length>, r: impl Into<Length>) -> Self {
+        Circle {
+            cx: Some(cx.into()),
+            cy: Some(cy.into()),
+            r: Some(r.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Ellipse {
+    pub fn new(cx: impl Into<Length>, cy: impl Into<Length>, rx: impl Into<Length>, ry: impl Into<Length>) -> Self {
+        Ellipse {
+            cx: Some(cx.into()),
+            cy: Some(cy.into()),
+            rx: Auto(Some(rx.into())),
+            ry: Auto(Some(ry.into())),
+            ..Default::default()
+        }
+    }
+}
+
+impl Rect {
+    pub fn new(
+        x: impl Into<Length>,
+        y: impl Into<Length>,
+        width: impl Into<Length>,
+        height: impl Into<Length>,
+    ) -> Self {
+        Rect {
+            x: Some(x.into()),
+            y: Some(y.into()),
+            width: Some(width.into()),
+            height: Some(height.into()),
+            ..Default::default()
+        }
+    }
+    /// round the corners
+    pub fn radius(mut self, rx: impl Into<Length>, ry: impl Into<Length>) -> Self {
+        self.rx = Some(rx.into());
+        self.ry = Some(ry.into());
+        self
+    }
+}
+
+impl Line {
+    pub fn new(x1: impl Into<Length>, y1: impl Into<Length>, x2: impl Into<Length>, y2: impl Into<Length>) -> Self {
+        Line {
+            x1: Some(x1.into()),
+            y1: Some(y1.into()),
+            x2: Some(x2.into()),
+            y2: Some(y2.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Polygon {
+    pub fn new(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        Polygon {
+            points: Some(Points(points.into_iter().collect())),
+            ..Default::default()
+        }
+    }
+}
+
+impl Polyline {
+    pub fn new(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        Polyline {
+            points: Some(Points(points.into_iter().collect())),
+            ..Default::default()
+        }
+    }
+}
+
+impl Path {
+    /// a path with the data `d`, a [`PathData`] or a [`PathBuilder`]
+    pub fn new(d: impl Into<PathData>) -> Self {
+        Path {
+            d: Some(d.into()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Group::default()
+    }
+    pub fn child(mut self, child: impl Into<Child>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+    pub fn children<C: Into<Child>>(mut self, children: impl IntoIterator<Item = C>) -> Self {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// Path data built with chained calls, in absolute coordinates.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::builder::PathBuilder;
+///
+/// let d = PathBuilder::new()
+///     .move_to(2., 12.)
+///     .cubic_to(2., 6., 22., 6., 22., 12.)
+///     .arc_to(10., 10., 0., false, true, 2., 12.)
+///     .close()
+///     .build();
+/// assert_eq!(d.to_string(), "M 2,12 C 2,6 22,6 22,12 A 10 10 0 0 1 2,12 Z");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathBuilder {
+    d: PathData,
+}
+
+impl PathBuilder {
+    pub fn new() -> Self {
+        PathBuilder::default()
+    }
+    /// a path starts with a moveto, one to the origin is added before another first command
+    fn push(mut self, command: impl Into<Command>) -> Self {
+        let command = command.into();
+        if self.d.is_empty() && !matches!(command, Command::MoveTo(_)) {
+            self.d.push(M {
+                x: 0_f32,
+                y: 0_f32,
+                relative: false,
+            });
+        }
+        self.d.push(command);
+        self
+    }
+    pub fn move_to(self, x: f32, y: f32) -> Self {
+        self.push(M { x, y, relative: false })
+    }
+    pub fn line_to(self, x: f32, y: f32) -> Self {
+        self.push(L { x, y, relative: false })
+    }
+    /// a cubic bezier curve with the control points `x1,y1` and `x2,y2`
+    pub fn cubic_to(self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) -> Self {
+        self.push(C {
+            x1,
+            y1,
+            x2,
+            y2,
+            x,
+            y,
+            relative: false,
+        })
+    }
+    /// a quadratic bezier curve with the control point `x1,y1`
+    pub fn quad_to(self, x1: f32, y1: f32, x: f32, y: f32) -> Self {
+        self.push(Q {
+            x1,
+            y1,
+            x,
+            y,
+            relative: false,
+        })
+    }
+    /// an elliptical arc, see [`A`] for the meaning of the arguments
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc_to(self, rx: f32, ry: f32, angle: f32, large_arc: bool, sweep: bool, x: f32, y: f32) -> Self {
+        self.push(A {
+            rx,
+            ry,
+            angle,
+            large_arc_flag: large_arc,
+            sweep_flag: sweep,
+            x,
+            y,
+            relative: false,
+        })
+    }
+    pub fn close(self) -> Self {
+        self.push(Z(false))
+    }
+    pub fn build(self) -> PathData {
+        self.d
+    }
+}
+
+impl From<PathBuilder> for PathData {
+    fn from(builder: PathBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod test_builder {
+    use super::*;
+    use crate::paint::Color;
+    use crate::transform::TransformFunction;
+
+    #[test]
+    fn test_svg_builder() {
+        let svg = Svg::builder()
+            .size(48., 48.)
+            .view_box(0., 0., 24., 24.)
+            .fill(Paint::None)
+            .stroke(Paint::CurrentColor)
+            .child(Circle::new(12., 12., 10.).stroke(Color::rgb(255, 0, 0)).stroke_width(2.))
+            .child(
+                Group::new()
+                    .transform(TransformFunction::Rotate { angle: 45., cx: 12., cy: 12. })
+                    .opacity(1.5)
+                    .children([Rect::new(4., 4., 16., 16.).radius(2., 2.)]),
+            )
+            .children([
+                Child::from(Line::new(0., 0., 24., 24.).stroke_linecap(LineCap::Round).stroke_dasharray([2., 1.])),
+                Polygon::new([(0., 0.), (1., 0.), (1., 1.)]).attr("fill-rule", "evenodd").into(),
+                Polyline::new([(0., 0.), (1., 1.)]).class("a").into(),
+                Ellipse::new(12., 12., 4., 2.).fill_opacity(0.5).into(),
+            ])
+            .build();
+        assert_eq!(
+            svg.to_string(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" height="48" width="48" fill="none" stroke="currentColor"><circle cx="12" cy="12" r="10" stroke="#ff0000" stroke-width="2"/><g transform="rotate(45 12 12)" opacity="1"><rect x="4" y="4" width="16" height="16" rx="2" ry="2"/></g><line x1="0" y1="0" x2="24" y2="24" stroke-linecap="round" stroke-dasharray="2 1"/><polygon points="0,0 1,0 1,1" fill-rule="evenodd"/><polyline points="0,0 1,1" class="a"/><ellipse cx="12" cy="12" rx="4" ry="2" fill-opacity="0.5"/></svg>"##
+        );
+        // what is built can be parsed back
+        let parsed: Svg = svg.to_string().parse().unwrap();
+        assert_eq!(parsed.to_string(), svg.to_string());
+    }
+
+    #[test]
+    fn test_path_builder() {
+        let d = PathBuilder::new().line_to(1., 1.).quad_to(2., 0., 3., 1.).close().build();
+        assert_eq!(d.to_string(), "M 0,0 L 1,1 Q 2,0 3,1 Z");
+        let path = Path::new(PathBuilder::new().move_to(1., 2.)).id("a").id("b");
+        assert_eq!(path.to_string(), r#"<path d="M 1,2" id="b"/>"#);
+    }
+}
@@ -1,30 +1,35 @@
 use std::fmt::Display;
 
-use nom::IResult;
+use crate::error::{attribute_in, IResult};
+use crate::length::{resolve, Axis, Length, LengthContext};
 
-use super::CommonKVs;
+use super::{Curves, CommonKVs, Path, PathData};
 
 /// The <circle> SVG element is an SVG basic shape,
 /// used to draw circles based on a center point and a radius.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Circle {
     pub common: CommonKVs,
     /// The x-axis coordinate of the center of the circle.
     /// Value type: <length>|<percentage> ; Default value: 0;
-    pub cx: Option<f32>,
+    pub cx: Option<Length>,
     /// The y-axis coordinate of the center of the circle.
     /// Value type: <length>|<percentage> ; Default value: 0;
-    pub cy: Option<f32>,
+    pub cy: Option<Length>,
     /// The radius of the circle. A value lower or equal to zero disables rendering of the circle. Value type: <length>|<percentage> ; Default value: 0;
-    pub r: Option<f32>,
+    pub r: Option<Length>,
 }
 
 impl Circle {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, Circle> {
-        let (s, (common, kvs)) = CommonKVs::from_str(s.trim()).unwrap();
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s.trim())?;
+        common.remove_others(&["cx", "cy", "r"]);
 
-        if kvs.is_none() {
-            Ok((
+        match kvs {
+            None => Ok((
                 s,
                 Circle {
                     common,
@@ -32,29 +37,111 @@ impl Circle {
                     cy: None,
                     r: None,
                 },
-            ))
-        } else {
-            Ok((
+            )),
+            Some(kvs) => Ok((
                 s,
                 Circle {
                     common,
-                    cx: kvs.as_ref().unwrap().get("cx").map(|v| v.parse().unwrap()),
-                    cy: kvs.as_ref().unwrap().get("cy").map(|v| v.parse().unwrap()),
-                    r: kvs.as_ref().unwrap().get("r").map(|v| v.parse().unwrap()),
+                    cx: attribute_in(&kvs, "cx")?,
+                    cy: attribute_in(&kvs, "cy")?,
+                    r: attribute_in(&kvs, "r")?,
                 },
-            ))
+            )),
         }
     }
 }
 
+impl Circle {
+    /// the equivalent `<path>`, four quarter arcs clockwise from the right-most point
+    pub fn to_path(&self, context: &LengthContext, curves: Curves) -> Path {
+        Path {
+            common: self.common.clone(),
+            d: Some(self.path_data(context, curves)),
+        }
+    }
+    /// four quarter arcs clockwise from the right-most point, empty if `r <= 0`
+    pub(crate) fn path_data(&self, context: &LengthContext, curves: Curves) -> PathData {
+        let cx = resolve(self.cx, Axis::X, context);
+        let cy = resolve(self.cy, Axis::Y, context);
+        let r = resolve(self.r, Axis::Other, context);
+        ellipse_data(cx, cy, r, r, curves)
+    }
+}
+
+/// four quarter arcs clockwise from the right-most point, empty if a radius is not positive
+pub(crate) fn ellipse_data(cx: f32, cy: f32, rx: f32, ry: f32, curves: Curves) -> PathData {
+    use super::{M, Z};
+
+    let mut d = PathData::new();
+    if rx <= 0_f32 || ry <= 0_f32 {
+        return d;
+    }
+    let mut from = (cx + rx, cy);
+    d.push(M {
+        x: from.0,
+        y: from.1,
+        relative: false,
+    });
+    for to in [(cx, cy + ry), (cx - rx, cy), (cx, cy - ry), (cx + rx, cy)] {
+        quarter(&mut d, curves, (cx, cy), from, to, (rx, ry));
+        from = to;
+    }
+    d.push(Z(false));
+    d
+}
+
+/// the magic number of the cubic bezier approximating a quarter of a circle
+const KAPPA: f32 = 0.552_284_8;
+
+/// a clockwise quarter of the axis aligned ellipse around `center`, from `from` to `to`
+pub(crate) fn quarter(
+    d: &mut PathData,
+    curves: Curves,
+    center: (f32, f32),
+    from: (f32, f32),
+    to: (f32, f32),
+    (rx, ry): (f32, f32),
+) {
+    use super::{A, C};
+
+    match curves {
+        Curves::Arcs => d.push(A {
+            rx,
+            ry,
+            angle: 0_f32,
+            large_arc_flag: false,
+            sweep_flag: true,
+            x: to.0,
+            y: to.1,
+            relative: false,
+        }),
+        // the tangent at one end is parallel to the radius of the other end
+        Curves::Cubics => d.push(C {
+            x1: from.0 + KAPPA * (to.0 - center.0),
+            y1: from.1 + KAPPA * (to.1 - center.1),
+            x2: to.0 + KAPPA * (from.0 - center.0),
+            y2: to.1 + KAPPA * (from.1 - center.1),
+            x: to.0,
+            y: to.1,
+            relative: false,
+        }),
+    }
+}
+
+impl Circle {
+    /// the attributes of the circle which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        [("cx", self.cx), ("cy", self.cy), ("r", self.r)]
+            .into_iter()
+            .filter_map(|(k, v)| v.map(|v| (k, v.to_string())))
+            .collect()
+    }
+}
+
 impl Display for Circle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" />",
-            self.cx.as_ref().unwrap_or(&0_f32),
-            self.cy.as_ref().unwrap_or(&0_f32),
-            self.r.as_ref().unwrap_or(&0_f32)
-        )
+        write!(f, "<circle")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, "/>")
     }
 }
@@ -4,150 +4,220 @@ mod line_cap;
 mod line_join;
 mod miter_limit;
 mod opacity;
+mod points;
 
-use std::{collections::HashMap, fmt::Display};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
 
 pub use dash_array::DashArray;
 pub use dash_offset::DashOffset;
 pub use line_cap::LineCap;
 pub use line_join::LineJoin;
 pub use miter_limit::MiterLimit;
-use nom::IResult;
 pub use opacity::Opacity;
+pub use points::Points;
 
+use crate::error::{attribute, into_error, Error, IResult, ParseError};
+use crate::length::Length;
+use crate::paint::Paint;
+use crate::transform::Transform;
 use crate::parser::parse_properties;
 
+/// `inherit`, `initial`... which every css property accepts.
+const CSS_WIDE_KEYWORDS: [&str; 5] = ["inherit", "initial", "unset", "revert", "revert-layer"];
+
+/// Attributes which are not handled by `CommonKVs`, left for the element itself.
+pub type OtherKVs<'a> = Option<HashMap<&'a str, &'a str>>;
+
 /// Common key-value pairs for SVG elements.
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct CommonKVs {
-    pub x: Option<f32>,
-    pub y: Option<f32>,
-    pub fill: Option<String>,
-    pub stroke: Option<String>,
-    pub stroke_width: Option<f32>,
+    pub x: Option<Length>,
+    pub y: Option<Length>,
+    pub fill: Option<Paint>,
+    pub stroke: Option<Paint>,
+    pub stroke_width: Option<Length>,
     pub stroke_linecap: Option<LineCap>,
     pub stroke_linejoin: Option<LineJoin>,
     pub stroke_dasharray: Option<DashArray>,
     pub stroke_dashoffset: Option<DashOffset>,
     pub stroke_opacity: Option<Opacity>,
     pub stroke_miterlimit: Option<MiterLimit>,
+    pub transform: Option<Transform>,
+    /// attributes without a typed field (`id`, `class`, `fill-rule`, `data-*`...) in source order
+    pub others: Vec<(String, String)>,
+    /// names of all attributes of the element in source order, to write them back in the same order
+    pub order: Vec<String>,
 }
 
 impl CommonKVs {
-    pub fn from_str(s: &str) -> IResult<&str, (CommonKVs, Option<HashMap<&str, &str>>)> {
-        let (s, kvs) = parse_properties(s.trim()).unwrap();
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, (CommonKVs, OtherKVs<'_>)> {
+        let (s, kvs) = parse_properties(s.trim())?;
+        let mut common = CommonKVs::default();
         let mut others = None;
-        let mut fill = None;
-        let mut stroke = None;
-        let mut stroke_width = None;
-        let mut stroke_linecap = None;
-        let mut stroke_linejoin = None;
-        let mut stroke_dasharray = None;
-        let mut stroke_dashoffset = None;
-        let mut stroke_opacity = None;
-        let mut stroke_miterlimit = None;
-        let mut x = None;
-        let mut y = None;
 
         for (k, v) in kvs.into_iter() {
-            match k {
-                "x" => {
-                    let _ = x.replace(v.parse().unwrap());
-                }
-                "y" => {
-                    let _ = y.replace(v.parse().unwrap());
-                }
-                "fill" => {
-                    let _ = fill.replace(v.to_string());
-                }
-                "stroke" => {
-                    let _ = stroke.replace(v.to_string());
-                }
-                "stroke-width" => {
-                    let _ = stroke_width.replace(v.parse().unwrap());
-                }
-                "stroke-linecap" => {
-                    let _ = stroke_linecap.replace(v.parse().unwrap());
-                }
-                "stroke-linejoin" => {
-                    let _ = stroke_linejoin.replace(v.parse().unwrap());
-                }
-                "stroke-dasharray" => {
-                    let _ = stroke_dasharray.replace(v.parse().unwrap());
-                }
-                "stroke-dashoffset" => {
-                    let _ = stroke_dashoffset.replace(v.parse().unwrap());
-                }
-                "stroke-opacity" => {
-                    let _ = stroke_opacity.replace(v.parse().unwrap());
-                }
-                "stroke-miterlimit" => {
-                    let _ = stroke_miterlimit.replace(v.parse().unwrap());
-                }
-                _ => {
-                    others.get_or_insert_with(|| HashMap::new()).insert(k, v);
-                }
+            common.order.push(k.to_string());
+            if !common.set_typed(k, v)? {
+                common.others.push((k.to_string(), v.to_string()));
+                others.get_or_insert_with(HashMap::new).insert(k, v);
             }
         }
 
-        Ok((
-            s,
-            (
-                CommonKVs {
-                    x,
-                    y,
-                    fill,
-                    stroke,
-                    stroke_width,
-                    stroke_linecap,
-                    stroke_linejoin,
-                    stroke_dasharray,
-                    stroke_dashoffset,
-                    stroke_opacity,
-                    stroke_miterlimit,
-                },
-                others,
-            ),
-        ))
+        Ok((s, (common, others)))
     }
-}
-
-impl Display for CommonKVs {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        if let Some(x) = &self.x {
-            s.push_str(&format!("x=\"{}\" ", x));
-        }
-        if let Some(y) = &self.y {
-            s.push_str(&format!("y=\"{}\" ", y));
-        }
-        if let Some(fill) = &self.fill {
-            s.push_str(&format!("fill=\"{}\" ", fill));
+    /// set attribute `name`, a typed field if there is one or else `others`,
+    /// used to apply css declarations
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        match self.set_typed(name, value) {
+            Ok(true) => self.remove_others(&[name]),
+            Ok(false) => match self.others.iter_mut().find(|(k, _)| k == name) {
+                Some((_, v)) => *v = value.to_string(),
+                None => self.others.push((name.to_string(), value.to_string())),
+            },
+            Err(e) => return Err(into_error(value, e)),
         }
-        if let Some(stroke) = &self.stroke {
-            s.push_str(&format!("stroke=\"{}\" ", stroke));
-        }
-        if let Some(stroke_width) = &self.stroke_width {
-            s.push_str(&format!("stroke-width=\"{}\" ", stroke_width));
-        }
-        if let Some(stroke_linecap) = &self.stroke_linecap {
-            s.push_str(&format!("stroke-linecap=\"{}\" ", stroke_linecap));
-        }
-        if let Some(stroke_linejoin) = &self.stroke_linejoin {
-            s.push_str(&format!("stroke-linejoin=\"{}\" ", stroke_linejoin));
-        }
-        if let Some(stroke_dasharray) = &self.stroke_dasharray {
-            s.push_str(&format!("stroke-dasharray=\"{}\" ", stroke_dasharray));
+        Ok(())
+    }
+    /// set the typed field of attribute `k`, `false` if there is none
+    fn set_typed<'a>(&mut self, k: &str, v: &'a str) -> Result<bool, nom::Err<ParseError<&'a str>>> {
+        // valid for every property but not a value of the typed fields, kept as it is in `others`
+        if CSS_WIDE_KEYWORDS.iter().any(|keyword| v.trim().eq_ignore_ascii_case(keyword)) {
+            self.clear_typed(k);
+            return Ok(false);
         }
-        if let Some(stroke_dashoffset) = &self.stroke_dashoffset {
-            s.push_str(&format!("stroke-dashoffset=\"{}\" ", stroke_dashoffset));
+        match k {
+            "x" => self.x = Some(attribute(k, v)?),
+            "y" => self.y = Some(attribute(k, v)?),
+            "fill" => self.fill = Some(attribute(k, v)?),
+            "stroke" => self.stroke = Some(attribute(k, v)?),
+            "stroke-width" => self.stroke_width = Some(attribute(k, v)?),
+            "stroke-linecap" => self.stroke_linecap = Some(attribute(k, v)?),
+            "stroke-linejoin" => self.stroke_linejoin = Some(attribute(k, v)?),
+            "stroke-dasharray" => self.stroke_dasharray = Some(attribute(k, v)?),
+            "stroke-dashoffset" => self.stroke_dashoffset = Some(attribute(k, v)?),
+            "stroke-opacity" => self.stroke_opacity = Some(attribute(k, v)?),
+            "stroke-miterlimit" => self.stroke_miterlimit = Some(attribute(k, v)?),
+            "transform" => self.transform = Some(attribute(k, v)?),
+            _ => return Ok(false),
         }
-        if let Some(stroke_opacity) = &self.stroke_opacity {
-            s.push_str(&format!("stroke-opacity=\"{}\" ", stroke_opacity));
+        Ok(true)
+    }
+    /// value of an attribute in `others`
+    pub fn other(&self, name: &str) -> Option<&str> {
+        self.others
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl CommonKVs {
+    /// the element parsed these attributes into its own fields, remove them from `others`
+    pub fn remove_others(&mut self, names: &[&str]) {
+        self.others.retain(|(k, _)| !names.contains(&k.as_str()));
+    }
+    /// the value of attribute `name` as it is written, typed or not
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.attributes()
+            .into_iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+            .or_else(|| self.other(name).map(str::to_string))
+    }
+    /// remove attribute `name`, typed or not
+    pub fn remove(&mut self, name: &str) {
+        self.clear_typed(name);
+        self.remove_others(&[name]);
+        self.order.retain(|k| k != name);
+    }
+    /// unset the typed field of attribute `name`, if there is one
+    fn clear_typed(&mut self, name: &str) {
+        match name {
+            "x" => self.x = None,
+            "y" => self.y = None,
+            "fill" => self.fill = None,
+            "stroke" => self.stroke = None,
+            "stroke-width" => self.stroke_width = None,
+            "stroke-linecap" => self.stroke_linecap = None,
+            "stroke-linejoin" => self.stroke_linejoin = None,
+            "stroke-dasharray" => self.stroke_dasharray = None,
+            "stroke-dashoffset" => self.stroke_dashoffset = None,
+            "stroke-opacity" => self.stroke_opacity = None,
+            "stroke-miterlimit" => self.stroke_miterlimit = None,
+            "transform" => self.transform = None,
+            _ => {}
         }
-        if let Some(stroke_miterlimit) = &self.stroke_miterlimit {
-            s.push_str(&format!("stroke-miterlimit=\"{}\" ", stroke_miterlimit));
+    }
+    /// the typed attributes which are set, as `(name, value)`
+    pub fn attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attrs = vec![];
+        let mut push = |name: &'static str, value: Option<String>| {
+            if let Some(value) = value {
+                attrs.push((name, value));
+            }
+        };
+        push("x", self.x.map(|v| v.to_string()));
+        push("y", self.y.map(|v| v.to_string()));
+        push("fill", self.fill.as_ref().map(|v| v.to_string()));
+        push("stroke", self.stroke.as_ref().map(|v| v.to_string()));
+        push("stroke-width", self.stroke_width.map(|v| v.to_string()));
+        push("stroke-linecap", self.stroke_linecap.map(|v| v.to_string()));
+        push("stroke-linejoin", self.stroke_linejoin.map(|v| v.to_string()));
+        push(
+            "stroke-dasharray",
+            self.stroke_dasharray.as_ref().map(|v| v.to_string()),
+        );
+        push(
+            "stroke-dashoffset",
+            self.stroke_dashoffset.map(|v| v.to_string()),
+        );
+        push("stroke-opacity", self.stroke_opacity.map(|v| v.to_string()));
+        push(
+            "stroke-miterlimit",
+            self.stroke_miterlimit.map(|v| v.to_string()),
+        );
+        push("transform", self.transform.as_ref().map(|v| v.to_string()));
+        attrs
+    }
+    /// write ` name="value"` for the element's own attributes, the common ones and `others`,
+    /// attributes which were parsed keep their source order, new ones follow them
+    pub fn write_attributes(
+        &self,
+        f: &mut Formatter<'_>,
+        own: Vec<(&'static str, String)>,
+    ) -> FmtResult {
+        for (k, v) in self.ordered_attributes(own) {
+            write!(f, " {}=\"{}\"", k, v.replace('"', "&quot;"))?;
         }
-        write!(f, "{}", s)
+        Ok(())
+    }
+    /// the element's own attributes, the common ones and `others`, as [`Self::write_attributes`]
+    /// writes them
+    pub fn ordered_attributes(&self, own: Vec<(&'static str, String)>) -> Vec<(String, String)> {
+        let mut attrs = own
+            .into_iter()
+            .chain(self.attributes())
+            .map(|(k, v)| (k.to_string(), v))
+            .chain(self.others.iter().cloned())
+            .collect::<Vec<(String, String)>>();
+        attrs.sort_by_key(|(k, _)| {
+            self.order
+                .iter()
+                .position(|o| o == k)
+                .unwrap_or(usize::MAX)
+        });
+        attrs
+    }
+}
+
+impl Display for CommonKVs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_attributes(f, vec![])
     }
 }
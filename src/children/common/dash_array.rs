@@ -1,18 +1,25 @@
 use std::{fmt::Display, str::FromStr};
 
+use crate::{error::Error, length::Length};
+
+/// The lengths of the dashes and gaps of `stroke-dasharray`, empty for `none`.
 #[derive(Debug, Clone, Default)]
-pub struct DashArray(pub Vec<f32>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DashArray(pub Vec<Length>);
 
 impl FromStr for DashArray {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "none" {
+            return Ok(DashArray(vec![]));
+        }
         let iter = s
-            .trim()
-            .split_whitespace()
+            .split(|c: char| c == ',' || c.is_whitespace())
             .filter(|s| !s.is_empty())
-            .map(|s| s.parse().unwrap())
-            .collect::<Vec<f32>>();
+            .map(|s| s.parse())
+            .collect::<Result<Vec<Length>, _>>()
+            .map_err(|_| Error::invalid_value(s, "a list of lengths"))?;
 
         Ok(DashArray(iter))
     }
@@ -20,6 +27,9 @@ impl FromStr for DashArray {
 
 impl Display for DashArray {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
         write!(
             f,
             "{}",
@@ -31,3 +41,21 @@ impl Display for DashArray {
         )
     }
 }
+
+#[cfg(test)]
+mod test_dash_array {
+    use super::*;
+
+    #[test]
+    fn test_dash_array() {
+        let dashes: DashArray = "1, 2 3%".parse().unwrap();
+        assert_eq!(dashes.to_string(), "1 2 3%");
+        let none: DashArray = " none ".parse().unwrap();
+        assert!(none.0.is_empty());
+        assert_eq!(none.to_string(), "none");
+        assert!("1 none".parse::<DashArray>().is_err());
+
+        let svg: crate::Svg = r#"<svg><path d="M0 0 H1" stroke-dasharray="none"/></svg>"#.parse().unwrap();
+        assert_eq!(svg.to_string(), r#"<svg><path d="M 0,0 H 1" stroke-dasharray="none"/></svg>"#);
+    }
+}
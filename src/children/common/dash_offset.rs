@@ -1,19 +1,14 @@
-#[derive(Debug, Clone, Copy)]
-pub struct DashOffset(pub f32);
+use crate::{error::Error, length::Length};
 
-impl Default for DashOffset {
-    fn default() -> Self {
-        Self(0_f32)
-    }
-}
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DashOffset(pub Length);
 
 impl std::str::FromStr for DashOffset {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim();
-        let f = s.parse().unwrap();
-        Ok(DashOffset(f))
+        Ok(DashOffset(s.parse()?))
     }
     
 }
@@ -1,27 +1,26 @@
 use std::fmt::Display;
 
-#[derive(Debug, Clone, Copy)]
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum LineCap {
+    #[default]
     Butt,
     Round,
     Square,
 }
 
-impl Default for LineCap {
-    fn default() -> Self {
-        LineCap::Butt
-    }
-}
-
 impl std::str::FromStr for LineCap {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "butt" => Ok(LineCap::Butt),
             "round" => Ok(LineCap::Round),
             "square" => Ok(LineCap::Square),
-            _ => Err("".to_string()),
+            _ => Err(Error::invalid_value(s, "`butt`, `round` or `square`")),
         }
     }
 }
@@ -1,22 +1,21 @@
 use std::fmt::Display;
 
-#[derive(Debug, Clone, Copy)]
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum LineJoin {
     Arcs,
+    #[default]
     Miter,
     Round,
     Bevel,
     MiterClip,
 }
 
-impl Default for LineJoin {
-    fn default() -> Self {
-        LineJoin::Miter
-    }
-}
-
 impl std::str::FromStr for LineJoin {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -25,7 +24,7 @@ impl std::str::FromStr for LineJoin {
             "round" => Ok(LineJoin::Round),
             "bevel" => Ok(LineJoin::Bevel),
             "miter-clip" => Ok(LineJoin::MiterClip),
-            _ => Err("".to_string()),
+            _ => Err(Error::invalid_value(s, "`arcs`, `miter`, `round`, `bevel` or `miter-clip`")),
         }
     }
 }
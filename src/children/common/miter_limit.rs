@@ -1,4 +1,7 @@
+use crate::error::Error;
+
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MiterLimit(pub f32);
 
 impl Default for MiterLimit {
@@ -8,12 +11,14 @@ impl Default for MiterLimit {
 }
 
 impl std::str::FromStr for MiterLimit {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
-        let f = s.parse().unwrap();
-        Ok(MiterLimit(f))
+        match s.parse::<f32>() {
+            Ok(f) if f.is_finite() => Ok(MiterLimit(f)),
+            _ => Err(Error::invalid_value(s, "a number")),
+        }
     }
 }
 
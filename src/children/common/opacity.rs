@@ -1,4 +1,7 @@
+use crate::error::Error;
+
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Opacity(pub f32);
 
 impl Default for Opacity {
@@ -8,13 +11,15 @@ impl Default for Opacity {
 }
 
 impl std::str::FromStr for Opacity {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
-        let f = s.parse().unwrap();
-        if f < 0_f32 || f > 1_f32 {
-            return Err(format!("Invalid opacity value: {}", f));
+        let f = s
+            .parse()
+            .map_err(|_| Error::invalid_value(s, "a number between 0 and 1"))?;
+        if !(0_f32..=1_f32).contains(&f) {
+            return Err(Error::invalid_value(s, "a number between 0 and 1"));
         }
         Ok(Opacity(f))
     }
@@ -0,0 +1,73 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::{
+    character::complete::multispace0,
+    multi::many0,
+    sequence::{preceded, terminated},
+};
+
+use crate::{
+    error::{Error, IResult},
+    parser::{number, sep},
+};
+
+/// The `points` of `<polygon>` and `<polyline>`: a list of `x,y` pairs.
+///
+/// Coordinates may be separated by commas and/or whitespace: `0,0 10,0`, `0 0 10 0` and `0,0,10,0` are the same.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Points(pub Vec<(f32, f32)>);
+
+impl Points {
+    fn numbers(s: &str) -> IResult<&str, Vec<f32>> {
+        preceded(multispace0, many0(terminated(number, sep)))(s)
+    }
+}
+
+impl FromStr for Points {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let numbers = match Points::numbers(s) {
+            Ok(("", numbers)) => numbers,
+            _ => return Err(Error::invalid_value(s, "a list of numbers")),
+        };
+        if numbers.len() % 2 != 0 {
+            return Err(Error::invalid_value(s, "an even number of coordinates"));
+        }
+
+        Ok(Points(
+            numbers.chunks_exact(2).map(|p| (p[0], p[1])).collect(),
+        ))
+    }
+}
+
+impl Display for Points {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, (x, y)) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{},{}", x, y)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_points {
+    use super::*;
+
+    #[test]
+    fn test_points() {
+        let expect = Points(vec![(0.0, 0.0), (10.0, 0.0), (5.0, -5.5)]);
+        assert_eq!("0,0 10,0 5,-5.5".parse::<Points>().unwrap(), expect);
+        assert_eq!("0 0 10 0 5 -5.5".parse::<Points>().unwrap(), expect);
+        assert_eq!(" 0,0,10,0,5-5.5 ".parse::<Points>().unwrap(), expect);
+        assert_eq!("0 , 0\n10 ,0 5, -5.5".parse::<Points>().unwrap(), expect);
+        assert_eq!("".parse::<Points>().unwrap(), Points::default());
+        assert_eq!(expect.to_string(), "0,0 10,0 5,-5.5");
+        assert!("0,0 10".parse::<Points>().is_err());
+        assert!("0,0 a,b".parse::<Points>().is_err());
+    }
+}
@@ -1,68 +1,129 @@
 use std::fmt::Display;
 
+use crate::error::{attribute_in, IResult};
+use crate::length::{resolve, Axis, Length, LengthContext};
 use crate::value::Auto;
 
-use super::CommonKVs;
+use super::{circle::ellipse_data, Curves, CommonKVs, Path, PathData};
 /// The <ellipse> element is an SVG basic shape,
 /// used to create ellipses based on a center coordinate, and both their x and y radius.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Ellipse {
     pub common: CommonKVs,
     /// The x position of the center of the ellipse. Value type: <length>|<percentage> ; Default value: 0;
-    pub cx: Option<f32>,
+    pub cx: Option<Length>,
     /// The y position of the center of the ellipse. Value type: <length>|<percentage> ; Default value: 0;
-    pub cy: Option<f32>,
+    pub cy: Option<Length>,
     /// The radius of the ellipse on the x axis. Value type: auto|<length>|<percentage> ; Default value: auto
-    pub rx: Auto<f32>,
+    pub rx: Auto<Length>,
     /// The radius of the ellipse on the y axis. Value type: auto|<length>|<percentage> ; Default value: auto;
-    pub ry: Auto<f32>,
+    pub ry: Auto<Length>,
 }
 
 impl Ellipse {
-    pub fn from_str(s: &str) -> nom::IResult<&str, Ellipse> {
-        let (s, (common, kvs)) = CommonKVs::from_str(s.trim()).unwrap();
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, Ellipse> {
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s.trim())?;
+        common.remove_others(&["cx", "cy", "rx", "ry"]);
+
+        if let Some(kvs) = kvs {
+            let cx = attribute_in(&kvs, "cx")?;
+            let cy = attribute_in(&kvs, "cy")?;
+            let rx = Auto::get_from_map(&kvs, "rx")?;
+            let ry = Auto::get_from_map(&kvs, "ry")?;
 
-        if kvs.is_none() {
             Ok((
                 s,
                 Ellipse {
                     common,
-                    cx: None,
-                    cy: None,
-                    rx: Auto(None),
-                    ry: Auto(None),
+                    cx,
+                    cy,
+                    rx,
+                    ry,
                 },
             ))
         } else {
-            let cx = kvs.as_ref().unwrap().get("cx").map(|v| v.parse().unwrap());
-            let cy = kvs.as_ref().unwrap().get("cy").map(|v| v.parse().unwrap());
-            let rx = Auto::get_from_map(&kvs.as_ref().unwrap(), "rx");
-            let ry = Auto::get_from_map(&kvs.as_ref().unwrap(), "ry");
-
             Ok((
                 s,
                 Ellipse {
                     common,
-                    cx,
-                    cy,
-                    rx,
-                    ry,
+                    cx: None,
+                    cy: None,
+                    rx: Auto(None),
+                    ry: Auto(None),
                 },
             ))
         }
     }
 }
 
+impl Ellipse {
+    /// the radii in user units, an `auto` radius is the other one (resolved on its own axis)
+    pub(crate) fn radii(&self, context: &LengthContext) -> (f32, f32) {
+        let rx = self.rx.0.map(|rx| rx.resolve(Axis::X, context));
+        let ry = self.ry.0.map(|ry| ry.resolve(Axis::Y, context));
+        (rx.or(ry).unwrap_or_default(), ry.or(rx).unwrap_or_default())
+    }
+    /// the equivalent `<path>`, four quarter arcs clockwise from the right-most point
+    pub fn to_path(&self, context: &LengthContext, curves: Curves) -> Path {
+        Path {
+            common: self.common.clone(),
+            d: Some(self.path_data(context, curves)),
+        }
+    }
+    /// four quarter arcs clockwise from the right-most point, empty if a radius is not positive
+    pub(crate) fn path_data(&self, context: &LengthContext, curves: Curves) -> PathData {
+        let (rx, ry) = self.radii(context);
+        ellipse_data(
+            resolve(self.cx, Axis::X, context),
+            resolve(self.cy, Axis::Y, context),
+            rx,
+            ry,
+            curves,
+        )
+    }
+}
+
+impl Ellipse {
+    /// the attributes of the ellipse which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        let mut own: Vec<(&'static str, String)> = [("cx", self.cx), ("cy", self.cy)]
+            .into_iter()
+            .filter_map(|(k, v)| v.map(|v| (k, v.to_string())))
+            .collect();
+        // `auto` is the default, only write it back if it was in the source
+        for (k, v) in [("rx", &self.rx), ("ry", &self.ry)] {
+            if v.0.is_some() || self.common.order.iter().any(|o| o == k) {
+                own.push((k, v.to_string()));
+            }
+        }
+        own
+    }
+}
+
 impl Display for Ellipse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" {} />",
-            self.cx.as_ref().unwrap_or(&0_f32),
-            self.cy.as_ref().unwrap_or(&0_f32),
-            self.rx,
-            self.ry,
-            self.common
-        )
+        write!(f, "<ellipse")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, "/>")
+    }
+}
+
+#[cfg(test)]
+mod test_ellipse {
+    use super::*;
+
+    #[test]
+    fn test_radii() {
+        let context = LengthContext::new(40.0, 20.0);
+        let (_, ellipse) = Ellipse::from_str(r#"rx="3" ry="2""#).unwrap();
+        assert_eq!(ellipse.radii(&context), (3.0, 2.0));
+        // an `auto` radius is the other one resolved on its own axis: a circle
+        let (_, ellipse) = Ellipse::from_str(r#"ry="10%""#).unwrap();
+        assert_eq!(ellipse.radii(&context), (2.0, 2.0));
+        let (_, ellipse) = Ellipse::from_str(r#"rx="10%" ry="auto""#).unwrap();
+        assert_eq!(ellipse.radii(&context), (4.0, 4.0));
     }
 }
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use crate::error::IResult;
+
+use super::{Child, CommonKVs};
+
+/// The <g> SVG element is a container used to group other SVG elements.
+///
+/// Attributes applied to the <g> element are inherited by its children,
+/// which is how most icon sets share `fill`, `stroke` and `transform`.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Group {
+    pub common: CommonKVs,
+    pub children: Vec<Child>,
+}
+
+impl Group {
+    /// parse the attributes of a group, the children are parsed by [`Child::parser`]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, Group> {
+        let (s, (common, _)) = CommonKVs::from_str(s.trim())?;
+        Ok((
+            s,
+            Group {
+                common,
+                children: vec![],
+            },
+        ))
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<g{}>", self.common)?;
+        for child in &self.children {
+            write!(f, "{}", child)?;
+        }
+        write!(f, "</g>")
+    }
+}
+
+#[cfg(test)]
+mod test_group {
+    use crate::{children::{Child, MAX_DEPTH}, paint::Paint, Svg};
+
+    #[test]
+    fn test_nested_groups() {
+        let svg: Svg = r##"<svg viewBox="0 0 24 24">
+            <g fill="none" stroke="#000">
+                <path d="M0 0L24 24"/>
+                <g stroke-width="2" transform="rotate(45 12 12)"><circle cx="12" cy="12" r="4"/></g>
+                <g></g>
+                <rect width="2" height="2"></rect>
+            </g>
+        </svg>"##
+            .parse()
+            .unwrap();
+        assert_eq!(svg.children.len(), 1);
+        let Child::Group(group) = &svg.children[0] else {
+            panic!("expect a group");
+        };
+        assert_eq!(group.common.fill, Some(Paint::None));
+        assert_eq!(group.children.len(), 4);
+        assert!(matches!(group.children[0], Child::Path(_)));
+        let Child::Group(inner) = &group.children[1] else {
+            panic!("expect a group");
+        };
+        assert_eq!(inner.common.stroke_width, Some(2.0.into()));
+        assert_eq!(
+            inner.common.transform.as_ref().map(|t| t.to_string()).as_deref(),
+            Some("rotate(45 12 12)")
+        );
+        assert!(matches!(inner.children[0], Child::Circle(_)));
+        assert!(matches!(&group.children[2], Child::Group(g) if g.children.is_empty()));
+        assert!(matches!(group.children[3], Child::Rect(_)));
+
+        // round trip
+        let again: Svg = svg.to_string().parse().unwrap();
+        assert_eq!(again.to_string(), svg.to_string());
+    }
+
+    #[test]
+    fn test_group_errors() {
+        let err = "<svg><g><g><circle r=\"x\"/></g></g></svg>"
+            .parse::<Svg>()
+            .unwrap_err();
+        assert_eq!(
+            err.location().unwrap().element,
+            vec!["svg", "g", "g", "circle"]
+        );
+        let err = "<svg><g><path/></svg>".parse::<Svg>().unwrap_err();
+        assert!(matches!(err, crate::Error::UnterminatedTag { ref name, .. } if name == "g"));
+        let err = "<svg><g><path/></rect></svg>".parse::<Svg>().unwrap_err();
+        assert!(matches!(err, crate::Error::UnterminatedTag { ref name, .. } if name == "g"));
+    }
+
+    #[test]
+    fn test_group_depth() {
+        let nested = |depth: usize| format!("<svg>{}{}</svg>", "<g>".repeat(depth), "</g>".repeat(depth));
+        assert!(nested(MAX_DEPTH).parse::<Svg>().is_ok());
+        let err = nested(MAX_DEPTH + 1).parse::<Svg>().unwrap_err();
+        assert!(matches!(err, crate::Error::TooDeep { ref name, .. } if name == "g"));
+        // `<svg>` and the groups around the one which is too deep
+        assert_eq!(err.location().unwrap().element.len(), MAX_DEPTH + 1);
+        // an error instead of a stack overflow
+        assert!(nested(100_000).parse::<Svg>().is_err());
+    }
+}
@@ -1,29 +1,34 @@
 use std::fmt::Display;
 
-use nom::IResult;
+use crate::error::{attribute_in, IResult};
+use crate::length::{resolve, Axis, Length, LengthContext};
 
-use super::CommonKVs;
+use super::{CommonKVs, Path, PathData, L, M};
 
 /// The <line> element is an SVG basic shape used to create a line connecting two points.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Line{
     pub common: CommonKVs,
     /// Defines the x-axis coordinate of the line starting point. Value type: <length>|<percentage>|<number> ; Default value: 0; 
-    pub x1: Option<f32>,
+    pub x1: Option<Length>,
     /// Defines the x-axis coordinate of the line ending point. Value type: <length>|<percentage>|<number> ; Default value: 0;
-    pub y1: Option<f32>,
+    pub y1: Option<Length>,
     /// Defines the y-axis coordinate of the line starting point. Value type: <length>|<percentage>|<number> ; Default value: 0; 
-    pub x2: Option<f32>,
+    pub x2: Option<Length>,
     /// Defines the y-axis coordinate of the line ending point. Value type: <length>|<percentage>|<number> ; Default value: 0;
-    pub y2: Option<f32>,
+    pub y2: Option<Length>,
 }
 
 impl Line {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, Line> {
-        let (s, (common, kvs)) = CommonKVs::from_str(s.trim()).unwrap();
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s.trim())?;
+        common.remove_others(&["x1", "y1", "x2", "y2"]);
 
-        if kvs.is_none() {
-            Ok((
+        match kvs {
+            None => Ok((
                 s,
                 Line {
                     common,
@@ -32,32 +37,61 @@ impl Line {
                     x2: None,
                     y2: None,
                 },
-            ))
-        } else {
-            Ok((
+            )),
+            Some(kvs) => Ok((
                 s,
                 Line {
                     common,
-                    x1: kvs.as_ref().unwrap().get("x1").map(|v| v.parse().unwrap()),
-                    y1: kvs.as_ref().unwrap().get("y1").map(|v| v.parse().unwrap()),
-                    x2: kvs.as_ref().unwrap().get("x2").map(|v| v.parse().unwrap()),
-                    y2: kvs.as_ref().unwrap().get("y2").map(|v| v.parse().unwrap()),
+                    x1: attribute_in(&kvs, "x1")?,
+                    y1: attribute_in(&kvs, "y1")?,
+                    x2: attribute_in(&kvs, "x2")?,
+                    y2: attribute_in(&kvs, "y2")?,
                 },
-            ))
+            )),
         }
     }
 }
 
+impl Line {
+    /// the equivalent `<path>`: `M x1,y1 L x2,y2`
+    pub fn to_path(&self, context: &LengthContext) -> Path {
+        let mut d = PathData::new();
+        d.push(M {
+            x: resolve(self.x1, Axis::X, context),
+            y: resolve(self.y1, Axis::Y, context),
+            relative: false,
+        });
+        d.push(L {
+            x: resolve(self.x2, Axis::X, context),
+            y: resolve(self.y2, Axis::Y, context),
+            relative: false,
+        });
+        Path {
+            common: self.common.clone(),
+            d: Some(d),
+        }
+    }
+}
+
+impl Line {
+    /// the attributes of the line which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        [
+            ("x1", self.x1),
+            ("y1", self.y1),
+            ("x2", self.x2),
+            ("y2", self.y2),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| v.map(|v| (k, v.to_string())))
+        .collect()
+    }
+}
+
 impl Display for Line {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {} />",
-            self.x1.as_ref().unwrap_or(&0_f32),
-            self.y1.as_ref().unwrap_or(&0_f32),
-            self.x2.as_ref().unwrap_or(&0_f32),
-            self.y2.as_ref().unwrap_or(&0_f32),
-            self.common
-        )
+        write!(f, "<line")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, "/>")
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,11 @@
 mod circle;
 mod common;
 mod ellipse;
+mod group;
 mod line;
 mod path;
+mod polygon;
+mod polyline;
 mod rect;
 
 use std::fmt::Display;
@@ -11,51 +14,167 @@ use std::str::FromStr;
 pub use circle::*;
 pub use common::*;
 pub use ellipse::*;
+pub use group::*;
 pub use line::*;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
+use nom::bytes::complete::{tag, take_until, take_while1};
+use nom::character::complete::multispace0;
+use nom::combinator::recognize;
 use nom::multi::many0;
-use nom::{sequence::preceded, IResult};
+use nom::sequence::{delimited, tuple};
 pub use path::*;
+pub use polygon::Polygon;
+pub use polyline::Polyline;
 pub use rect::*;
 
-use super::parser::trim;
+use crate::error::{Error, ErrorKind, IResult, Location, ParseError};
+use crate::length::LengthContext;
+
+use super::parser::{parse_normal_key, parse_properties, trim};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum Child {
     Circle(Circle),
     Ellipse(Ellipse),
+    Group(Group),
     Line(Line),
     Path(Path),
+    Polygon(Polygon),
+    Polyline(Polyline),
     Rect(Rect),
+    /// an element without a type in this crate (`<title>`, `<defs>`, `<use>`...),
+    /// kept as it is so that it can be written back
+    Unknown {
+        name: String,
+        attrs: Vec<(String, String)>,
+        children: Vec<Child>,
+    },
+    /// character data between elements, e.g. the content of `<title>`, written back as it is
+    Text(String),
+    /// the content of a `<!-- ... -->` comment
+    Comment(String),
     // unsupported now ----------------------
     // Text,
     // TextPath,
     // Tspan,
     // Tref,
-    // Polygon,
-    // Polyline,
+}
+
+/// The deepest nesting of elements below `<svg>` which is parsed, deeper documents are an error
+/// instead of overflowing the stack, even in debug builds on threads with 2 MiB of stack.
+pub const MAX_DEPTH: usize = 128;
+
+/// How the rounded parts of shapes are drawn when they are converted to paths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Curves {
+    /// elliptical arcs, exact
+    #[default]
+    Arcs,
+    /// cubic beziers, the standard approximation of a quarter of an ellipse
+    Cubics,
 }
 
 impl Child {
-    fn which(s: &str) -> IResult<&str, Child> {
-        let (s, name) = preceded(
-            trim(tag("<")),
-            alt((
-                tag("path"),
-                tag("circle"),
-                tag("rect"),
-                tag("line"),
-                tag("ellipse"),
-            )),
-        )(s)?;
+    /// the element name, `#text` and `#comment` for character data
+    pub fn name(&self) -> &str {
+        match self {
+            Child::Circle(_) => "circle",
+            Child::Ellipse(_) => "ellipse",
+            Child::Group(_) => "g",
+            Child::Line(_) => "line",
+            Child::Path(_) => "path",
+            Child::Polygon(_) => "polygon",
+            Child::Polyline(_) => "polyline",
+            Child::Rect(_) => "rect",
+            Child::Unknown { name, .. } => name,
+            Child::Text(_) => "#text",
+            Child::Comment(_) => "#comment",
+        }
+    }
+    /// the attributes of a known element
+    pub fn common(&self) -> Option<&CommonKVs> {
+        match self {
+            Child::Circle(c) => Some(&c.common),
+            Child::Ellipse(e) => Some(&e.common),
+            Child::Group(g) => Some(&g.common),
+            Child::Line(l) => Some(&l.common),
+            Child::Path(p) => Some(&p.common),
+            Child::Polygon(p) => Some(&p.common),
+            Child::Polyline(p) => Some(&p.common),
+            Child::Rect(r) => Some(&r.common),
+            Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => None,
+        }
+    }
+    pub fn common_mut(&mut self) -> Option<&mut CommonKVs> {
+        match self {
+            Child::Circle(c) => Some(&mut c.common),
+            Child::Ellipse(e) => Some(&mut e.common),
+            Child::Group(g) => Some(&mut g.common),
+            Child::Line(l) => Some(&mut l.common),
+            Child::Path(p) => Some(&mut p.common),
+            Child::Polygon(p) => Some(&mut p.common),
+            Child::Polyline(p) => Some(&mut p.common),
+            Child::Rect(r) => Some(&mut r.common),
+            Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => None,
+        }
+    }
+    /// the equivalent `<path>` of a shape (or a copy of a path), `None` for other elements.
+    ///
+    /// lengths are resolved to user units with `context`
+    pub fn to_path(&self, context: &LengthContext, curves: Curves) -> Option<Path> {
+        match self {
+            Child::Circle(c) => Some(c.to_path(context, curves)),
+            Child::Ellipse(e) => Some(e.to_path(context, curves)),
+            Child::Line(l) => Some(l.to_path(context)),
+            Child::Path(p) => Some(p.clone()),
+            Child::Polygon(p) => Some(p.to_path()),
+            Child::Polyline(p) => Some(p.to_path()),
+            Child::Rect(r) => Some(r.to_path(context, curves)),
+            Child::Group(_) | Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => None,
+        }
+    }
+    /// parse the start of a tag: `<name`, a closing tag `</name>` is not matched
+    fn which(s: &str) -> IResult<&str, (&str, Child)> {
+        let (s, _) = trim(tag("<"))(s)?;
+        let (s, name) = parse_normal_key(s)?;
 
-        Ok((s, name.parse().unwrap()))
+        let child = name.parse().unwrap_or_else(|_| Child::Unknown {
+            name: name.to_string(),
+            attrs: vec![],
+            children: vec![],
+        });
+        Ok((s, (name, child)))
     }
+    /// text or a comment between elements, text is kept as it is written
+    fn character_data(s: &str) -> IResult<&str, Child> {
+        if let Ok((s, comment)) = delimited(
+            tag::<_, _, ParseError<&str>>("<!--"),
+            take_until("-->"),
+            tag("-->"),
+        )(s)
+        {
+            return Ok((s, Child::Comment(comment.to_string())));
+        }
+        if let Ok((s, cdata)) = recognize(tuple((
+            tag::<_, _, ParseError<&str>>("<![CDATA["),
+            take_until("]]>"),
+            tag("]]>"),
+        )))(s)
+        {
+            return Ok((s, Child::Text(cdata.to_string())));
+        }
+        let (s, text) = take_while1(|c| c != '<')(s)?;
+        Ok((s, Child::Text(text.to_string())))
+    }
+    /// nom parser of the children of an element, see [`MAX_DEPTH`]
     pub fn parser(s: &str) -> IResult<&str, Vec<Child>> {
-        fn single(s: &str) -> IResult<&str, Child> {
-            let (s, child) = Child::which(s)?;
-
+        Child::nested(s, 0)
+    }
+    /// the children at `depth` levels below `<svg>`
+    fn nested(s: &str, depth: usize) -> IResult<&str, Vec<Child>> {
+        fn element(s: &str, child: Child) -> IResult<&str, Child> {
             let (s, child) = match child {
                 Child::Path(_) => {
                     let (s, path) = Path::from_str(s)?;
@@ -77,27 +196,122 @@ impl Child {
                     let (s, ellipse) = Ellipse::from_str(s)?;
                     (s, Child::Ellipse(ellipse))
                 }
+                Child::Group(_) => {
+                    let (s, group) = Group::from_str(s)?;
+                    (s, Child::Group(group))
+                }
+                Child::Polygon(_) => {
+                    let (s, polygon) = Polygon::from_str(s)?;
+                    (s, Child::Polygon(polygon))
+                }
+                Child::Polyline(_) => {
+                    let (s, polyline) = Polyline::from_str(s)?;
+                    (s, Child::Polyline(polyline))
+                }
+                Child::Unknown { name, .. } => {
+                    let (s, attrs) = parse_properties(s)?;
+                    (
+                        s,
+                        Child::Unknown {
+                            name,
+                            attrs: owned(attrs),
+                            children: vec![],
+                        },
+                    )
+                }
+                child @ (Child::Text(_) | Child::Comment(_)) => (s, child),
             };
-            let (s, _) = trim(tag("/"))(s)?;
-            let (s, _) = trim(tag(">"))(s)?;
             Ok((s, child))
         }
+        fn owned(attrs: Vec<(&str, &str)>) -> Vec<(String, String)> {
+            attrs
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        }
+        /// the end of an element: `/>` or `>` children `</name>`,
+        /// `attrs` is the source of the attributes, used when a shape has children
+        fn end<'a>(
+            s: &'a str,
+            name: &str,
+            attrs: &'a str,
+            mut child: Child,
+            depth: usize,
+        ) -> IResult<&'a str, Child> {
+            let unterminated = |s| {
+                nom::Err::Failure(ParseError::new(
+                    s,
+                    ErrorKind::UnterminatedTag(name.to_string()),
+                ))
+            };
+            if let Ok((s, _)) = alt((tag::<_, _, ParseError<&str>>("/>"), tag("/ >")))(s) {
+                return Ok((s, child));
+            }
+            let (s, _) = tag(">")(s).map_err(|_: nom::Err<ParseError<&str>>| unterminated(s))?;
+            let (rest, children) = Child::nested(s, depth + 1)?;
+            match &mut child {
+                Child::Group(group) => group.children = children,
+                Child::Unknown { children: c, .. } => *c = children,
+                _ if children.is_empty() => {}
+                // a shape with children (`<title>`, `<animate>`...) is kept as an unknown element
+                _ => {
+                    let (_, attrs) = parse_properties(attrs.trim_start())?;
+                    child = Child::Unknown {
+                        name: name.to_string(),
+                        attrs: owned(attrs),
+                        children,
+                    };
+                }
+            }
+            // what follows `>` is character data of the parent
+            let (s, _) = tuple((trim(tag("</")), tag(name), multispace0, tag(">")))(rest)
+                .map_err(|_: nom::Err<ParseError<&str>>| unterminated(rest))?;
+            Ok((s, child))
+        }
+        fn single(s: &str, depth: usize) -> IResult<&str, Child> {
+            if let Ok(data) = Child::character_data(s) {
+                return Ok(data);
+            }
+            let (s, (name, child)) = Child::which(s)?;
+            if depth >= MAX_DEPTH {
+                return Err(nom::Err::Failure(ParseError::new(s, ErrorKind::TooDeep(name.to_string()))));
+            }
+            // once the element is recognized, every error is a failure of this element
+            element(s, child)
+                .and_then(|(rest, child)| end(rest, name, s, child, depth))
+                .map_err(|e| match e {
+                    nom::Err::Error(e) | nom::Err::Failure(e) => nom::Err::Failure(e.within(name)),
+                    e => e,
+                })
+        }
 
-        many0(single)(s)
+        let (s, mut children) = many0(|s| single(s, depth))(s)?;
+        // whitespace only separates elements, unless it is between text (`<tspan>a</tspan> b`)
+        let blank = |child: &Child| matches!(child, Child::Text(text) if text.trim().is_empty());
+        if !children.iter().any(|child| matches!(child, Child::Text(_)) && !blank(child)) {
+            children.retain(|child| !blank(child));
+        }
+        Ok((s, children))
     }
 }
 
 impl FromStr for Child {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "circle" => Ok(Child::Circle(Circle::default())),
             "ellipse" => Ok(Child::Ellipse(Ellipse::default())),
+            "g" => Ok(Child::Group(Group::default())),
             "line" => Ok(Child::Line(Line::default())),
             "path" => Ok(Child::Path(Path::default())),
+            "polygon" => Ok(Child::Polygon(Polygon::default())),
+            "polyline" => Ok(Child::Polyline(Polyline::default())),
             "rect" => Ok(Child::Rect(Rect::default())),
-            _ => Err(format!("Unsupported children: {}", s)),
+            _ => Err(Error::UnsupportedElement {
+                name: s.to_string(),
+                location: Location::default(),
+            }),
         }
     }
 }
@@ -107,9 +321,32 @@ impl Display for Child {
         match self {
             Child::Circle(c) => write!(f, "{}", c),
             Child::Ellipse(e) => write!(f, "{}", e),
+            Child::Group(g) => write!(f, "{}", g),
             Child::Line(l) => write!(f, "{}", l),
             Child::Path(p) => write!(f, "{}", p),
+            Child::Polygon(p) => write!(f, "{}", p),
+            Child::Polyline(p) => write!(f, "{}", p),
             Child::Rect(r) => write!(f, "{}", r),
+            Child::Unknown {
+                name,
+                attrs,
+                children,
+            } => {
+                write!(f, "<{}", name)?;
+                for (k, v) in attrs {
+                    write!(f, " {}=\"{}\"", k, v.replace('"', "&quot;"))?;
+                }
+                if children.is_empty() {
+                    return write!(f, "/>");
+                }
+                write!(f, ">")?;
+                for child in children {
+                    write!(f, "{}", child)?;
+                }
+                write!(f, "</{}>", name)
+            }
+            Child::Text(text) => write!(f, "{}", text),
+            Child::Comment(comment) => write!(f, "<!--{}-->", comment),
         }
     }
 }
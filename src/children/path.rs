@@ -1,53 +1,85 @@
 use std::fmt::Display;
 
-use nom::IResult;
+use crate::error::{ErrorKind, IResult, ParseError};
 
 use super::CommonKVs;
 
 mod close_path;
 mod command;
 mod cubic_bezier;
+mod data;
 mod elliptical_arc;
+mod encode;
 mod line_to;
+mod measure;
 mod move_to;
+mod normalize;
 mod quadratic_bezier;
+mod segment;
+mod simplify;
+mod subpath;
 
 pub use close_path::Z;
 pub use command::Command;
 pub use cubic_bezier::*;
-pub use elliptical_arc::A;
+pub use data::PathData;
+pub use elliptical_arc::{ArcCenter, A};
+pub(crate) use encode::round;
 pub use line_to::*;
+pub use measure::PathMeasure;
 pub use move_to::M;
+pub use normalize::Cursor;
 pub use quadratic_bezier::*;
+pub use segment::{Segment, Segments};
+pub use subpath::{Subpath, Subpaths};
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Path {
     pub common: CommonKVs,
-    pub d: Option<Command>,
+    pub d: Option<PathData>,
 }
 
 impl Path {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, Path> {
-        let (s, (common, kvs)) = CommonKVs::from_str(s.trim()).unwrap();
-
-        if kvs.is_none() {
-            Ok((s, Path { common, d: None }))
-        } else {
-            let d = kvs.as_ref().unwrap().get("d").map(|v| v.to_string());
-            let d  = d.map(|v| Command::from_str(&v).unwrap().1);
-            Ok((s, Path { common,d}))
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s.trim())?;
+        common.remove_others(&["d"]);
+
+        match kvs {
+            None => Ok((s, Path { common, d: None })),
+            Some(kvs) => {
+                let d = match kvs.get("d") {
+                    Some(v) => {
+                        let (rest, d) = PathData::from_str(v)?;
+                        if !rest.is_empty() {
+                            return Err(nom::Err::Failure(ParseError::new(
+                                rest,
+                                ErrorKind::InvalidPathData,
+                            )));
+                        }
+                        Some(d)
+                    }
+                    None => None,
+                };
+                Ok((s, Path { common, d }))
+            }
         }
     }
 }
 
+impl Path {
+    /// the attributes of the path which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        self.d.iter().map(|d| ("d", d.to_string())).collect()
+    }
+}
+
 impl Display for Path {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let d = if self.d.as_ref().is_some() {
-            self.d.as_ref().unwrap().to_string()
-        } else {
-            "".to_string()
-        };
-
-        f.write_fmt(format_args!("<path d=\"{}\" {} />", d, self.common))
+        write!(f, "<path")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, "/>")
     }
 }
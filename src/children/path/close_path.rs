@@ -2,21 +2,26 @@ use std::fmt::Display;
 
 use nom::{branch::alt, character::complete::char};
 
+use crate::error::IResult;
+use crate::parser::trim;
+
 /// Z = closepath (close the path)
 /// if bool is true, it is a relative command
-#[derive(Debug, Clone, PartialEq)]
-pub struct Z(bool);
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Z(pub bool);
 
 impl Z {
-    pub fn from_str(s: &str) -> nom::IResult<&str, Z> {
-        let (s, releative) = alt((char('z'), char('Z')))(s)?;
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, Z> {
+        let (s, releative) = trim(alt((char('z'), char('Z'))))(s)?;
         Ok((s, Z(releative == 'z')))
     }
 }
 
 impl Display for Z {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Z")
+        write!(f, "{}", if self.0 { "z" } else { "Z" })
     }
 }
 
@@ -25,23 +30,7 @@ mod test_close{
     use super::*;
     #[test]
     fn test_close() {
-        assert_eq!(
-            Z::from_str("Z"),
-            Ok((
-                "",
-                Z {
-                    0: false
-                }
-            ))
-        );
-        assert_eq!(
-            Z::from_str("z"),
-            Ok((
-                "",
-                Z {
-                    0: true
-                }
-            ))
-        );
+        assert_eq!(Z::from_str("Z"), Ok(("", Z(false))));
+        assert_eq!(Z::from_str("z"), Ok(("", Z(true))));
     }
 }
\ No newline at end of file
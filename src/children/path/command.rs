@@ -1,8 +1,9 @@
 use std::fmt::Display;
 
-use super::{CubicBezier, LineTo, QuadraticBezier, A, M, Z};
+use super::{CubicBezier, LineTo, QuadraticBezier, A, C, H, L, M, Q, S, T, V, Z};
 use nom::branch::alt;
-use nom::{combinator::map, IResult};
+use crate::error::IResult;
+use nom::combinator::map;
 
 /// Required. A set of commands which define the path.
 ///
@@ -29,6 +30,7 @@ pub enum Command {
 }
 
 impl Command {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, Command> {
         alt((
             map(M::from_str, |v| v.into()),
@@ -78,6 +80,29 @@ impl From<QuadraticBezier> for Command {
     }
 }
 
+/// leaf commands convert through their group, so `L` becomes `Command::LineTo(LineTo::L(_))`
+macro_rules! impl_from_leaf {
+    ($($leaf:ty => $group:ty),*) => {
+        $(
+            impl From<$leaf> for Command {
+                fn from(v: $leaf) -> Self {
+                    <$group>::from(v).into()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_leaf!(
+    L => LineTo,
+    H => LineTo,
+    V => LineTo,
+    C => CubicBezier,
+    S => CubicBezier,
+    Q => QuadraticBezier,
+    T => QuadraticBezier
+);
+
 impl From<A> for Command {
     fn from(a: A) -> Self {
         Command::EllipticalArc(a)
@@ -1,9 +1,11 @@
 use std::fmt::Display;
 
-use crate::parser::{point, trim};
+use crate::parser::{command, point, sep};
 use nom::branch::alt;
 use nom::combinator::map;
-use nom::{bytes::complete::tag, sequence::pair, IResult};
+use nom::sequence::{preceded, tuple};
+use crate::error::IResult;
+use nom::character::complete::multispace0;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CubicBezier {
@@ -21,6 +23,7 @@ impl Display for CubicBezier {
 }
 
 impl CubicBezier {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, CubicBezier> {
         alt((
             map(C::from_str, |v| v.into()),
@@ -41,7 +44,8 @@ impl From<S> for CubicBezier {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct C {
     pub x1: f32,
     pub y1: f32,
@@ -69,11 +73,17 @@ impl Display for C {
 }
 
 impl C {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, C> {
-        let (s, (relative, ((x1, y1), ((x2, y2), (x, y))))) = trim(pair(
-            trim(alt((tag("c"), tag("C")))),
-            trim(pair(point, pair(point, point))),
-        ))(s)?;
+        let (s, relative) = command('c')(s)?;
+        let (s, c) = C::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, c))
+    }
+    /// parse the arguments after the command letter: `x1,y1 x2,y2 x,y`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, C> {
+        let (s, ((x1, y1), (x2, y2), (x, y))) =
+            tuple((point, preceded(sep, point), preceded(sep, point)))(s)?;
 
         Ok((
             s,
@@ -84,13 +94,14 @@ impl C {
                 y2,
                 x,
                 y,
-                relative: relative == "c",
+                relative,
             },
         ))
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct S {
     pub x2: f32,
     pub y2: f32,
@@ -114,11 +125,16 @@ impl Display for S {
 }
 
 impl S {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, S> {
-        let (s, (relative, ((x2, y2), (x, y)))) = trim(pair(
-            trim(alt((tag("s"), tag("S")))),
-            trim(pair(point, point)),
-        ))(s)?;
+        let (s, relative) = command('s')(s)?;
+        let (s, smooth) = S::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, smooth))
+    }
+    /// parse the arguments after the command letter: `x2,y2 x,y`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, S> {
+        let (s, ((x2, y2), (x, y))) = tuple((point, preceded(sep, point)))(s)?;
 
         Ok((
             s,
@@ -127,7 +143,7 @@ impl S {
                 y2,
                 x,
                 y,
-                relative: relative == "s",
+                relative,
             },
         ))
     }
@@ -0,0 +1,332 @@
+use std::fmt::Display;
+
+use nom::{
+    character::complete::{multispace0, one_of},
+    multi::many0,
+    sequence::{preceded, terminated},
+};
+
+use crate::error::IResult;
+use crate::parser::sep;
+
+use super::{Command, A, C, H, L, M, Q, S, T, V, Z};
+
+/// The full content of a `d` attribute: every command of the path, in order.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::children::PathData;
+///
+/// let (_, d) = PathData::from_str("M12 22L24 19L36 16").unwrap();
+/// assert_eq!(d.len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathData(pub Vec<Command>);
+
+impl PathData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// parse all commands of a path data string.
+    ///
+    /// follow the svg path grammar, so a command letter can be followed by several sets of arguments
+    /// (`L1 2 3 4` is `L1 2 L3 4`, `m0 0 10 10` is `m0 0 l10 10`)
+    /// and numbers do not need separators when there is no ambiguity (`M10-5`, `a1 1 0 00.5.5`)
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, PathData> {
+        let (s, segments) = terminated(many0(segment), multispace0)(s)?;
+        Ok((s, PathData(segments.into_iter().flatten().collect())))
+    }
+    pub fn iter(&self) -> std::slice::Iter<'_, Command> {
+        self.0.iter()
+    }
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Command> {
+        self.0.iter_mut()
+    }
+    pub fn push<C>(&mut self, command: C)
+    where
+        C: Into<Command>,
+    {
+        self.0.push(command.into());
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn commands(&self) -> &[Command] {
+        &self.0
+    }
+}
+
+/// parse one command letter and all of the argument sets following it
+fn segment(s: &str) -> IResult<&str, Vec<Command>> {
+    let (s, letter) = preceded(multispace0, one_of("MmLlHhVvCcSsQqTtAaZz"))(s)?;
+    let (s, _) = multispace0(s)?;
+    let relative = letter.is_ascii_lowercase();
+
+    match letter.to_ascii_uppercase() {
+        'M' => {
+            // a moveto followed by several pairs of coordinates, the subsequent pairs are linetos
+            let (s, m) = M::args(s, relative)?;
+            let (s, lines) = many0(preceded(sep, |s| L::args(s, relative)))(s)?;
+            let mut commands = vec![Command::from(m)];
+            commands.extend(lines.into_iter().map(Command::from));
+            Ok((s, commands))
+        }
+        'L' => repeated(s, |s| L::args(s, relative)),
+        'H' => repeated(s, |s| H::args(s, relative)),
+        'V' => repeated(s, |s| V::args(s, relative)),
+        'C' => repeated(s, |s| C::args(s, relative)),
+        'S' => repeated(s, |s| S::args(s, relative)),
+        'Q' => repeated(s, |s| Q::args(s, relative)),
+        'T' => repeated(s, |s| T::args(s, relative)),
+        'A' => repeated(s, |s| A::args(s, relative)),
+        _ => Ok((s, vec![Z(relative).into()])),
+    }
+}
+
+/// at least one set of arguments, each following set is the same command
+fn repeated<'a, O, F>(s: &'a str, mut args: F) -> IResult<&'a str, Vec<Command>>
+where
+    O: Into<Command>,
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    let (s, first) = args(s)?;
+    let (s, others) = many0(preceded(sep, &mut args))(s)?;
+    let mut commands = vec![first.into()];
+    commands.extend(others.into_iter().map(Into::into));
+    Ok((s, commands))
+}
+
+impl Display for PathData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, command) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", command)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<Command>> for PathData {
+    fn from(commands: Vec<Command>) -> Self {
+        PathData(commands)
+    }
+}
+
+impl<C: Into<Command>> Extend<C> for PathData {
+    fn extend<I: IntoIterator<Item = C>>(&mut self, iter: I) {
+        self.0.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+impl<C: Into<Command>> FromIterator<C> for PathData {
+    fn from_iter<I: IntoIterator<Item = C>>(iter: I) -> Self {
+        PathData(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl IntoIterator for PathData {
+    type Item = Command;
+    type IntoIter = std::vec::IntoIter<Command>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a PathData {
+    type Item = &'a Command;
+    type IntoIter = std::slice::Iter<'a, Command>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut PathData {
+    type Item = &'a mut Command;
+    type IntoIter = std::slice::IterMut<'a, Command>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test_path_data {
+    use super::*;
+    use crate::children::{LineTo, L, M, Z};
+
+    #[test]
+    fn test_all_segments() {
+        let (s, d) = PathData::from_str("M12 22L24 19L36 16").unwrap();
+        assert_eq!(s, "");
+        assert_eq!(
+            d,
+            PathData(vec![
+                Command::MoveTo(M {
+                    x: 12.0,
+                    y: 22.0,
+                    relative: false
+                }),
+                Command::LineTo(LineTo::L(L {
+                    x: 24.0,
+                    y: 19.0,
+                    relative: false
+                })),
+                Command::LineTo(LineTo::L(L {
+                    x: 36.0,
+                    y: 16.0,
+                    relative: false
+                })),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = "M 10 10 L 20 20 C 30 30 40 40 50 50 Q 60 60 70 70 A 80 80 90 0 1 100 100 z";
+        let (s, d) = PathData::from_str(input).unwrap();
+        assert_eq!(s, "");
+        assert_eq!(d.len(), 6);
+        let output = d.to_string();
+        let (s, again) = PathData::from_str(&output).unwrap();
+        assert_eq!(s, "");
+        assert_eq!(d, again);
+    }
+
+    #[test]
+    fn test_push_extend() {
+        let mut d = PathData::new();
+        d.push(M {
+            x: 0.0,
+            y: 0.0,
+            relative: false,
+        });
+        d.extend(vec![
+            LineTo::L(L {
+                x: 1.0,
+                y: 1.0,
+                relative: false,
+            }),
+            LineTo::L(L {
+                x: 2.0,
+                y: 0.0,
+                relative: false,
+            }),
+        ]);
+        d.push(Z(false));
+        assert_eq!(d.len(), 4);
+        assert_eq!(d.to_string(), "M 0,0 L 1,1 L 2,0 Z");
+    }
+
+    /// parse `input` completely, panic if anything is left
+    fn parse(input: &str) -> PathData {
+        let (s, d) = PathData::from_str(input).unwrap();
+        assert_eq!(s, "", "unparsed rest of {:?}", input);
+        d
+    }
+
+    /// both inputs must describe the same command sequence
+    fn assert_same(compact: &str, explicit: &str) {
+        assert_eq!(parse(compact), parse(explicit), "{:?} != {:?}", compact, explicit);
+    }
+
+    #[test]
+    fn test_spec_examples() {
+        // examples from the svg 1.1 "paths" chapter
+        let cases = [
+            ("M 100 100 L 300 100 L 200 300 z", 4),
+            ("M100,200 C100,100 250,100 250,200 S400,300 400,200", 3),
+            ("M200,300 Q400,50 600,300 T1000,300", 3),
+            ("M300,200 h-150 a150,150 0 1,0 150,-150 z", 4),
+            ("M275,175 v-150 a150,150 0 0,0 -150,150 z", 4),
+            (
+                "M600,350 l 50,-25 a25,25 -30 0,1 50,-25 l 50,-25 a25,50 -30 0,1 50,-25 l 50,-25",
+                6,
+            ),
+            ("M100,200 C100,100 400,100 400,200", 2),
+            ("M 10 315\n L 110 215\n A 30 50 0 0 1 162.55 162.45\n L 172.55 152.45\n Z", 5),
+        ];
+        for (input, len) in cases {
+            assert_eq!(parse(input).len(), len, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_implicit_commands() {
+        // svg 1.1 8.3.2: "M 100 100 L 200 200" == "M100 100L200 200"
+        assert_same("M100 100L200 200", "M 100 100 L 200 200");
+        // "M 100 200 L 200 100 L -100 -200" == "M 100 200 L 200 100 -100 -200"
+        assert_same("M 100 200 L 200 100 -100 -200", "M 100 200 L 200 100 L -100 -200");
+        assert_same("L1 2 3 4", "L1 2 L3 4");
+        assert_same("h1 2 3", "h1 h2 h3");
+        assert_same("V1,2", "V1 V2");
+        assert_same("c1 1 2 2 3 3 4 4 5 5 6 6", "c1 1 2 2 3 3 c4 4 5 5 6 6");
+        assert_same("S1 1 2 2 3 3 4 4", "S1 1 2 2 S3 3 4 4");
+        assert_same("q1 1 2 2 3 3 4 4", "q1 1 2 2 q3 3 4 4");
+        assert_same("T1 1 2 2", "T1 1 T2 2");
+        assert_same(
+            "A1 1 0 0 0 2 2 3 3 0 1 1 4 4",
+            "A1 1 0 0 0 2 2 A3 3 0 1 1 4 4",
+        );
+        // moveto followed by several pairs: the others are implicit linetos
+        assert_same("M0 0 10 10 20 0", "M0 0 L10 10 L20 0");
+        assert_same("m0 0 10 10", "m0 0 l10 10");
+    }
+
+    #[test]
+    fn test_compact_numbers() {
+        assert_same("M10-5", "M 10 -5");
+        assert_same("M 100-200", "M 100 -200");
+        assert_same("M 0.6.5", "M 0.6 0.5");
+        assert_same("M1.5.5", "M 1.5 0.5");
+        assert_same("M-.5-.5l.5.5", "M -0.5 -0.5 l 0.5 0.5");
+        assert_same("M1e2 1E-1", "M 100 0.1");
+        assert_same("M+1 +2", "M 1 2");
+        assert_same("M1.,2.", "M 1 2");
+        assert_same("C1,2,3,4,5,6", "C 1 2 3 4 5 6");
+        assert_same("M 1 , 2", "M 1 2");
+        assert_same("M\t1\r\n2z", "M 1 2 z");
+        assert_same("M0 0zm1 1z", "M 0 0 z m 1 1 z");
+    }
+
+    #[test]
+    fn test_packed_arc_flags() {
+        assert_same("a1 1 0 00.5.5", "a 1 1 0 0 0 0.5 0.5");
+        assert_same("A5 5 30 1110 10", "A 5 5 30 1 1 10 10");
+        assert_same("a1,1,0,1,0,2,2", "a 1 1 0 1 0 2 2");
+        let d = parse("a1 1 0 00.5.5");
+        assert_eq!(
+            d.0[0],
+            Command::EllipticalArc(A {
+                rx: 1.0,
+                ry: 1.0,
+                angle: 0.0,
+                large_arc_flag: false,
+                sweep_flag: false,
+                x: 0.5,
+                y: 0.5,
+                relative: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        // parsing stops at the first error, the rest is left for the caller
+        assert_eq!(PathData::from_str("M 10").unwrap().0, "M 10");
+        assert_eq!(PathData::from_str("M0 0 L").unwrap().0, "L");
+        assert_eq!(PathData::from_str("M0 0 L 1 2 3").unwrap().0, "3");
+        assert_eq!(PathData::from_str("A1 1 0 2 0 1 1").unwrap().0, "A1 1 0 2 0 1 1");
+        assert_eq!(PathData::from_str("M1e 2").unwrap().0, "M1e 2");
+        // numbers out of the range of f32 would be written back as `inf`
+        assert_eq!(PathData::from_str("M1e39 0").unwrap().0, "M1e39 0");
+        assert_eq!(PathData::from_str("M0 0 L-1e39 0").unwrap().0, "L-1e39 0");
+    }
+}
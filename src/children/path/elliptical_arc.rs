@@ -1,12 +1,20 @@
 use std::fmt::Display;
 
-use crate::parser::{bool_flag, point, trim};
-use nom::branch::alt;
+use crate::error::IResult;
+use crate::parser::{bool_flag, command, number, point, sep};
 
-use nom::{bytes::complete::tag, number::complete::float, sequence::pair};
+use nom::{
+    character::complete::multispace0,
+    sequence::{preceded, tuple},
+};
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use super::C;
 
 /// Draw an Arc curve from the current point to the coordinate x,y.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct A {
     /// rx and ry are the two radii of the ellipse;
     pub rx: f32,
@@ -42,15 +50,24 @@ impl Display for A {
 }
 
 impl A {
-    pub fn from_str(s: &str) -> nom::IResult<&str, A> {
-        let (s, (relative, ((rx, ry), (angle, (large_arc_flag, (sweep_flag, (x, y))))))) =
-            trim(pair(
-                trim(alt((tag("a"), tag("A")))),
-                trim(pair(
-                    point,
-                    pair(float, pair(bool_flag, pair(bool_flag, point))),
-                )),
-            ))(s)?;
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, A> {
+        let (s, relative) = command('a')(s)?;
+        let (s, a) = A::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, a))
+    }
+    /// parse the arguments after the command letter: `rx ry angle large-arc-flag sweep-flag x,y`
+    ///
+    /// flags are a single `0` or `1` and do not need a separator after them
+    pub fn args(s: &str, relative: bool) -> IResult<&str, A> {
+        let (s, ((rx, ry), angle, large_arc_flag, sweep_flag, (x, y))) = tuple((
+            point,
+            preceded(sep, number),
+            preceded(sep, bool_flag),
+            preceded(sep, bool_flag),
+            preceded(sep, point),
+        ))(s)?;
 
         Ok((
             s,
@@ -58,19 +75,167 @@ impl A {
                 rx,
                 ry,
                 angle,
-                large_arc_flag: large_arc_flag,
-                sweep_flag: sweep_flag,
+                large_arc_flag,
+                sweep_flag,
                 x,
                 y,
-                relative: relative == "a",
+                relative,
             },
         ))
     }
 }
 
+/// The center parameterization of an arc, see the implementation notes of svg (F.6.5).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcCenter {
+    pub cx: f32,
+    pub cy: f32,
+    /// radii after the correction of out-of-range radii, always positive
+    pub rx: f32,
+    pub ry: f32,
+    /// rotation of the ellipse in radians
+    pub angle: f32,
+    /// angle of the start point in radians, on the unrotated ellipse
+    pub start: f32,
+    /// the swept angle in radians, positive when `sweep_flag` is set
+    pub sweep: f32,
+}
+
+impl ArcCenter {
+    /// the point of the ellipse at the angle `theta`
+    pub fn point(&self, theta: f32) -> (f32, f32) {
+        let (sin, cos) = self.angle.sin_cos();
+        let (x, y) = (self.rx * theta.cos(), self.ry * theta.sin());
+        (self.cx + cos * x - sin * y, self.cy + sin * x + cos * y)
+    }
+    /// the cubic beziers approximating the arc, one per quarter turn at most
+    pub fn to_cubics(&self) -> Vec<C> {
+        let count = (self.sweep.abs() / FRAC_PI_2 - 1e-3).ceil().max(1_f32) as usize;
+        let step = self.sweep / count as f32;
+        // length of the tangents of a bezier approximating an arc of `step` on the unit circle
+        let alpha = 4_f32 / 3_f32 * (step / 4_f32).tan();
+        (0..count)
+            .map(|index| {
+                let theta1 = self.start + step * index as f32;
+                let theta2 = theta1 + step;
+                let (x1, y1) = self.point(theta1);
+                let (x, y) = self.point(theta2);
+                let (d1x, d1y) = self.derivative(theta1);
+                let (d2x, d2y) = self.derivative(theta2);
+                C {
+                    x1: x1 + alpha * d1x,
+                    y1: y1 + alpha * d1y,
+                    x2: x - alpha * d2x,
+                    y2: y - alpha * d2y,
+                    x,
+                    y,
+                    relative: false,
+                }
+            })
+            .collect()
+    }
+    /// the derivative of [`ArcCenter::point`] at the angle `theta`
+    pub fn derivative(&self, theta: f32) -> (f32, f32) {
+        let (sin, cos) = self.angle.sin_cos();
+        let (x, y) = (-self.rx * theta.sin(), self.ry * theta.cos());
+        (cos * x - sin * y, sin * x + cos * y)
+    }
+}
+
+impl A {
+    /// the absolute end point of the arc starting at `from`
+    pub fn end(&self, from: (f32, f32)) -> (f32, f32) {
+        match self.relative {
+            true => (from.0 + self.x, from.1 + self.y),
+            false => (self.x, self.y),
+        }
+    }
+    /// the center parameterization of the arc starting at `from`.
+    ///
+    /// `None` if the arc is a straight line (a radius is zero) or nothing (it ends where it starts),
+    /// radii too small to reach the end point are scaled up (F.6.6)
+    pub fn center(&self, from: (f32, f32)) -> Option<ArcCenter> {
+        let (x1, y1) = from;
+        let (x2, y2) = self.end(from);
+        let (mut rx, mut ry) = (self.rx.abs(), self.ry.abs());
+        if (x1 == x2 && y1 == y2) || rx == 0_f32 || ry == 0_f32 {
+            return None;
+        }
+        let angle = self.angle.to_radians();
+        let (sin, cos) = angle.sin_cos();
+        let (dx, dy) = ((x1 - x2) / 2_f32, (y1 - y2) / 2_f32);
+        let x1p = cos * dx + sin * dy;
+        let y1p = -sin * dx + cos * dy;
+
+        let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+        if lambda > 1_f32 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+        let numerator = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+        let denominator = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+        let mut coefficient = (numerator / denominator).max(0_f32).sqrt();
+        if self.large_arc_flag == self.sweep_flag {
+            coefficient = -coefficient;
+        }
+        let cxp = coefficient * rx * y1p / ry;
+        let cyp = -coefficient * ry * x1p / rx;
+
+        let vector_angle = |(ux, uy): (f32, f32), (vx, vy): (f32, f32)| {
+            (ux * vy - uy * vx).atan2(ux * vx + uy * vy)
+        };
+        let u = ((x1p - cxp) / rx, (y1p - cyp) / ry);
+        let v = ((-x1p - cxp) / rx, (-y1p - cyp) / ry);
+        let start = vector_angle((1_f32, 0_f32), u);
+        let mut sweep = vector_angle(u, v) % TAU;
+        if !self.sweep_flag && sweep > 0_f32 {
+            sweep -= TAU;
+        } else if self.sweep_flag && sweep < 0_f32 {
+            sweep += TAU;
+        }
+
+        Some(ArcCenter {
+            cx: cos * cxp - sin * cyp + (x1 + x2) / 2_f32,
+            cy: sin * cxp + cos * cyp + (y1 + y2) / 2_f32,
+            rx,
+            ry,
+            angle,
+            start,
+            sweep,
+        })
+    }
+    /// the cubic beziers approximating the arc starting at `from`, one per quarter turn at most.
+    ///
+    /// an arc with a zero radius is a single straight "curve", an arc ending where it starts is nothing
+    pub fn to_cubics(&self, from: (f32, f32)) -> Vec<C> {
+        let end = self.end(from);
+        let Some(arc) = self.center(from) else {
+            if from == end {
+                return vec![];
+            }
+            return vec![C {
+                x1: from.0,
+                y1: from.1,
+                x2: end.0,
+                y2: end.1,
+                x: end.0,
+                y: end.1,
+                relative: false,
+            }];
+        };
+        let mut cubics = arc.to_cubics();
+        // exactly the end point of the arc, not a rounding error away from it
+        if let Some(last) = cubics.last_mut() {
+            (last.x, last.y) = end;
+        }
+        cubics
+    }
+}
+
 #[cfg(test)]
 mod test_elliptical_arc {
     use super::*;
+    use std::f32::consts::PI;
     #[test]
     fn test_a() {
         assert_eq!(
@@ -107,4 +272,63 @@ mod test_elliptical_arc {
             ))
         );
     }
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_center() {
+        // a half circle, clockwise (in the y-down system) from (0,0) to (2,0) over (1,-1)
+        let a = A::from_str("A 1 1 0 0 1 2 0").unwrap().1;
+        let arc = a.center((0.0, 0.0)).unwrap();
+        assert!(close((arc.cx, arc.cy), (1.0, 0.0)));
+        assert!(close((arc.start, arc.sweep), (PI, PI)));
+        assert!(close(arc.point(arc.start + arc.sweep / 2.0), (1.0, -1.0)));
+        // the other half
+        let a = A::from_str("A 1 1 0 0 0 2 0").unwrap().1;
+        let arc = a.center((0.0, 0.0)).unwrap();
+        assert!(close((arc.start, arc.sweep), (PI, -PI)));
+        assert!(close(arc.point(arc.start + arc.sweep / 2.0), (1.0, 1.0)));
+        // radii too small are scaled up
+        let a = A::from_str("a 0.5 0.5 0 0 1 2 0").unwrap().1;
+        let arc = a.center((1.0, 1.0)).unwrap();
+        assert!(close((arc.rx, arc.ry), (1.0, 1.0)));
+        assert!(close((arc.cx, arc.cy), (2.0, 1.0)));
+        // a large arc on a rotated ellipse
+        let a = A::from_str("A 2 1 30 1 0 1 1").unwrap().1;
+        let arc = a.center((0.0, 0.0)).unwrap();
+        assert!(arc.sweep < -PI);
+        assert!(close(arc.point(arc.start), (0.0, 0.0)));
+        assert!(close(arc.point(arc.start + arc.sweep), (1.0, 1.0)));
+
+        assert!(A::from_str("A 0 1 0 0 1 2 0").unwrap().1.center((0.0, 0.0)).is_none());
+        assert!(A::from_str("a 1 1 0 0 1 0 0").unwrap().1.center((3.0, 3.0)).is_none());
+    }
+
+    #[test]
+    fn test_to_cubics() {
+        let a = A::from_str("A 1 1 0 1 1 2 0").unwrap().1;
+        let cubics = a.to_cubics((2.0, 0.0));
+        assert!(cubics.is_empty());
+        // a full turn but the last bit
+        let a = A::from_str("A 10 10 0 1 1 0 -1").unwrap().1;
+        let cubics = a.to_cubics((1.0, 0.0));
+        assert_eq!(cubics.len(), 4);
+        assert_eq!((cubics[3].x, cubics[3].y), (0.0, -1.0));
+        // a quarter of the unit circle, the standard approximation
+        let a = A::from_str("A 1 1 0 0 1 0 1").unwrap().1;
+        let cubics = a.to_cubics((1.0, 0.0));
+        assert_eq!(cubics.len(), 1);
+        let kappa = 0.552_284_8;
+        assert!(close((cubics[0].x1, cubics[0].y1), (1.0, kappa)));
+        assert!(close((cubics[0].x2, cubics[0].y2), (kappa, 1.0)));
+        // a zero radius is a line
+        let a = A::from_str("a 0 1 0 0 1 2 2").unwrap().1;
+        let cubics = a.to_cubics((1.0, 1.0));
+        assert_eq!(
+            (cubics[0].x1, cubics[0].y1, cubics[0].x2, cubics[0].y2, cubics[0].x, cubics[0].y),
+            (1.0, 1.0, 3.0, 3.0, 3.0, 3.0)
+        );
+    }
 }
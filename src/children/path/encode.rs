@@ -0,0 +1,399 @@
+use super::{Command, CubicBezier, Cursor, LineTo, PathData, QuadraticBezier, A, C, H, L, M, Q, S, T, V};
+
+type Point = (f32, f32);
+
+/// `value` rounded to `precision` decimals, without a negative zero
+pub(crate) fn round(value: f32, precision: Option<u8>) -> f32 {
+    let rounded = match precision {
+        Some(decimals) => {
+            let scaled = value * 10_f32.powi(decimals as i32);
+            // from 2^23 on every f32 is an integer, there are no decimals left to round
+            match scaled.abs() < 8_388_608_f32 {
+                true => scaled.round() / 10_f32.powi(decimals as i32),
+                false => value,
+            }
+        }
+        None => value,
+    };
+    match rounded == 0_f32 {
+        true => 0_f32,
+        false => rounded,
+    }
+}
+
+/// whether two points are the same but for the errors of float operations
+fn same(a: Point, b: Point) -> bool {
+    let close = |a: f32, b: f32| (a - b).abs() <= 8_f32 * f32::EPSILON * a.abs().max(b.abs()).max(1_f32);
+    close(a.0, b.0) && close(a.1, b.1)
+}
+
+/// the reflection of `control` about `point`, the first control point of `S` and `T`
+fn reflect(control: Point, point: Point) -> Point {
+    (2_f32 * point.0 - control.0, 2_f32 * point.1 - control.1)
+}
+
+/// a number without its leading zero: `.5`, `-.5`
+fn number(value: f32) -> String {
+    let written = value.to_string();
+    match (written.strip_prefix("0."), written.strip_prefix("-0.")) {
+        (Some(fraction), _) => format!(".{}", fraction),
+        (_, Some(fraction)) => format!("-.{}", fraction),
+        _ => written,
+    }
+}
+
+/// an argument as it is written, arc flags need no separator around them
+#[derive(Debug, Clone)]
+enum Arg {
+    Number(String),
+    Flag(bool),
+}
+
+/// the letter and the arguments of a command
+fn args(command: &Command) -> (char, Vec<Arg>) {
+    let numbers = |values: &[f32]| values.iter().map(|v| Arg::Number(number(*v))).collect::<Vec<_>>();
+    let (letter, relative, args) = match command {
+        Command::MoveTo(m) => ('M', m.relative, numbers(&[m.x, m.y])),
+        Command::LineTo(LineTo::L(l)) => ('L', l.relative, numbers(&[l.x, l.y])),
+        Command::LineTo(LineTo::H(h)) => ('H', h.relative, numbers(&[h.x])),
+        Command::LineTo(LineTo::V(v)) => ('V', v.relative, numbers(&[v.y])),
+        Command::CubicBezier(CubicBezier::C(c)) => {
+            ('C', c.relative, numbers(&[c.x1, c.y1, c.x2, c.y2, c.x, c.y]))
+        }
+        Command::CubicBezier(CubicBezier::S(s)) => ('S', s.relative, numbers(&[s.x2, s.y2, s.x, s.y])),
+        Command::QuadraticBezier(QuadraticBezier::Q(q)) => ('Q', q.relative, numbers(&[q.x1, q.y1, q.x, q.y])),
+        Command::QuadraticBezier(QuadraticBezier::T(t)) => ('T', t.relative, numbers(&[t.x, t.y])),
+        Command::EllipticalArc(a) => {
+            let mut args = numbers(&[a.rx, a.ry, a.angle]);
+            args.push(Arg::Flag(a.large_arc_flag));
+            args.push(Arg::Flag(a.sweep_flag));
+            args.extend(numbers(&[a.x, a.y]));
+            ('A', a.relative, args)
+        }
+        Command::ClosePath(z) => ('Z', z.0, vec![]),
+    };
+    match relative {
+        true => (letter.to_ascii_lowercase(), args),
+        false => (letter, args),
+    }
+}
+
+/// whether `next` would continue the number `last` without a separator
+fn needs_separator(last: Option<&Arg>, next: &str) -> bool {
+    match last {
+        Some(Arg::Number(last)) => !(next.starts_with('-') || (next.starts_with('.') && last.contains('.'))),
+        None | Some(Arg::Flag(_)) => false,
+    }
+}
+
+/// the path data written so far
+#[derive(Debug, Default)]
+struct Encoder {
+    out: String,
+    /// the letter which a following command can leave out, `L` after `M`
+    repeat: Option<char>,
+    last: Option<Arg>,
+}
+
+impl Encoder {
+    /// what `command` adds to the path data
+    fn fragment(&self, command: &Command) -> String {
+        let (letter, args) = args(command);
+        let mut fragment = String::new();
+        let mut last = match self.repeat == Some(letter) && !args.is_empty() {
+            true => self.last.clone(),
+            false => {
+                fragment.push(letter);
+                None
+            }
+        };
+        for arg in args {
+            let text = match arg {
+                Arg::Number(ref number) => number.clone(),
+                Arg::Flag(flag) => if flag { "1" } else { "0" }.to_string(),
+            };
+            if needs_separator(last.as_ref(), &text) {
+                fragment.push(' ');
+            }
+            fragment.push_str(&text);
+            last = Some(arg);
+        }
+        fragment
+    }
+    fn push(&mut self, command: &Command) {
+        let fragment = self.fragment(command);
+        self.out.push_str(&fragment);
+        let (letter, mut args) = args(command);
+        self.repeat = match letter {
+            'M' => Some('L'),
+            'm' => Some('l'),
+            'Z' | 'z' => None,
+            letter => Some(letter),
+        };
+        self.last = args.pop();
+    }
+}
+
+impl Command {
+    /// every number of the command mapped by `f`, the flags of arcs are kept
+    pub(crate) fn map_numbers(&self, f: impl Fn(f32) -> f32) -> Command {
+        match *self {
+            Command::MoveTo(m) => M {
+                x: f(m.x),
+                y: f(m.y),
+                ..m
+            }
+            .into(),
+            Command::LineTo(LineTo::L(l)) => L {
+                x: f(l.x),
+                y: f(l.y),
+                ..l
+            }
+            .into(),
+            Command::LineTo(LineTo::H(h)) => H { x: f(h.x), ..h }.into(),
+            Command::LineTo(LineTo::V(v)) => V { y: f(v.y), ..v }.into(),
+            Command::CubicBezier(CubicBezier::C(c)) => C {
+                x1: f(c.x1),
+                y1: f(c.y1),
+                x2: f(c.x2),
+                y2: f(c.y2),
+                x: f(c.x),
+                y: f(c.y),
+                ..c
+            }
+            .into(),
+            Command::CubicBezier(CubicBezier::S(s)) => S {
+                x2: f(s.x2),
+                y2: f(s.y2),
+                x: f(s.x),
+                y: f(s.y),
+                ..s
+            }
+            .into(),
+            Command::QuadraticBezier(QuadraticBezier::Q(q)) => Q {
+                x1: f(q.x1),
+                y1: f(q.y1),
+                x: f(q.x),
+                y: f(q.y),
+                ..q
+            }
+            .into(),
+            Command::QuadraticBezier(QuadraticBezier::T(t)) => T {
+                x: f(t.x),
+                y: f(t.y),
+                ..t
+            }
+            .into(),
+            Command::EllipticalArc(a) => A {
+                rx: f(a.rx),
+                ry: f(a.ry),
+                angle: f(a.angle),
+                x: f(a.x),
+                y: f(a.y),
+                ..a
+            }
+            .into(),
+            Command::ClosePath(z) => z.into(),
+        }
+    }
+}
+
+impl PathData {
+    /// the shortest path data string drawing the same path, with numbers rounded to `precision`
+    /// decimals, `None` keeps them as they are.
+    ///
+    /// repeated command letters and separators which are not needed are left out (`M10-5`, `.5.5`),
+    /// numbers lose their leading zero, each command is written absolute or relative, whichever
+    /// is shorter, and lines and curves become `H`, `V`, `S` or `T` where those draw the same.
+    /// every point of the result is within half a unit of the last decimal of the original one
+    ///
+    /// ## Example
+    /// ```rust
+    /// use svg_icon::children::PathData;
+    ///
+    /// let (_, d) = PathData::from_str("M 10,10 L 20,10 L 20,20.5 C 20,25 25,30 30,30 C 35,30 40,25 40,20").unwrap();
+    /// assert_eq!(d.encode(Some(3)), "M10 10H20V20.5C20 25 25 30 30 30s10-5 10-10");
+    /// let (_, d) = PathData::from_str("M 0.5,0.5 L 0.25,-0.3333333").unwrap();
+    /// assert_eq!(d.encode(Some(2)), "M.5.5.25-.33");
+    /// ```
+    pub fn encode(&self, precision: Option<u8>) -> String {
+        let mut encoder = Encoder::default();
+        // the path as it is, and as it is read back from what is written
+        let (mut source, mut target) = (Cursor::default(), Cursor::default());
+        // the last control points of cubic and quadratic curves, for `S` and `T`
+        let (mut source_cubic, mut source_quad): (Option<Point>, Option<Point>) = (None, None);
+        let (mut target_cubic, mut target_quad): (Option<Point>, Option<Point>) = (None, None);
+        for command in self.iter() {
+            let from = source.current;
+            let full = match source.absolute(command) {
+                Command::LineTo(_) => L {
+                    x: source.current.0,
+                    y: source.current.1,
+                    relative: false,
+                }
+                .into(),
+                Command::CubicBezier(CubicBezier::S(s)) => {
+                    let (x1, y1) = source_cubic.map_or(from, |control| reflect(control, from));
+                    C {
+                        x1,
+                        y1,
+                        x2: s.x2,
+                        y2: s.y2,
+                        x: s.x,
+                        y: s.y,
+                        relative: false,
+                    }
+                    .into()
+                }
+                Command::QuadraticBezier(QuadraticBezier::T(t)) => {
+                    let (x1, y1) = source_quad.map_or(from, |control| reflect(control, from));
+                    Q {
+                        x1,
+                        y1,
+                        x: t.x,
+                        y: t.y,
+                        relative: false,
+                    }
+                    .into()
+                }
+                absolute => absolute,
+            };
+            (source_cubic, source_quad) = match full {
+                Command::CubicBezier(CubicBezier::C(c)) => (Some((c.x2, c.y2)), None),
+                Command::QuadraticBezier(QuadraticBezier::Q(q)) => (None, Some((q.x1, q.y1))),
+                _ => (None, None),
+            };
+
+            let current = target.current;
+            let reflected_cubic = target_cubic.map_or(current, |control| reflect(control, current));
+            let reflected_quad = target_quad.map_or(current, |control| reflect(control, current));
+            let short = match full.map_numbers(|v| round(v, precision)) {
+                Command::LineTo(LineTo::L(l)) if l.y == current.1 => H { x: l.x, relative: false }.into(),
+                Command::LineTo(LineTo::L(l)) if l.x == current.0 => V { y: l.y, relative: false }.into(),
+                Command::CubicBezier(CubicBezier::C(c)) if same(reflected_cubic, (c.x1, c.y1)) => S {
+                    x2: c.x2,
+                    y2: c.y2,
+                    x: c.x,
+                    y: c.y,
+                    relative: false,
+                }
+                .into(),
+                Command::QuadraticBezier(QuadraticBezier::Q(q)) if same(reflected_quad, (q.x1, q.y1)) => T {
+                    x: q.x,
+                    y: q.y,
+                    relative: false,
+                }
+                .into(),
+                rounded => rounded,
+            };
+            (target_cubic, target_quad) = match short {
+                Command::CubicBezier(CubicBezier::C(C { x2, y2, .. }))
+                | Command::CubicBezier(CubicBezier::S(S { x2, y2, .. })) => (Some((x2, y2)), None),
+                Command::QuadraticBezier(QuadraticBezier::Q(q)) => (None, Some((q.x1, q.y1))),
+                Command::QuadraticBezier(QuadraticBezier::T(_)) => (None, Some(reflected_quad)),
+                _ => (None, None),
+            };
+
+            // rounded again, the difference of two rounded numbers may not be
+            let relative = { target }.relative(&short).map_numbers(|v| round(v, precision));
+            target.absolute(&short);
+            match encoder.fragment(&relative).len() < encoder.fragment(&short).len() {
+                true => encoder.push(&relative),
+                false => encoder.push(&short),
+            }
+        }
+        encoder.out
+    }
+}
+
+#[cfg(test)]
+mod test_encode {
+    use super::*;
+    use crate::children::Segment;
+
+    fn parse(s: &str) -> PathData {
+        let (rest, d) = PathData::from_str(s).unwrap();
+        assert_eq!(rest, "");
+        d
+    }
+
+    /// the encoded path parses back to the same geometry, arcs are only compared at their ends
+    /// as a small move of an end point can move their center a lot
+    fn assert_same_geometry(s: &str, precision: Option<u8>, tolerance: f32) {
+        let d = parse(s);
+        let encoded = d.encode(precision);
+        let decoded = parse(&encoded);
+        let (segments, decoded_segments): (Vec<_>, Vec<_>) = (d.segments().collect(), decoded.segments().collect());
+        assert_eq!(segments.len(), decoded_segments.len(), "{} -> {}", s, encoded);
+        for (a, b) in segments.iter().zip(decoded_segments.iter()) {
+            let ts: &[f32] = match a {
+                Segment::Arc { .. } => &[0_f32, 1_f32],
+                _ => &[0_f32, 0.25, 0.5, 0.75, 1_f32],
+            };
+            for &t in ts {
+                let ((ax, ay), (bx, by)) = (a.point(t), b.point(t));
+                assert!(
+                    (ax - bx).abs() <= tolerance && (ay - by).abs() <= tolerance,
+                    "{} -> {}: {:?} != {:?}",
+                    s,
+                    encoded,
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_separators() {
+        assert_eq!(parse("M 10,-5 L 20,20 L 30,10").encode(None), "M10-5 20 20 30 10");
+        assert_eq!(parse("M 0.5,0.5 L -0.5,0.7").encode(None), "M.5.5-.5.7");
+        assert_eq!(parse("M 1,0.5 l 0.5,0.5").encode(None), "M1 .5l.5.5");
+        assert_eq!(parse("M 0,0 A 5 5 0 1 0 -0.5,0.5").encode(None), "M0 0A5 5 0 10-.5.5");
+        // a second moveto needs its letter, the arguments after `M` are linetos
+        assert_eq!(parse("M 0,0 M 1,1 L 2,2 Z M 5,5 l 1,2 z").encode(None), "M0 0M1 1 2 2ZM5 5 6 7Z");
+    }
+
+    #[test]
+    fn test_shortest_commands() {
+        assert_eq!(parse("M100 100 L101 100 L101 0 L 100 100 Z").encode(None), "M100 100h1V0l-1 100Z");
+        assert_eq!(
+            parse("M0 0 C0 1 1 2 2 2 C3 2 4 1 4 0 Q5 -2 6 0 Q7 2 8 0 T10 0").encode(None),
+            "M0 0C0 1 1 2 2 2S4 1 4 0Q5-2 6 0T8 0t2 0"
+        );
+        // without a previous curve the first control point of `S` is the current point
+        assert_eq!(parse("M1 1 C1 1 2 2 3 1").encode(None), "M1 1S2 2 3 1");
+        assert_eq!(parse("M0 0 s1 1 2 0 t2 2").encode(None), "M0 0S1 1 2 0T4 2");
+    }
+
+    #[test]
+    fn test_precision() {
+        assert_eq!(parse("M0.12345 0.5 l0.33333 0.33333 H-0.0001").encode(Some(2)), "M.12.5.46.83H0");
+        assert_eq!(parse("M 1.23456789,0").encode(None), "M1.2345679 0");
+        assert_eq!(parse("M 1.23456789,0").encode(Some(0)), "M1 0");
+        // more decimals than a f32 has keep the numbers
+        for precision in [39, u8::MAX] {
+            let d = parse("M0 0 L1.5 2 l.25 3e30");
+            assert_eq!(d.encode(Some(precision)), d.encode(None));
+            assert_eq!(d.round(precision), d);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let paths = [
+            "M12 22L24 19L36 16",
+            "m10 10 l5 5 h5 v-5 c1 1 2 2 3 3 s1 1 2 2 q1 1 2 2 t2 2 a1 1 0 0 1 2 0 z m1 1 l1 0 z l2 2",
+            "M0.1 0.2 C0.3 0.4 0.5 0.6 0.7 0.8 S1.1 1.2 1.3 1.4 Q2 2 3 -3 T4.5 4.5 T5 5",
+            "M-1.5 -2.25 a3 2 30 1 1 4 4 A3 2 -30 0 0 -1.5 -2.25 Z",
+            "M100.123 200.456 L100.123 300 L-50 300 Z",
+        ];
+        for path in paths {
+            assert_same_geometry(path, None, 1e-4);
+            assert_same_geometry(path, Some(3), 1e-3);
+            assert_same_geometry(path, Some(1), 0.1);
+            let d = parse(path);
+            assert!(d.encode(Some(3)).len() < d.to_string().len());
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use std::fmt::Display;
 
-use crate::parser::{point, trim};
+use crate::parser::{command, number, point};
 use nom::branch::alt;
 
 use nom::combinator::map;
 
-use nom::{bytes::complete::tag, number::complete::float, sequence::pair};
+use crate::error::IResult;
+use nom::character::complete::multispace0;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum LineTo {
@@ -25,7 +26,8 @@ impl Display for LineTo {
 }
 
 impl LineTo {
-    pub fn from_str(s: &str) -> nom::IResult<&str, LineTo> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, LineTo> {
         alt((
             // L::from_str, H::from_str, V::from_str
             map(L::from_str, |v| v.into()),
@@ -54,7 +56,8 @@ impl From<V> for LineTo {
 }
 
 /// L = lineto (create a line)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct L {
     pub x: f32,
     pub y: f32,
@@ -74,22 +77,23 @@ impl Display for L {
 }
 
 impl L {
-    pub fn from_str(s: &str) -> nom::IResult<&str, L> {
-        let (s, (relative, (x, y))) = trim(pair(trim(alt((tag("l"), tag("L")))), point))(s)?;
-
-        Ok((
-            s,
-            L {
-                x,
-                y,
-                relative: relative == "l",
-            },
-        ))
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, L> {
+        let (s, relative) = command('l')(s)?;
+        let (s, l) = L::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, l))
+    }
+    /// parse the arguments after the command letter: `x,y`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, L> {
+        let (s, (x, y)) = point(s)?;
+        Ok((s, L { x, y, relative }))
     }
 }
 
 /// H = horizontal lineto (create a horizontal line)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct H {
     pub x: f32,
     pub relative: bool,
@@ -102,21 +106,23 @@ impl Display for H {
 }
 
 impl H {
-    pub fn from_str(s: &str) -> nom::IResult<&str, H> {
-        let (s, (relative, x)) = trim(pair(trim(alt((tag("h"), tag("H")))), float))(s)?;
-
-        Ok((
-            s,
-            H {
-                x,
-                relative: relative == "h",
-            },
-        ))
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, H> {
+        let (s, relative) = command('h')(s)?;
+        let (s, h) = H::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, h))
+    }
+    /// parse the arguments after the command letter: `x`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, H> {
+        let (s, x) = number(s)?;
+        Ok((s, H { x, relative }))
     }
 }
 
 /// V = vertical lineto (create a vertical line)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct V {
     pub y: f32,
     pub relative: bool,
@@ -129,16 +135,17 @@ impl Display for V {
 }
 
 impl V {
-    pub fn from_str(s: &str) -> nom::IResult<&str, V> {
-        let (s, (relative, y)) = trim(pair(trim(alt((tag("v"), tag("V")))), float))(s)?;
-
-        Ok((
-            s,
-            V {
-                y,
-                relative: relative == "v",
-            },
-        ))
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, V> {
+        let (s, relative) = command('v')(s)?;
+        let (s, v) = V::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, v))
+    }
+    /// parse the arguments after the command letter: `y`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, V> {
+        let (s, y) = number(s)?;
+        Ok((s, V { y, relative }))
     }
 }
 
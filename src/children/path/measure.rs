@@ -0,0 +1,250 @@
+use super::{PathData, Segment};
+
+type Point = (f32, f32);
+
+/// nodes and weights of the 5 point Gauss-Legendre quadrature on `-1..=1`
+const GAUSS_LEGENDRE: [(f32, f32); 5] = [
+    (0_f32, 0.568_888_9),
+    (-0.538_469_3, 0.478_628_67),
+    (0.538_469_3, 0.478_628_67),
+    (-0.906_179_8, 0.236_926_89),
+    (0.906_179_8, 0.236_926_89),
+];
+
+/// the deepest subdivision of a segment when its length is integrated
+const MAX_DEPTH: u32 = 12;
+
+impl Segment {
+    /// the speed `|B'(t)|` integrated over `a..b` with the Gauss-Legendre quadrature
+    fn quadrature(&self, a: f32, b: f32) -> f32 {
+        let (half, middle) = ((b - a) / 2_f32, (a + b) / 2_f32);
+        GAUSS_LEGENDRE
+            .iter()
+            .map(|(node, weight)| {
+                let (x, y) = self.derivative(middle + half * node);
+                weight * x.hypot(y)
+            })
+            .sum::<f32>()
+            * half
+    }
+    /// the length from `a` to `b`, split in halves until they agree within `tolerance`
+    fn integrate(&self, a: f32, b: f32, whole: f32, tolerance: f32, depth: u32) -> f32 {
+        let middle = (a + b) / 2_f32;
+        let (left, right) = (self.quadrature(a, middle), self.quadrature(middle, b));
+        if depth == 0 || (left + right - whole).abs() <= tolerance {
+            return left + right;
+        }
+        self.integrate(a, middle, left, tolerance / 2_f32, depth - 1)
+            + self.integrate(middle, b, right, tolerance / 2_f32, depth - 1)
+    }
+    /// the length of the segment from its start to `t` in `0..=1`, within `tolerance`
+    pub fn length_to(&self, t: f32, tolerance: f32) -> f32 {
+        let t = t.clamp(0_f32, 1_f32);
+        match *self {
+            Segment::Move { .. } => 0_f32,
+            Segment::Line { from, to } | Segment::Close { from, to } => {
+                (to.0 - from.0).hypot(to.1 - from.1) * t
+            }
+            // a circle has no need of an integral
+            Segment::Arc { arc, .. } if arc.rx == arc.ry => arc.rx * (arc.sweep * t).abs(),
+            _ => self.integrate(0_f32, t, self.quadrature(0_f32, t), tolerance, MAX_DEPTH),
+        }
+    }
+    /// the length of the whole segment, within `tolerance`
+    pub fn length(&self, tolerance: f32) -> f32 {
+        self.length_to(1_f32, tolerance)
+    }
+    /// the parameter where the length from the start is `length`, within `tolerance`
+    fn t_at(&self, length: f32, total: f32, tolerance: f32) -> f32 {
+        match *self {
+            _ if total <= 0_f32 => 0_f32,
+            Segment::Line { .. } | Segment::Close { .. } => length / total,
+            Segment::Arc { arc, .. } if arc.rx == arc.ry => length / total,
+            _ => {
+                // bisection, the length grows with `t`
+                let (mut low, mut high) = (0_f32, 1_f32);
+                let mut t = length / total;
+                for _ in 0..32 {
+                    let error = self.length_to(t, tolerance / 4_f32) - length;
+                    if error.abs() <= tolerance {
+                        break;
+                    }
+                    if error > 0_f32 {
+                        high = t;
+                    } else {
+                        low = t;
+                    }
+                    t = (low + high) / 2_f32;
+                }
+                t
+            }
+        }
+    }
+}
+
+/// The lengths of the segments of a path, to query positions along it like
+/// `getTotalLength()` and `getPointAtLength()` of the DOM.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::children::{PathData, PathMeasure};
+///
+/// let (_, d) = PathData::from_str("M0 0 h3 v4 z").unwrap();
+/// let measure = PathMeasure::new(&d, PathMeasure::DEFAULT_TOLERANCE);
+/// assert_eq!(measure.total_length(), 12.0);
+/// assert_eq!(measure.point_at(5.0), Some((3.0, 2.0)));
+/// assert_eq!(measure.tangent_at(5.0), Some((0.0, 1.0)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathMeasure {
+    /// every segment with the length of the path before it and its own length
+    segments: Vec<(Segment, f32, f32)>,
+    tolerance: f32,
+    total: f32,
+}
+
+impl PathMeasure {
+    /// the maximum error of lengths, in user units
+    pub const DEFAULT_TOLERANCE: f32 = 1e-3;
+
+    /// measure every segment of `d`, each one within `tolerance`
+    pub fn new(d: &PathData, tolerance: f32) -> Self {
+        let mut total = 0_f32;
+        let segments = d
+            .segments()
+            .map(|segment| {
+                let length = segment.length(tolerance);
+                let start = total;
+                total += length;
+                (segment, start, length)
+            })
+            .collect();
+        PathMeasure {
+            segments,
+            tolerance,
+            total,
+        }
+    }
+    pub fn total_length(&self) -> f32 {
+        self.total
+    }
+    /// the segments of the path with their lengths
+    pub fn segment_lengths(&self) -> impl Iterator<Item = (&Segment, f32)> + '_ {
+        self.segments.iter().map(|(segment, _, length)| (segment, *length))
+    }
+    /// the segment at `length` from the start, and the parameter in it.
+    /// `length` is clamped to the path, `None` if there is no segment or the path is too long
+    /// for its length to be a number (`M-3e38 0 H3e38`)
+    fn locate(&self, length: f32) -> Option<(&Segment, f32)> {
+        if !self.total.is_finite() {
+            return None;
+        }
+        let length = length.clamp(0_f32, self.total);
+        // the last segment starting before `length`, or the first one drawing anything
+        let index = self
+            .segments
+            .partition_point(|(_, start, own)| *start + *own < length)
+            .min(self.segments.len().checked_sub(1)?);
+        let (segment, start, own) = &self.segments[index];
+        Some((segment, segment.t_at(length - start, *own, self.tolerance)))
+    }
+    /// the point at `length` from the start of the path, like `getPointAtLength()`.
+    /// `None` if the path is empty or its length is not finite
+    pub fn point_at(&self, length: f32) -> Option<Point> {
+        let (segment, t) = self.locate(length)?;
+        Some(segment.point(t))
+    }
+    /// the direction of the path at `length` from its start, a unit vector.
+    /// `None` if the path has no length there
+    pub fn tangent_at(&self, length: f32) -> Option<Point> {
+        let (segment, t) = self.locate(length)?;
+        segment
+            .tangent_at(t)
+            .or_else(|| segment.tangent(t >= 0.5_f32))
+    }
+}
+
+impl PathData {
+    /// the length of the path, like `getTotalLength()`, within [`PathMeasure::DEFAULT_TOLERANCE`]
+    /// for every segment
+    pub fn length(&self) -> f32 {
+        PathMeasure::new(self, PathMeasure::DEFAULT_TOLERANCE).total_length()
+    }
+}
+
+#[cfg(test)]
+mod test_measure {
+    use super::*;
+    use std::f32::consts::PI;
+
+    fn path(s: &str) -> PathData {
+        let (rest, d) = PathData::from_str(s).unwrap();
+        assert_eq!(rest, "");
+        d
+    }
+
+    fn close(a: f32, b: f32, tolerance: f32) -> bool {
+        (a - b).abs() <= tolerance
+    }
+
+    #[test]
+    fn test_length() {
+        assert_eq!(path("M0 0 L3 4 m10 10 h-2 z").length(), 5.0 + 2.0 + 2.0);
+        assert_eq!(path("").length(), 0.0);
+        // a circle and an ellipse, the perimeter of an ellipse by the formula of Ramanujan
+        assert!(close(path("M1 0 A1 1 0 1 1 -1 0 A1 1 0 1 1 1 0").length(), 2.0 * PI, 1e-4));
+        let (a, b) = (3_f32, 1_f32);
+        let ellipse = PI * (3.0 * (a + b) - ((3.0 * a + b) * (a + 3.0 * b)).sqrt());
+        assert!(close(path("M3 0 A3 1 0 1 1 -3 0 A3 1 0 1 1 3 0").length(), ellipse, 1e-3));
+        // a straight curve is as long as the line
+        assert!(close(path("M0 0 C1 0 2 0 3 0").length(), 3.0, 1e-4));
+        assert!(close(path("M0 0 Q5 0 10 0").length(), 10.0, 1e-4));
+        // the length of a parabola y = x², 0 <= x <= 1
+        let parabola = (2.0 * 5_f32.sqrt() + (2.0 + 5_f32.sqrt()).ln()) / 4.0;
+        assert!(close(path("M0 0 Q0.5 0 1 1").length(), parabola, 1e-4));
+        // a loose tolerance is enough for a rough length
+        let measure = PathMeasure::new(&path("M0 0 C0 10 10 10 10 0"), 0.5);
+        let exact = path("M0 0 C0 10 10 10 10 0").length();
+        assert!(close(measure.total_length(), exact, 0.5));
+    }
+
+    #[test]
+    fn test_point_at() {
+        let d = path("M0 0 H10 M0 10 A5 5 0 0 0 10 10");
+        let measure = PathMeasure::new(&d, PathMeasure::DEFAULT_TOLERANCE);
+        assert!(close(measure.total_length(), 10.0 + 5.0 * PI, 1e-4));
+        let lengths: Vec<f32> = measure.segment_lengths().map(|(_, l)| l).collect();
+        assert_eq!(lengths.len(), 4);
+        assert_eq!(measure.point_at(-1.0), Some((0.0, 0.0)));
+        assert_eq!(measure.point_at(4.0), Some((4.0, 0.0)));
+        assert_eq!(measure.tangent_at(4.0), Some((1.0, 0.0)));
+        // the middle of the arc, counterclockwise below the center (5, 10)
+        let (x, y) = measure.point_at(10.0 + 2.5 * PI).unwrap();
+        assert!(close(x, 5.0, 1e-4) && close(y, 15.0, 1e-4));
+        let (x, y) = measure.tangent_at(10.0 + 2.5 * PI).unwrap();
+        assert!(close(x, 1.0, 1e-4) && close(y, 0.0, 1e-4));
+        assert_eq!(measure.point_at(100.0), Some((10.0, 10.0)));
+
+        // points along a curve are where their length says
+        let d = path("M0 0 C0 10 10 10 10 0");
+        let measure = PathMeasure::new(&d, 1e-4);
+        let half = measure.total_length() / 2.0;
+        let (x, _) = measure.point_at(half).unwrap();
+        assert!(close(x, 5.0, 1e-3));
+        let quarter = measure.total_length() / 4.0;
+        let (segment, t) = measure.locate(quarter).unwrap();
+        assert!(matches!(segment, Segment::Cubic { .. }));
+        assert!(close(segment.length_to(t, 1e-5), quarter, 1e-3));
+
+        assert_eq!(PathMeasure::new(&path(""), 0.1).point_at(1.0), None);
+        assert_eq!(PathMeasure::new(&path("M1 1"), 0.1).point_at(1.0), Some((1.0, 1.0)));
+        assert_eq!(PathMeasure::new(&path("M1 1"), 0.1).tangent_at(1.0), None);
+
+        // a length out of the range of f32 has no points
+        let measure = PathMeasure::new(&path("M-3e38 0 C3e38 0 -3e38 0 3e38 0"), 0.1);
+        assert!(!measure.total_length().is_finite());
+        assert_eq!(measure.point_at(1.0), None);
+        assert_eq!(measure.tangent_at(f32::NAN), None);
+        assert_eq!(PathMeasure::new(&path("M0 0 H1"), 0.1).point_at(f32::NAN).map(|p| p.1), Some(0.0));
+    }
+}
@@ -1,15 +1,15 @@
 use std::fmt::Display;
 
-use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::sequence::pair;
-use nom::{character::complete::space1, number::complete::float, sequence::separated_pair};
+use nom::character::complete::multispace0;
 
-use crate::parser::trim;
+use crate::error::IResult;
+
+use crate::parser::{command, point};
 /// MoveTo instructions can be thought of as picking up the drawing instrument,
 /// and setting it down somewhere else—in other words, moving the current point (Po; {xo, yo}).
 /// There is no line drawn between Po and the new current point (Pn; {xn, yn}).
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct M {
     pub x: f32,
     pub y: f32,
@@ -18,20 +18,17 @@ pub struct M {
 }
 
 impl M {
-    pub fn from_str(s: &str) -> nom::IResult<&str, M> {
-        let (s, (relative, (x, y))) = trim(pair(
-            trim(alt((tag("m"), tag("M")))),
-            trim(separated_pair(float, alt((tag(","), space1)), float)),
-        ))(s)?;
-
-        Ok((
-            s,
-            M {
-                x,
-                y,
-                relative: relative == "m",
-            },
-        ))
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, M> {
+        let (s, relative) = command('m')(s)?;
+        let (s, m) = M::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, m))
+    }
+    /// parse the arguments after the command letter: `x,y`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, M> {
+        let (s, (x, y)) = point(s)?;
+        Ok((s, M { x, y, relative }))
     }
 }
 
@@ -0,0 +1,214 @@
+use super::{Command, CubicBezier, LineTo, PathData, QuadraticBezier, A, C, H, L, M, Q, S, T, V, Z};
+
+/// The current point and the start of the current subpath, while walking through path data.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::children::{Cursor, PathData};
+///
+/// let (_, d) = PathData::from_str("m1 1 h2 z l1 1").unwrap();
+/// let mut cursor = Cursor::default();
+/// let absolute: Vec<String> = d.iter().map(|c| cursor.absolute(c).to_string()).collect();
+/// assert_eq!(absolute, ["M 1,1", "H 3", "Z", "L 2,2"]);
+/// assert_eq!(cursor.current, (2.0, 2.0));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cursor {
+    pub current: (f32, f32),
+    /// where the last moveto went, the current point after a closepath
+    pub start: (f32, f32),
+}
+
+impl Cursor {
+    /// the absolute form of `command`, then move to its end
+    pub fn absolute(&mut self, command: &Command) -> Command {
+        let absolute = match command.is_relative() {
+            true => command.shifted(self.current, false),
+            false => command.clone(),
+        };
+        self.advance(&absolute);
+        absolute
+    }
+    /// the relative form of `command`, then move to its end
+    pub fn relative(&mut self, command: &Command) -> Command {
+        let (x, y) = self.current;
+        let absolute = self.absolute(command);
+        absolute.shifted((-x, -y), true)
+    }
+    /// move to the end of an absolute command
+    fn advance(&mut self, absolute: &Command) {
+        self.current = match absolute {
+            Command::MoveTo(m) => {
+                self.start = (m.x, m.y);
+                self.start
+            }
+            Command::LineTo(LineTo::L(L { x, y, .. }))
+            | Command::CubicBezier(CubicBezier::C(C { x, y, .. }))
+            | Command::CubicBezier(CubicBezier::S(S { x, y, .. }))
+            | Command::QuadraticBezier(QuadraticBezier::Q(Q { x, y, .. }))
+            | Command::QuadraticBezier(QuadraticBezier::T(T { x, y, .. }))
+            | Command::EllipticalArc(A { x, y, .. }) => (*x, *y),
+            Command::LineTo(LineTo::H(h)) => (h.x, self.current.1),
+            Command::LineTo(LineTo::V(v)) => (self.current.0, v.y),
+            Command::ClosePath(_) => self.start,
+        };
+    }
+}
+
+impl Command {
+    pub fn is_relative(&self) -> bool {
+        match self {
+            Command::MoveTo(M { relative, .. })
+            | Command::LineTo(LineTo::L(L { relative, .. }))
+            | Command::LineTo(LineTo::H(H { relative, .. }))
+            | Command::LineTo(LineTo::V(V { relative, .. }))
+            | Command::CubicBezier(CubicBezier::C(C { relative, .. }))
+            | Command::CubicBezier(CubicBezier::S(S { relative, .. }))
+            | Command::QuadraticBezier(QuadraticBezier::Q(Q { relative, .. }))
+            | Command::QuadraticBezier(QuadraticBezier::T(T { relative, .. }))
+            | Command::EllipticalArc(A { relative, .. })
+            | Command::ClosePath(Z(relative)) => *relative,
+        }
+    }
+    /// every point moved by `(dx, dy)`, flagged as `relative`
+    fn shifted(&self, (dx, dy): (f32, f32), relative: bool) -> Command {
+        match *self {
+            Command::MoveTo(m) => M {
+                x: m.x + dx,
+                y: m.y + dy,
+                relative,
+            }
+            .into(),
+            Command::LineTo(LineTo::L(l)) => L {
+                x: l.x + dx,
+                y: l.y + dy,
+                relative,
+            }
+            .into(),
+            Command::LineTo(LineTo::H(h)) => H {
+                x: h.x + dx,
+                relative,
+            }
+            .into(),
+            Command::LineTo(LineTo::V(v)) => V {
+                y: v.y + dy,
+                relative,
+            }
+            .into(),
+            Command::CubicBezier(CubicBezier::C(c)) => C {
+                x1: c.x1 + dx,
+                y1: c.y1 + dy,
+                x2: c.x2 + dx,
+                y2: c.y2 + dy,
+                x: c.x + dx,
+                y: c.y + dy,
+                relative,
+            }
+            .into(),
+            Command::CubicBezier(CubicBezier::S(s)) => S {
+                x2: s.x2 + dx,
+                y2: s.y2 + dy,
+                x: s.x + dx,
+                y: s.y + dy,
+                relative,
+            }
+            .into(),
+            Command::QuadraticBezier(QuadraticBezier::Q(q)) => Q {
+                x1: q.x1 + dx,
+                y1: q.y1 + dy,
+                x: q.x + dx,
+                y: q.y + dy,
+                relative,
+            }
+            .into(),
+            Command::QuadraticBezier(QuadraticBezier::T(t)) => T {
+                x: t.x + dx,
+                y: t.y + dy,
+                relative,
+            }
+            .into(),
+            Command::EllipticalArc(a) => A {
+                x: a.x + dx,
+                y: a.y + dy,
+                relative,
+                ..a
+            }
+            .into(),
+            Command::ClosePath(_) => Z(relative).into(),
+        }
+    }
+}
+
+impl PathData {
+    /// every command in absolute coordinates
+    pub fn to_absolute(&self) -> PathData {
+        let mut cursor = Cursor::default();
+        self.iter().map(|command| cursor.absolute(command)).collect()
+    }
+    /// every command in coordinates relative to the previous end point
+    pub fn to_relative(&self) -> PathData {
+        let mut cursor = Cursor::default();
+        self.iter().map(|command| cursor.relative(command)).collect()
+    }
+    /// for each command, the absolute or the relative form, whichever is written shorter
+    /// (the absolute one on a tie)
+    pub fn to_shortest(&self) -> PathData {
+        let mut cursor = Cursor::default();
+        self.iter()
+            .map(|command| {
+                let (x, y) = cursor.current;
+                let absolute = cursor.absolute(command);
+                let relative = absolute.shifted((-x, -y), true);
+                match relative.to_string().len() < absolute.to_string().len() {
+                    true => relative,
+                    false => absolute,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_normalize {
+    use super::*;
+
+    fn parse(s: &str) -> PathData {
+        let (rest, d) = PathData::from_str(s).unwrap();
+        assert_eq!(rest, "");
+        d
+    }
+
+    #[test]
+    fn test_to_absolute() {
+        let d = parse("m10 10 l5 5 h5 v-5 c1 1 2 2 3 3 s1 1 2 2 q1 1 2 2 t2 2 a1 1 0 0 1 2 0 z m1 1 l1 0 z l2 2");
+        assert_eq!(
+            d.to_absolute().to_string(),
+            "M 10,10 L 15,15 H 20 V 10 C 21,11 22,12 23,13 S 24,14 25,15 Q 26,16 27,17 T 29,19 A 1 1 0 0 1 31,19 Z \
+             M 11,11 L 12,11 Z L 13,13"
+        );
+        // absolute commands are kept, a relative moveto at the start is absolute
+        assert_eq!(parse("M1 2 H3 V4").to_absolute(), parse("M1 2 H3 V4"));
+        assert_eq!(parse("m1 2").to_absolute(), parse("M1 2"));
+    }
+
+    #[test]
+    fn test_to_relative() {
+        let d = parse("M10 10 L15 15 H20 V10 C21 11 22 12 23 13 A1 1 0 0 1 25 13 Z L11 11");
+        let relative = d.to_relative();
+        assert_eq!(
+            relative.to_string(),
+            "m 10,10 l 5,5 h 5 v -5 c 1,1 2,2 3,3 a 1 1 0 0 1 2,0 z l 1,1"
+        );
+        assert_eq!(relative.to_absolute(), d);
+    }
+
+    #[test]
+    fn test_to_shortest() {
+        let d = parse("M100 100 L101 101 L0 100 H100 V101 Z");
+        assert_eq!(
+            d.to_shortest().to_string(),
+            "M 100,100 l 1,1 L 0,100 H 100 v 1 Z"
+        );
+        assert_eq!(d.to_shortest().to_absolute(), d.to_absolute());
+    }
+}
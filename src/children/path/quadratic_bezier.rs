@@ -1,9 +1,11 @@
 use std::fmt::Display;
 
-use crate::parser::{point, trim};
+use crate::parser::{command, point, sep};
 use nom::branch::alt;
 use nom::combinator::map;
-use nom::{bytes::complete::tag, sequence::pair};
+use nom::sequence::{preceded, tuple};
+use crate::error::IResult;
+use nom::character::complete::multispace0;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum QuadraticBezier {
@@ -21,13 +23,13 @@ impl Display for QuadraticBezier {
 }
 
 impl QuadraticBezier {
-    pub fn from_str(s: &str) -> nom::IResult<&str, QuadraticBezier> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, QuadraticBezier> {
         alt((
             map(Q::from_str, |v| v.into()),
             map(T::from_str, |v| v.into()),
         ))(s)
     }
-    
 }
 
 impl From<Q> for QuadraticBezier {
@@ -42,7 +44,8 @@ impl From<T> for QuadraticBezier {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Q {
     pub x1: f32,
     pub y1: f32,
@@ -66,11 +69,16 @@ impl Display for Q {
 }
 
 impl Q {
-    pub fn from_str(s: &str) -> nom::IResult<&str, Q> {
-        let (s, (relative, ((x1, y1), (x, y)))) = trim(pair(
-            trim(alt((tag("q"), tag("Q")))),
-            trim(pair(point, point)),
-        ))(s)?;
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, Q> {
+        let (s, relative) = command('q')(s)?;
+        let (s, q) = Q::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, q))
+    }
+    /// parse the arguments after the command letter: `x1,y1 x,y`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, Q> {
+        let (s, ((x1, y1), (x, y))) = tuple((point, preceded(sep, point)))(s)?;
 
         Ok((
             s,
@@ -79,13 +87,14 @@ impl Q {
                 y1,
                 x,
                 y,
-                relative: relative == "q",
+                relative,
             },
         ))
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct T {
     pub x: f32,
     pub y: f32,
@@ -105,17 +114,17 @@ impl Display for T {
 }
 
 impl T {
-    pub fn from_str(s: &str) -> nom::IResult<&str, T> {
-        let (s, (relative, (x, y))) = trim(pair(trim(alt((tag("t"), tag("T")))), point))(s)?;
-
-        Ok((
-            s,
-            T {
-                x,
-                y,
-                relative: relative == "t",
-            },
-        ))
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, T> {
+        let (s, relative) = command('t')(s)?;
+        let (s, t) = T::args(s, relative)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, t))
+    }
+    /// parse the arguments after the command letter: `x,y`
+    pub fn args(s: &str, relative: bool) -> IResult<&str, T> {
+        let (s, (x, y)) = point(s)?;
+        Ok((s, T { x, y, relative }))
     }
 }
 
@@ -0,0 +1,255 @@
+use super::{ArcCenter, Command, CubicBezier, Cursor, PathData, QuadraticBezier};
+
+type Point = (f32, f32);
+
+/// A piece of a path in absolute coordinates, with its start point and resolved control points.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::children::{PathData, Segment};
+///
+/// let (_, d) = PathData::from_str("M0 0 h2 s1 1 2 0").unwrap();
+/// let segments: Vec<Segment> = d.segments().collect();
+/// assert_eq!(segments[1], Segment::Line { from: (0.0, 0.0), to: (2.0, 0.0) });
+/// assert!(matches!(segments[2], Segment::Cubic { control1: (2.0, 0.0), .. }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// the start of a subpath
+    Move { to: Point },
+    Line { from: Point, to: Point },
+    Quadratic { from: Point, control: Point, to: Point },
+    Cubic { from: Point, control1: Point, control2: Point, to: Point },
+    Arc { from: Point, to: Point, arc: ArcCenter },
+    /// the line back to the start of the subpath
+    Close { from: Point, to: Point },
+}
+
+impl Segment {
+    /// the first point, where the previous segment ended
+    pub fn from(&self) -> Point {
+        match *self {
+            Segment::Move { to } => to,
+            Segment::Line { from, .. }
+            | Segment::Quadratic { from, .. }
+            | Segment::Cubic { from, .. }
+            | Segment::Arc { from, .. }
+            | Segment::Close { from, .. } => from,
+        }
+    }
+    pub fn to(&self) -> Point {
+        match *self {
+            Segment::Move { to }
+            | Segment::Line { to, .. }
+            | Segment::Quadratic { to, .. }
+            | Segment::Cubic { to, .. }
+            | Segment::Arc { to, .. }
+            | Segment::Close { to, .. } => to,
+        }
+    }
+    /// the point at `t` in `0..=1`
+    pub fn point(&self, t: f32) -> Point {
+        let mt = 1_f32 - t;
+        match *self {
+            Segment::Move { to } => to,
+            Segment::Line { from, to } | Segment::Close { from, to } => {
+                (mt * from.0 + t * to.0, mt * from.1 + t * to.1)
+            }
+            Segment::Quadratic { from, control, to } => {
+                let (a, b, c) = (mt * mt, 2_f32 * mt * t, t * t);
+                (
+                    a * from.0 + b * control.0 + c * to.0,
+                    a * from.1 + b * control.1 + c * to.1,
+                )
+            }
+            Segment::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                let (a, b, c, d) = (mt * mt * mt, 3_f32 * mt * mt * t, 3_f32 * mt * t * t, t * t * t);
+                (
+                    a * from.0 + b * control1.0 + c * control2.0 + d * to.0,
+                    a * from.1 + b * control1.1 + c * control2.1 + d * to.1,
+                )
+            }
+            Segment::Arc { to, arc, .. } => match t {
+                // exactly the end point, not a rounding error away from it
+                _ if t >= 1_f32 => to,
+                _ => arc.point(arc.start + arc.sweep * t),
+            },
+        }
+    }
+    /// the derivative at `t` in `0..=1`, it may be zero where control points coincide
+    pub fn derivative(&self, t: f32) -> Point {
+        let mt = 1_f32 - t;
+        match *self {
+            Segment::Move { .. } => (0_f32, 0_f32),
+            Segment::Line { from, to } | Segment::Close { from, to } => {
+                (to.0 - from.0, to.1 - from.1)
+            }
+            Segment::Quadratic { from, control, to } => (
+                2_f32 * (mt * (control.0 - from.0) + t * (to.0 - control.0)),
+                2_f32 * (mt * (control.1 - from.1) + t * (to.1 - control.1)),
+            ),
+            Segment::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                let (a, b, c) = (3_f32 * mt * mt, 6_f32 * mt * t, 3_f32 * t * t);
+                (
+                    a * (control1.0 - from.0) + b * (control2.0 - control1.0) + c * (to.0 - control2.0),
+                    a * (control1.1 - from.1) + b * (control2.1 - control1.1) + c * (to.1 - control2.1),
+                )
+            }
+            Segment::Arc { arc, .. } => {
+                let (x, y) = arc.derivative(arc.start + arc.sweep * t);
+                (x * arc.sweep, y * arc.sweep)
+            }
+        }
+    }
+    /// the direction of the segment at its start or end, `None` if it has no length
+    pub fn tangent(&self, end: bool) -> Option<Point> {
+        let t = if end { 1_f32 } else { 0_f32 };
+        // where control points coincide with an end point, the direction is given
+        // by the next control point, look slightly inside the segment
+        [t, (t - 1e-3).abs(), 0.5]
+            .into_iter()
+            .find_map(|t| self.tangent_at(t))
+    }
+    /// the normalized derivative at `t`, `None` where it is zero
+    pub(crate) fn tangent_at(&self, t: f32) -> Option<Point> {
+        let (x, y) = self.derivative(t);
+        let length = x.hypot(y);
+        (length > 0_f32).then(|| (x / length, y / length))
+    }
+}
+
+/// The iterator of [`PathData::segments`].
+#[derive(Debug, Clone)]
+pub struct Segments<'a> {
+    commands: std::slice::Iter<'a, Command>,
+    cursor: Cursor,
+    /// the last control point of the previous segment, reflected by `S` and `T`
+    previous: Option<Segment>,
+}
+
+impl Iterator for Segments<'_> {
+    type Item = Segment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let command = self.commands.next()?;
+            let from = self.cursor.current;
+            let absolute = self.cursor.absolute(command);
+            let to = self.cursor.current;
+            let reflect = |(x, y): Point| (2_f32 * from.0 - x, 2_f32 * from.1 - y);
+            let segment = match absolute {
+                Command::MoveTo(_) => Segment::Move { to },
+                Command::LineTo(_) => Segment::Line { from, to },
+                Command::CubicBezier(CubicBezier::C(c)) => Segment::Cubic {
+                    from,
+                    control1: (c.x1, c.y1),
+                    control2: (c.x2, c.y2),
+                    to,
+                },
+                Command::CubicBezier(CubicBezier::S(s)) => Segment::Cubic {
+                    from,
+                    control1: match self.previous {
+                        Some(Segment::Cubic { control2, .. }) => reflect(control2),
+                        _ => from,
+                    },
+                    control2: (s.x2, s.y2),
+                    to,
+                },
+                Command::QuadraticBezier(QuadraticBezier::Q(q)) => Segment::Quadratic {
+                    from,
+                    control: (q.x1, q.y1),
+                    to,
+                },
+                Command::QuadraticBezier(QuadraticBezier::T(_)) => Segment::Quadratic {
+                    from,
+                    control: match self.previous {
+                        Some(Segment::Quadratic { control, .. }) => reflect(control),
+                        _ => from,
+                    },
+                    to,
+                },
+                Command::EllipticalArc(a) => match a.center(from) {
+                    Some(arc) => Segment::Arc { from, to, arc },
+                    // an arc with a zero radius is a line
+                    None if from != to => Segment::Line { from, to },
+                    // an arc ending where it starts is omitted
+                    None => {
+                        self.previous = None;
+                        continue;
+                    }
+                },
+                Command::ClosePath(_) => Segment::Close { from, to },
+            };
+            self.previous = Some(segment);
+            return Some(segment);
+        }
+    }
+}
+
+impl PathData {
+    /// the segments of the path, see [`Segment`]
+    pub fn segments(&self) -> Segments<'_> {
+        Segments {
+            commands: self.0.iter(),
+            cursor: Cursor::default(),
+            previous: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_segment {
+    use super::*;
+
+    fn segments(s: &str) -> Vec<Segment> {
+        let (rest, d) = PathData::from_str(s).unwrap();
+        assert_eq!(rest, "");
+        d.segments().collect()
+    }
+
+    #[test]
+    fn test_segments() {
+        let all = segments("m1 1 v2 q1 1 2 0 t2 0 z A 0 1 0 0 1 5 5 A 1 1 0 0 1 5 5");
+        assert_eq!(all.len(), 6);
+        assert_eq!(all[0], Segment::Move { to: (1.0, 1.0) });
+        assert_eq!(all[1], Segment::Line { from: (1.0, 1.0), to: (1.0, 3.0) });
+        assert_eq!(
+            all[3],
+            Segment::Quadratic {
+                from: (3.0, 3.0),
+                control: (4.0, 2.0),
+                to: (5.0, 3.0)
+            }
+        );
+        assert_eq!(all[4], Segment::Close { from: (5.0, 3.0), to: (1.0, 1.0) });
+        assert_eq!(all[5], Segment::Line { from: (1.0, 1.0), to: (5.0, 5.0) });
+    }
+
+    #[test]
+    fn test_point_and_tangent() {
+        let all = segments("M0 0 C0 0 1 1 2 0 A1 1 0 0 1 4 0");
+        let cubic = all[1];
+        assert_eq!(cubic.point(0.0), (0.0, 0.0));
+        assert_eq!(cubic.point(1.0), (2.0, 0.0));
+        assert_eq!(cubic.point(0.5), (0.625, 0.375));
+        // the first control point is the start point, the curve leaves towards the second one
+        let (x, y) = cubic.tangent(false).unwrap();
+        assert!((x - y).abs() < 1e-2 && x > 0.0);
+        let arc = all[2];
+        let (x, y) = arc.point(0.5);
+        assert!((x - 3.0).abs() < 1e-5 && (y + 1.0).abs() < 1e-5);
+        let (x, y) = arc.tangent(false).unwrap();
+        assert!(x.abs() < 1e-5 && (y + 1.0).abs() < 1e-5);
+        assert_eq!(Segment::Line { from: (1.0, 1.0), to: (1.0, 1.0) }.tangent(false), None);
+    }
+}
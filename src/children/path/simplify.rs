@@ -0,0 +1,110 @@
+use super::{PathData, Segment, C, L, M, Z};
+
+/// the cubic bezier with the same curve as the quadratic one with the control point `q`
+fn elevate(from: (f32, f32), (qx, qy): (f32, f32), (x, y): (f32, f32)) -> C {
+    let third = 2_f32 / 3_f32;
+    C {
+        x1: from.0 + third * (qx - from.0),
+        y1: from.1 + third * (qy - from.1),
+        x2: x + third * (qx - x),
+        y2: y + third * (qy - y),
+        x,
+        y,
+        relative: false,
+    }
+}
+
+impl PathData {
+    /// the same path with only absolute `M`, `L`, `C` and `Z` commands.
+    ///
+    /// `H` and `V` become `L`, `S` and `T` get their reflected control points,
+    /// quadratic curves are elevated to cubic ones and arcs are approximated by cubic curves
+    /// (see [`A::to_cubics`](super::A::to_cubics)), an arc with a zero radius is a line
+    pub fn simplify(&self) -> PathData {
+        let mut result = PathData::new();
+        for command in self.segments() {
+            match command {
+                Segment::Move { to: (x, y) } => result.push(M {
+                    x,
+                    y,
+                    relative: false,
+                }),
+                Segment::Line { to: (x, y), .. } => result.push(L {
+                    x,
+                    y,
+                    relative: false,
+                }),
+                Segment::Quadratic { from, control, to } => result.push(elevate(from, control, to)),
+                Segment::Cubic {
+                    control1,
+                    control2,
+                    to,
+                    ..
+                } => result.push(C {
+                    x1: control1.0,
+                    y1: control1.1,
+                    x2: control2.0,
+                    y2: control2.1,
+                    x: to.0,
+                    y: to.1,
+                    relative: false,
+                }),
+                Segment::Arc { to, arc, .. } => {
+                    let mut cubics = arc.to_cubics();
+                    // exactly the end point of the arc, not a rounding error away from it
+                    if let Some(last) = cubics.last_mut() {
+                        (last.x, last.y) = to;
+                    }
+                    result.extend(cubics);
+                }
+                Segment::Close { .. } => result.push(Z(false)),
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test_simplify {
+    use super::*;
+    use crate::children::{Command, CubicBezier};
+
+    fn simplify(s: &str) -> String {
+        let (rest, d) = PathData::from_str(s).unwrap();
+        assert_eq!(rest, "");
+        d.simplify().to_string()
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(simplify("m1 1 h2 v2 H0 z l1 1"), "M 1,1 L 3,1 L 3,3 L 0,3 Z L 2,2");
+    }
+
+    #[test]
+    fn test_smooth_curves() {
+        assert_eq!(
+            simplify("M0 0 C0 1 1 2 2 2 S4 1 4 0 s1 1 2 0"),
+            "M 0,0 C 0,1 1,2 2,2 C 3,2 4,1 4,0 C 4,-1 5,1 6,0"
+        );
+        // without a previous cubic the first control point is the current point
+        assert_eq!(simplify("M0 0 L1 1 S2 2 3 1"), "M 0,0 L 1,1 C 1,1 2,2 3,1");
+        assert_eq!(
+            simplify("M0 0 Q3 3 6 0 T12 0 L 0 0 T3 3"),
+            "M 0,0 C 2,2 4,2 6,0 C 8,-2 10,-2 12,0 L 0,0 C 0,0 1,1 3,3"
+        );
+    }
+
+    #[test]
+    fn test_arcs() {
+        assert_eq!(simplify("M0 0 A1 1 0 0 1 0 0"), "M 0,0");
+        assert_eq!(simplify("M0 0 a0 1 0 0 1 2 0"), "M 0,0 L 2,0");
+        let (_, d) = PathData::from_str("M0 0 A1 1 0 0 1 2 0 A1 1 0 1 1 0 0 Z").unwrap();
+        let simple = d.simplify();
+        assert_eq!(simple.len(), 6);
+        assert!(simple.iter().all(|command| matches!(
+            command,
+            Command::MoveTo(_) | Command::CubicBezier(CubicBezier::C(_)) | Command::ClosePath(_)
+        )));
+        assert!(matches!(simple.0[2], Command::CubicBezier(CubicBezier::C(c)) if c.x == 2.0 && c.y == 0.0));
+    }
+}
@@ -0,0 +1,183 @@
+use std::iter::Peekable;
+
+use super::{PathData, Segment, Segments};
+
+type Point = (f32, f32);
+
+/// the most lines a single segment is split into
+const MAX_LINES: f32 = 1024_f32;
+
+impl Segment {
+    /// how many lines of equal parameter steps stay within `tolerance` of the segment
+    fn line_count(&self, tolerance: f32) -> usize {
+        let tolerance = tolerance.max(f32::EPSILON);
+        let norm = |x: f32, y: f32| x.hypot(y);
+        // a step `h` of a curve is at most `h² max|B''| / 8` away from its chord
+        let count = match *self {
+            Segment::Move { .. } | Segment::Line { .. } | Segment::Close { .. } => 1_f32,
+            Segment::Quadratic { from, control, to } => {
+                let second = 2_f32 * norm(from.0 - 2_f32 * control.0 + to.0, from.1 - 2_f32 * control.1 + to.1);
+                (second / (8_f32 * tolerance)).sqrt()
+            }
+            Segment::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                let second = 6_f32
+                    * norm(
+                        from.0 - 2_f32 * control1.0 + control2.0,
+                        from.1 - 2_f32 * control1.1 + control2.1,
+                    )
+                    .max(norm(
+                        control1.0 - 2_f32 * control2.0 + to.0,
+                        control1.1 - 2_f32 * control2.1 + to.1,
+                    ));
+                (second / (8_f32 * tolerance)).sqrt()
+            }
+            // the sagitta of a step `δ` on a circle of radius `r` is `r (1 - cos(δ / 2))`
+            Segment::Arc { arc, .. } => {
+                let radius = arc.rx.max(arc.ry);
+                let step = 2_f32 * (1_f32 - tolerance / radius).max(-1_f32).acos();
+                arc.sweep.abs() / step
+            }
+        };
+        count.ceil().clamp(1_f32, MAX_LINES) as usize
+    }
+    /// append the end points of the lines approximating the segment within `tolerance`,
+    /// its start point is not included
+    pub fn flatten_into(&self, tolerance: f32, points: &mut Vec<Point>) {
+        let count = self.line_count(tolerance);
+        points.extend((1..count).map(|index| self.point(index as f32 / count as f32)));
+        points.push(self.to());
+    }
+}
+
+/// A part of a path between two movetos, as a polyline.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Subpath {
+    /// the start point and the end of each line, the start is not repeated at the end of a closed one
+    pub points: Vec<Point>,
+    /// ended by a closepath, the last point is connected to the first one
+    pub closed: bool,
+}
+
+/// The iterator of [`PathData::flatten`].
+#[derive(Debug, Clone)]
+pub struct Subpaths<'a> {
+    segments: Peekable<Segments<'a>>,
+    tolerance: f32,
+}
+
+impl Iterator for Subpaths<'_> {
+    type Item = Subpath;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut subpath: Option<Subpath> = None;
+        loop {
+            let Some(segment) = self.segments.next() else {
+                // a trailing moveto alone draws nothing
+                return subpath.filter(|subpath| subpath.points.len() > 1);
+            };
+            let current = subpath.get_or_insert_with(|| Subpath {
+                points: vec![segment.from()],
+                closed: false,
+            });
+            match segment {
+                // a moveto alone draws nothing, the subpath restarts
+                Segment::Move { to } => current.points = vec![to],
+                Segment::Close { .. } => {
+                    current.closed = true;
+                    return subpath;
+                }
+                segment => segment.flatten_into(self.tolerance, &mut current.points),
+            }
+            if matches!(self.segments.peek(), Some(Segment::Move { .. })) && current.points.len() > 1 {
+                return subpath;
+            }
+        }
+    }
+}
+
+impl PathData {
+    /// the subpaths as polylines, curves are split into lines which are at most
+    /// `tolerance` away from them
+    ///
+    /// ## Example
+    /// ```rust
+    /// use svg_icon::children::PathData;
+    ///
+    /// let (_, d) = PathData::from_str("M0 0 Q5 10 10 0 z M20 0 h5").unwrap();
+    /// let subpaths: Vec<_> = d.flatten(0.1).collect();
+    /// assert_eq!(subpaths.len(), 2);
+    /// assert!(subpaths[0].closed && subpaths[0].points.len() > 3);
+    /// assert_eq!(subpaths[1].points, [(20.0, 0.0), (25.0, 0.0)]);
+    /// ```
+    pub fn flatten(&self, tolerance: f32) -> Subpaths<'_> {
+        Subpaths {
+            segments: self.segments().peekable(),
+            tolerance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_subpath {
+    use super::*;
+
+    fn flatten(s: &str, tolerance: f32) -> Vec<Subpath> {
+        let (rest, d) = PathData::from_str(s).unwrap();
+        assert_eq!(rest, "");
+        d.flatten(tolerance).collect()
+    }
+
+    #[test]
+    fn test_subpaths() {
+        let subpaths = flatten("M0 0 M1 1 h1 v1 z l1 0 M5 5 z M9 9", 0.1);
+        assert_eq!(
+            subpaths,
+            [
+                Subpath {
+                    points: vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0)],
+                    closed: true
+                },
+                // after a closepath, the next subpath starts at the start of the previous one
+                Subpath {
+                    points: vec![(1.0, 1.0), (2.0, 1.0)],
+                    closed: false
+                },
+                Subpath {
+                    points: vec![(5.0, 5.0)],
+                    closed: true
+                },
+            ]
+        );
+        assert!(flatten("", 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_tolerance() {
+        // every point is on the circle and the middle of every line is within the tolerance
+        for tolerance in [1.0, 0.1, 0.01] {
+            let subpaths = flatten("M10 0 A10 10 0 1 1 -10 0 A10 10 0 1 1 10 0", tolerance);
+            let points = &subpaths[0].points;
+            assert!(points.len() > 4);
+            assert_eq!(points.last(), Some(&(10.0, 0.0)));
+            for pair in points.windows(2) {
+                let (x, y) = ((pair[0].0 + pair[1].0) / 2.0, (pair[0].1 + pair[1].1) / 2.0);
+                let distance = 10.0 - x.hypot(y);
+                assert!(distance >= -1e-3 && distance <= tolerance, "{} > {}", distance, tolerance);
+            }
+        }
+        // a finer tolerance needs more lines
+        let coarse = flatten("M0 0 C0 10 10 10 10 0", 1.0)[0].points.len();
+        let fine = flatten("M0 0 C0 10 10 10 10 0", 0.01)[0].points.len();
+        assert!(coarse < fine);
+        // lines are not split
+        assert_eq!(flatten("M0 0 L10 10", 0.001)[0].points.len(), 2);
+        // the points of a straight curve are on the line
+        let points = &flatten("M0 0 Q5 0 10 0 T20 0", 0.1)[0].points;
+        assert!(points.iter().all(|(_, y)| *y == 0.0));
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Display;
+
+use crate::error::{attribute_in, IResult};
+
+use super::{Command, CommonKVs, Path, PathData, Points, L, M, Z};
+
+/// The <polygon> element defines a closed shape consisting of a set of connected straight line segments.
+/// The last point is connected to the first point.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Polygon {
+    pub common: CommonKVs,
+    /// the list of points (pairs of x,y absolute coordinates) required to draw the polygon.
+    pub points: Option<Points>,
+}
+
+impl Polygon {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, Polygon> {
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s.trim())?;
+        common.remove_others(&["points"]);
+        let points = match kvs {
+            Some(kvs) => attribute_in(&kvs, "points")?,
+            None => None,
+        };
+
+        Ok((s, Polygon { common, points }))
+    }
+    /// the equivalent `<path>`: `M p0 L p1 ... Z`
+    pub fn to_path(&self) -> Path {
+        let mut d = polyline_data(&self.points.clone().unwrap_or_default());
+        if !d.is_empty() {
+            d.push(Z(false));
+        }
+        Path {
+            common: self.common.clone(),
+            d: Some(d),
+        }
+    }
+}
+
+impl Polygon {
+    /// the attributes of the polygon which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        self.points.iter().map(|points| ("points", points.to_string())).collect()
+    }
+}
+
+impl Display for Polygon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<polygon")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, "/>")
+    }
+}
+
+/// `M p0 L p1 L p2 ...`
+pub(crate) fn polyline_data(points: &Points) -> PathData {
+    points
+        .0
+        .iter()
+        .enumerate()
+        .map(|(index, &(x, y))| {
+            if index == 0 {
+                M {
+                    x,
+                    y,
+                    relative: false,
+                }
+                .into()
+            } else {
+                L {
+                    x,
+                    y,
+                    relative: false,
+                }
+                .into()
+            }
+        })
+        .collect::<Vec<Command>>()
+        .into()
+}
+
+#[cfg(test)]
+mod test_polygon {
+    use super::*;
+    use crate::paint::{Color, Paint};
+
+    #[test]
+    fn test_polygon() {
+        let (s, polygon) =
+            Polygon::from_str(r#"points="0,100 50,25 50,75 100,0" fill="red""#).unwrap();
+        assert_eq!(s, "");
+        assert_eq!(polygon.points.as_ref().unwrap().0.len(), 4);
+        assert_eq!(polygon.common.fill, Some(Paint::Color(Color::rgb(255, 0, 0))));
+        assert_eq!(
+            polygon.to_path().d.unwrap().to_string(),
+            "M 0,100 L 50,25 L 50,75 L 100,0 Z"
+        );
+        assert!(Polygon::from_str(r#"points="0,100 50""#).is_err());
+        // `points` is only written if it was there
+        let (_, polygon) = Polygon::from_str(r#"fill="red""#).unwrap();
+        assert_eq!(polygon.to_string(), r##"<polygon fill="#ff0000"/>"##);
+        let (_, polygon) = Polygon::from_str(r#"points="""#).unwrap();
+        assert_eq!(polygon.to_string(), r#"<polygon points=""/>"#);
+    }
+}
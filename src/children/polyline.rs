@@ -0,0 +1,72 @@
+use std::fmt::Display;
+
+use crate::error::{attribute_in, IResult};
+
+use super::{polygon::polyline_data, CommonKVs, Path, Points};
+
+/// The <polyline> SVG element is an SVG basic shape that creates straight lines connecting several points.
+/// Typically a polyline is used to create open shapes as the last point doesn't have to be connected to the first point.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Polyline {
+    pub common: CommonKVs,
+    /// the list of points (pairs of x,y absolute coordinates) required to draw the polyline.
+    pub points: Option<Points>,
+}
+
+impl Polyline {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, Polyline> {
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s.trim())?;
+        common.remove_others(&["points"]);
+        let points = match kvs {
+            Some(kvs) => attribute_in(&kvs, "points")?,
+            None => None,
+        };
+
+        Ok((s, Polyline { common, points }))
+    }
+    /// the equivalent `<path>`: `M p0 L p1 ...`
+    pub fn to_path(&self) -> Path {
+        Path {
+            common: self.common.clone(),
+            d: Some(polyline_data(&self.points.clone().unwrap_or_default())),
+        }
+    }
+}
+
+impl Polyline {
+    /// the attributes of the polyline which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        self.points.iter().map(|points| ("points", points.to_string())).collect()
+    }
+}
+
+impl Display for Polyline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<polyline")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, "/>")
+    }
+}
+
+#[cfg(test)]
+mod test_polyline {
+    use super::*;
+
+    #[test]
+    fn test_polyline() {
+        let (s, polyline) = Polyline::from_str(r#"points="0 0 10-10,20 0""#).unwrap();
+        assert_eq!(s, "");
+        assert_eq!(polyline.points.as_ref().unwrap().0, vec![(0.0, 0.0), (10.0, -10.0), (20.0, 0.0)]);
+        assert_eq!(
+            polyline.to_path().d.unwrap().to_string(),
+            "M 0,0 L 10,-10 L 20,0"
+        );
+        assert_eq!(
+            polyline.to_string(),
+            r#"<polyline points="0,0 10,-10 20,0"/>"#
+        );
+    }
+}
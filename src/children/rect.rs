@@ -1,74 +1,205 @@
 use std::fmt::Display;
 
-use super::CommonKVs;
+use crate::error::{attribute_in, IResult};
+use crate::length::{resolve, Axis, Length, LengthContext};
+
+use super::{circle::quarter, Curves, CommonKVs, Path, PathData, H, M, V, Z};
 
 /// The <rect> element is a basic SVG shape that draws rectangles,
 /// defined by their position, width, and height. The rectangles may have their corners rounded.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Rect {
     pub common: CommonKVs,
-    pub x: Option<f32>,
-    pub y: Option<f32>,
-    pub width: Option<f32>,
-    pub height: Option<f32>,
-    pub rx: Option<f32>,
-    pub ry: Option<f32>,
+    pub x: Option<Length>,
+    pub y: Option<Length>,
+    pub width: Option<Length>,
+    pub height: Option<Length>,
+    pub rx: Option<Length>,
+    pub ry: Option<Length>,
 }
 
 impl Rect {
-    pub fn from_str(s: &str) -> nom::IResult<&str, Rect> {
-        let (s, (common, kvs)) = CommonKVs::from_str(s.trim()).unwrap();
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> IResult<&str, Rect> {
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s.trim())?;
+        common.remove_others(&["width", "height", "rx", "ry"]);
+        // `x` and `y` are collected by `CommonKVs`, they belong to the rect
+        let x = common.x.take();
+        let y = common.y.take();
 
-        if kvs.is_none() {
-            Ok((
+        match kvs {
+            None => Ok((
                 s,
                 Rect {
                     common,
-                    x: None,
-                    y: None,
+                    x,
+                    y,
                     width: None,
                     height: None,
                     rx: None,
                     ry: None,
                 },
-            ))
-        } else {
-            Ok((
+            )),
+            Some(kvs) => Ok((
                 s,
                 Rect {
                     common,
-                    x: kvs.as_ref().unwrap().get("x").map(|v| v.parse().unwrap()),
-                    y: kvs.as_ref().unwrap().get("y").map(|v| v.parse().unwrap()),
-                    width: kvs
-                        .as_ref()
-                        .unwrap()
-                        .get("width")
-                        .map(|v| v.parse().unwrap()),
-                    height: kvs
-                        .as_ref()
-                        .unwrap()
-                        .get("height")
-                        .map(|v| v.parse().unwrap()),
-                    rx: kvs.as_ref().unwrap().get("rx").map(|v| v.parse().unwrap()),
-                    ry: kvs.as_ref().unwrap().get("ry").map(|v| v.parse().unwrap()),
+                    x,
+                    y,
+                    width: attribute_in(&kvs, "width")?,
+                    height: attribute_in(&kvs, "height")?,
+                    rx: attribute_in(&kvs, "rx")?,
+                    ry: attribute_in(&kvs, "ry")?,
                 },
-            ))
+            )),
         }
     }
 }
 
+impl Rect {
+    /// the corner radii in user units: a missing one is the other one (resolved on its own axis),
+    /// both are at most half of the width and the height
+    pub(crate) fn radii(&self, context: &LengthContext) -> (f32, f32) {
+        let rx = self.rx.map(|rx| rx.resolve(Axis::X, context));
+        let ry = self.ry.map(|ry| ry.resolve(Axis::Y, context));
+        let (rx, ry) = (rx.or(ry).unwrap_or_default(), ry.or(rx).unwrap_or_default());
+        let width = resolve(self.width, Axis::X, context);
+        let height = resolve(self.height, Axis::Y, context);
+        (
+            rx.clamp(0_f32, (width / 2_f32).max(0_f32)),
+            ry.clamp(0_f32, (height / 2_f32).max(0_f32)),
+        )
+    }
+    /// the equivalent `<path>`, clockwise from the top left corner
+    pub fn to_path(&self, context: &LengthContext, curves: Curves) -> Path {
+        Path {
+            common: self.common.clone(),
+            d: Some(self.path_data(context, curves)),
+        }
+    }
+    /// clockwise from the top left corner, with curves at rounded corners,
+    /// empty if the width or height is not positive
+    pub(crate) fn path_data(&self, context: &LengthContext, curves: Curves) -> PathData {
+        let x = resolve(self.x, Axis::X, context);
+        let y = resolve(self.y, Axis::Y, context);
+        let width = resolve(self.width, Axis::X, context);
+        let height = resolve(self.height, Axis::Y, context);
+        let mut d = PathData::new();
+        if width <= 0_f32 || height <= 0_f32 {
+            return d;
+        }
+        let (rx, ry) = self.radii(context);
+        let rounded = rx > 0_f32 && ry > 0_f32;
+        // from the end of the previous side to `to`, around the center of the corner
+        let corner = |d: &mut PathData, center: (f32, f32), from: (f32, f32), to: (f32, f32)| {
+            if rounded {
+                quarter(d, curves, center, from, to, (rx, ry));
+            }
+        };
+        let (rx, ry) = if rounded { (rx, ry) } else { (0_f32, 0_f32) };
+        d.push(M {
+            x: x + rx,
+            y,
+            relative: false,
+        });
+        d.push(H {
+            x: x + width - rx,
+            relative: false,
+        });
+        corner(
+            &mut d,
+            (x + width - rx, y + ry),
+            (x + width - rx, y),
+            (x + width, y + ry),
+        );
+        d.push(V {
+            y: y + height - ry,
+            relative: false,
+        });
+        corner(
+            &mut d,
+            (x + width - rx, y + height - ry),
+            (x + width, y + height - ry),
+            (x + width - rx, y + height),
+        );
+        d.push(H {
+            x: x + rx,
+            relative: false,
+        });
+        corner(
+            &mut d,
+            (x + rx, y + height - ry),
+            (x + rx, y + height),
+            (x, y + height - ry),
+        );
+        d.push(V {
+            y: y + ry,
+            relative: false,
+        });
+        corner(&mut d, (x + rx, y + ry), (x, y + ry), (x + rx, y));
+        d.push(Z(false));
+        d
+    }
+}
+
+impl Rect {
+    /// the attributes of the rect which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        [
+            ("x", self.x),
+            ("y", self.y),
+            ("width", self.width),
+            ("height", self.height),
+            ("rx", self.rx),
+            ("ry", self.ry),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| v.map(|v| (k, v.to_string())))
+        .collect()
+    }
+}
+
 impl Display for Rect {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" ry=\"{}\" {} />",
-            self.x.as_ref().unwrap_or(&0_f32),
-            self.y.as_ref().unwrap_or(&0_f32),
-            self.width.as_ref().unwrap_or(&0_f32),
-            self.height.as_ref().unwrap_or(&0_f32),
-            self.rx.as_ref().unwrap_or(&0_f32),
-            self.ry.as_ref().unwrap_or(&0_f32),
-            self.common
-        )
+        write!(f, "<rect")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, "/>")
+    }
+}
+
+#[cfg(test)]
+mod test_rect {
+    use super::*;
+    use crate::children::{Command, CubicBezier};
+
+    #[test]
+    fn test_to_path() {
+        let context = LengthContext::default();
+        let (_, rect) = Rect::from_str(r#"x="1" y="2" width="10" height="4" fill="red""#).unwrap();
+        let path = rect.to_path(&context, Curves::Arcs);
+        assert_eq!(path.to_string(), r##"<path fill="#ff0000" d="M 1,2 H 11 V 6 H 1 V 2 Z"/>"##);
+
+        // a missing radius is the other one, clamped to half of the height
+        let (_, rect) = Rect::from_str(r#"width="10" height="4" rx="3""#).unwrap();
+        assert_eq!(
+            rect.to_path(&context, Curves::Arcs).d.unwrap().to_string(),
+            "M 3,0 H 7 A 3 2 0 0 1 10,2 V 2 A 3 2 0 0 1 7,4 H 3 A 3 2 0 0 1 0,2 V 2 A 3 2 0 0 1 3,0 Z"
+        );
+        let (_, rect) = Rect::from_str(r#"width="10" height="10" rx="50%""#).unwrap();
+        let d = rect.to_path(&LengthContext::new(20.0, 20.0), Curves::Cubics).d.unwrap();
+        let kappa = 5.0 * 0.552_284_8_f32;
+        assert!(matches!(d.0[2], Command::CubicBezier(CubicBezier::C(c))
+            if c.x1 == 5.0 + kappa && c.y1 == 0.0 && c.x2 == 10.0 && c.y2 == 5.0 - kappa && c.x == 10.0 && c.y == 5.0));
+
+        // a percentage radius is resolved on its own axis, then copied: the corners are circular
+        let (_, rect) = Rect::from_str(r#"width="10" height="10" ry="10%""#).unwrap();
+        assert_eq!(rect.radii(&LengthContext::new(40.0, 20.0)), (2.0, 2.0));
+        let (_, rect) = Rect::from_str(r#"width="10" height="10" rx="10%""#).unwrap();
+        assert_eq!(rect.radii(&LengthContext::new(40.0, 20.0)), (4.0, 4.0));
+
+        let (_, empty) = Rect::from_str(r#"width="10""#).unwrap();
+        assert!(empty.to_path(&context, Curves::Arcs).d.unwrap().is_empty());
     }
 }
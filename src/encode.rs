@@ -1,7 +1,10 @@
 use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub enum EnCoding{
+    #[default]
     UTF8,
     UTF16,
     UTF32,
@@ -17,14 +20,8 @@ pub enum EnCoding{
     UNKNOWN(String),
 }
 
-impl Default for EnCoding{
-    fn default() -> Self {
-        EnCoding::UTF8
-    }
-}
-
 impl FromStr for EnCoding{
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s{
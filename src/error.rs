@@ -0,0 +1,305 @@
+use std::fmt::Display;
+
+/// `nom::IResult` using the crate [`ParseError`]
+pub type IResult<I, O> = nom::IResult<I, O, ParseError<I>>;
+
+/// Where an [`Error`] happened in the parsed source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Location {
+    /// byte offset from the start of the source
+    pub offset: usize,
+    /// line number, start from 1
+    pub line: usize,
+    /// column number in chars, start from 1
+    pub column: usize,
+    /// names of the elements from the root to the one that failed, e.g. `["svg", "path"]`
+    pub element: Vec<String>,
+}
+
+impl Location {
+    /// locate `input` in `source`, `input` should be a slice of `source`
+    pub fn new(source: &str, input: &str, element: Vec<String>) -> Self {
+        let start = source.as_ptr() as usize;
+        let at = input.as_ptr() as usize;
+        let offset = if at >= start && at <= start + source.len() {
+            at - start
+        } else {
+            source.len().saturating_sub(input.len())
+        };
+        let before = source.get(..offset).unwrap_or(source);
+        let line = before.matches('\n').count() + 1;
+        let column = before
+            .rsplit('\n')
+            .next()
+            .map(|l| l.chars().count())
+            .unwrap_or_default()
+            + 1;
+
+        Location {
+            offset,
+            line,
+            column,
+            element,
+        }
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        if !self.element.is_empty() {
+            write!(f, " in <{}>", self.element.join(" > "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors of parsing svg documents and values.
+#[derive(Debug)]
+pub enum Error {
+    /// an element which this crate can not handle
+    UnsupportedElement { name: String, location: Location },
+    /// the value of an attribute can not be parsed
+    InvalidAttribute {
+        name: String,
+        value: String,
+        reason: String,
+        location: Location,
+    },
+    /// an element is opened but never closed
+    UnterminatedTag { name: String, location: Location },
+    /// elements are nested deeper than [`MAX_DEPTH`](crate::children::MAX_DEPTH)
+    TooDeep { name: String, location: Location },
+    /// path data (`d` attribute) which does not follow the path grammar, `rest` is the part from the error on
+    InvalidPathData { rest: String, location: Location },
+    /// the xml declaration `<?xml ... ?>` is broken
+    InvalidHeader { location: Location },
+    /// any other syntax error
+    UnexpectedInput { found: String, location: Location },
+    /// a value parsed without a source, e.g. `"2".parse::<Opacity>()`
+    InvalidValue { value: String, expected: &'static str },
+    /// a `url(#id)` reference (gradient, clip path...) is under a transform which can not be
+    /// flattened, the referenced element would keep the old coordinates
+    TransformedReference { value: String },
+    Io(std::io::Error),
+}
+
+impl Error {
+    pub fn invalid_value(value: &str, expected: &'static str) -> Self {
+        Error::InvalidValue {
+            value: value.to_string(),
+            expected,
+        }
+    }
+    /// location in the source, values parsed without a source and io errors have none
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::UnsupportedElement { location, .. }
+            | Error::InvalidAttribute { location, .. }
+            | Error::UnterminatedTag { location, .. }
+            | Error::TooDeep { location, .. }
+            | Error::InvalidPathData { location, .. }
+            | Error::InvalidHeader { location }
+            | Error::UnexpectedInput { location, .. } => Some(location),
+            Error::InvalidValue { .. } | Error::TransformedReference { .. } | Error::Io(_) => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnsupportedElement { name, location } => {
+                write!(f, "unsupported element `<{}>` at {}", name, location)
+            }
+            Error::InvalidAttribute {
+                name,
+                value,
+                reason,
+                location,
+            } => write!(
+                f,
+                "invalid value `{}` of attribute `{}` at {}: {}",
+                value, name, location, reason
+            ),
+            Error::UnterminatedTag { name, location } => {
+                write!(f, "unterminated tag `<{}>` at {}", name, location)
+            }
+            Error::TooDeep { name, location } => write!(
+                f,
+                "element `<{}>` at {} is nested deeper than {} levels",
+                name,
+                location,
+                crate::children::MAX_DEPTH
+            ),
+            Error::InvalidPathData { rest, location } => {
+                write!(f, "invalid path data `{}` at {}", rest, location)
+            }
+            Error::InvalidHeader { location } => {
+                write!(f, "invalid xml declaration at {}", location)
+            }
+            Error::UnexpectedInput { found, location } => {
+                write!(f, "unexpected input `{}` at {}", found, location)
+            }
+            Error::InvalidValue { value, expected } => {
+                write!(f, "invalid value `{}`, expected {}", value, expected)
+            }
+            Error::TransformedReference { value } => {
+                write!(f, "can not flatten a transform of `{}`, the referenced element would not follow", value)
+            }
+            Error::Io(e) => write!(f, "io error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// What went wrong in a [`ParseError`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    Nom(nom::error::ErrorKind),
+    UnsupportedElement(String),
+    InvalidAttribute { name: String, reason: String },
+    UnterminatedTag(String),
+    TooDeep(String),
+    InvalidPathData,
+    InvalidHeader,
+}
+
+/// The error of the nom parsers in this crate, it keeps the input where the error happened,
+/// use [`ParseError::into_error`] to locate it in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError<I> {
+    pub input: I,
+    pub kind: ErrorKind,
+    /// element names from the failed one to the root
+    pub element: Vec<String>,
+}
+
+impl<I> ParseError<I> {
+    pub fn new(input: I, kind: ErrorKind) -> Self {
+        ParseError {
+            input,
+            kind,
+            element: vec![],
+        }
+    }
+    /// mark that the error happened inside the element `name`
+    pub fn within(mut self, name: &str) -> Self {
+        self.element.push(name.to_string());
+        self
+    }
+}
+
+impl ParseError<&str> {
+    /// build the public [`Error`], `source` is the whole text which was parsed
+    pub fn into_error(self, source: &str) -> Error {
+        let input = self.input;
+        let location = Location::new(source, input, self.element.into_iter().rev().collect());
+        match self.kind {
+            ErrorKind::UnsupportedElement(name) => Error::UnsupportedElement { name, location },
+            ErrorKind::InvalidAttribute { name, reason } => Error::InvalidAttribute {
+                name,
+                value: input.to_string(),
+                reason,
+                location,
+            },
+            ErrorKind::UnterminatedTag(name) => Error::UnterminatedTag { name, location },
+            ErrorKind::TooDeep(name) => Error::TooDeep { name, location },
+            ErrorKind::InvalidPathData => Error::InvalidPathData {
+                rest: input.to_string(),
+                location,
+            },
+            ErrorKind::InvalidHeader => Error::InvalidHeader { location },
+            ErrorKind::Nom(_) => Error::UnexpectedInput {
+                found: input.chars().take(16).collect(),
+                location,
+            },
+        }
+    }
+}
+
+impl<I> nom::error::ParseError<I> for ParseError<I> {
+    fn from_error_kind(input: I, kind: nom::error::ErrorKind) -> Self {
+        ParseError::new(input, ErrorKind::Nom(kind))
+    }
+
+    fn append(_: I, _: nom::error::ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<I, E> nom::error::FromExternalError<I, E> for ParseError<I> {
+    fn from_external_error(input: I, kind: nom::error::ErrorKind, _: E) -> Self {
+        ParseError::new(input, ErrorKind::Nom(kind))
+    }
+}
+
+/// convert a nom error of a whole document into [`Error`]
+pub fn into_error(source: &str, e: nom::Err<ParseError<&str>>) -> Error {
+    match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => e.into_error(source),
+        nom::Err::Incomplete(_) => Error::UnexpectedInput {
+            found: String::new(),
+            location: Location::new(source, &source[source.len()..], vec![]),
+        },
+    }
+}
+
+/// parse the value of attribute `name`, a failure points to `value` in the source
+pub fn attribute<'a, T>(name: &str, value: &'a str) -> Result<T, nom::Err<ParseError<&'a str>>>
+where
+    T: std::str::FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|e: T::Err| {
+        nom::Err::Failure(ParseError::new(
+            value,
+            ErrorKind::InvalidAttribute {
+                name: name.to_string(),
+                reason: e.to_string(),
+            },
+        ))
+    })
+}
+
+/// parse attribute `name` of an element if it exists, see [`attribute`]
+pub fn attribute_in<'a, T>(
+    kvs: &std::collections::HashMap<&str, &'a str>,
+    name: &str,
+) -> Result<Option<T>, nom::Err<ParseError<&'a str>>>
+where
+    T: std::str::FromStr,
+    T::Err: Display,
+{
+    kvs.get(name).map(|v| attribute(name, v)).transpose()
+}
+
+#[cfg(test)]
+mod test_error {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let source = "<svg>\n  <path d=\"M0\"/>\n</svg>";
+        let at = source.find("M0").unwrap();
+        let location = Location::new(source, &source[at..at + 2], vec!["svg".to_string()]);
+        assert_eq!(location.offset, at);
+        assert_eq!(location.line, 2);
+        assert_eq!(location.column, 12);
+        assert_eq!(location.to_string(), "line 2, column 12 in <svg>");
+    }
+}
@@ -0,0 +1,171 @@
+//! Re-frame an icon into another `viewBox`.
+
+use crate::error::Error;
+use crate::transform::{Matrix, Transform};
+use crate::view_box::{PreserveAspectRatio, ViewBox};
+use crate::Svg;
+
+/// How [`Svg::fit`] frames the content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitOptions {
+    /// the `viewBox` of the result
+    pub view_box: ViewBox,
+    /// space kept empty on each side of the content, in units of `view_box`
+    pub padding: f32,
+    /// how the content is scaled and aligned into the padded `view_box`
+    pub aspect: PreserveAspectRatio,
+    /// crop to the content, else the current `viewBox` is the frame which is fitted
+    pub crop: bool,
+    /// crop to the strokes too, not only to the filled areas
+    pub include_stroke: bool,
+}
+
+impl Default for FitOptions {
+    /// crop to the content with its strokes and center it in `0 0 24 24`
+    fn default() -> Self {
+        FitOptions {
+            view_box: ViewBox::new(0_f32, 0_f32, 24_f32, 24_f32),
+            padding: 0_f32,
+            aspect: PreserveAspectRatio::default(),
+            crop: true,
+            include_stroke: true,
+        }
+    }
+}
+
+impl Svg {
+    /// the current frame: the `viewBox`, or `0 0 width height`
+    fn frame(&self) -> ViewBox {
+        let context = self.length_context();
+        self.view_box
+            .unwrap_or_else(|| ViewBox::new(0_f32, 0_f32, context.width, context.height))
+    }
+    /// scale and move all elements into `options.view_box` and set it as the `viewBox`.
+    ///
+    /// the geometry is rewritten (see [`Svg::flatten_transforms`]), no transform is left.
+    /// `width` and `height` are not changed. returns the transform which was applied,
+    /// or the error of flattening and the document is not changed
+    ///
+    /// ## Example
+    /// ```rust
+    /// use svg_icon::{fit::FitOptions, Svg};
+    ///
+    /// let mut svg: Svg = r#"<svg viewBox="0 0 48 48"><rect x="8" y="8" width="16" height="32"/></svg>"#
+    ///     .parse()
+    ///     .unwrap();
+    /// svg.fit(&FitOptions {
+    ///     padding: 2.0,
+    ///     ..Default::default()
+    /// }).unwrap();
+    /// assert_eq!(
+    ///     svg.to_string(),
+    ///     r#"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"><rect x="7" y="2" width="10" height="20"/></svg>"#
+    /// );
+    /// ```
+    pub fn fit(&mut self, options: &FitOptions) -> Result<Matrix, Error> {
+        let content = match options.include_stroke {
+            true => self.stroke_bbox(),
+            false => self.bbox(),
+        };
+        let frame = match content {
+            Some(bbox) if options.crop => {
+                ViewBox::new(bbox.min_x, bbox.min_y, bbox.width(), bbox.height())
+            }
+            _ => self.frame(),
+        };
+        let padding = options.padding;
+        let target = ViewBox::new(
+            options.view_box.min_x + padding,
+            options.view_box.min_y + padding,
+            (options.view_box.width - 2_f32 * padding).max(0_f32),
+            (options.view_box.height - 2_f32 * padding).max(0_f32),
+        );
+        let matrix = options.aspect.matrix(&frame, &target);
+        // the transform of the root is already in the bounding box, it is applied first
+        let root = self.common.transform.as_ref().map(|t| t.matrix());
+        let all = matrix.multiply(&root.unwrap_or(Matrix::IDENTITY));
+        let previous = self.common.transform.replace(Transform::from(all));
+        if let Err(e) = self.flatten_transforms() {
+            self.common.transform = previous;
+            return Err(e);
+        }
+        self.view_box = Some(options.view_box);
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod test_fit {
+    use super::*;
+    use crate::bbox::BBox;
+    use crate::view_box::Align;
+
+    #[test]
+    fn test_fit() {
+        let source = r##"<svg viewBox="0 0 1024 1024" stroke="#000" stroke-width="64"><path d="M128 256 H896"/><circle cx="512" cy="640" r="128" stroke="none"/></svg>"##;
+        let mut svg: Svg = source.parse().unwrap();
+        svg.fit(&FitOptions {
+            padding: 2.0,
+            ..Default::default()
+        }).unwrap();
+        // the content is 768 x 544 with the stroke, scaled by 20 / 768
+        assert_eq!(svg.view_box, Some(ViewBox::new(0.0, 0.0, 24.0, 24.0)));
+        let bbox = svg.stroke_bbox().unwrap();
+        let half = 544.0 * 20.0 / 768.0 / 2.0;
+        let expected = BBox::new(2.0, 12.0 - half, 22.0, 12.0 + half);
+        for (a, b) in [
+            (bbox.min_x, expected.min_x),
+            (bbox.min_y, expected.min_y),
+            (bbox.max_x, expected.max_x),
+            (bbox.max_y, expected.max_y),
+        ] {
+            assert!((a - b).abs() < 1e-3, "{:?} != {:?}", bbox, expected);
+        }
+        assert!(svg.common.transform.is_none());
+        assert!(!svg.to_string().contains("transform"));
+
+        // aligned to the top left, without the strokes
+        let mut svg: Svg = source.parse().unwrap();
+        svg.fit(&FitOptions {
+            aspect: PreserveAspectRatio {
+                align: Align::XMinYMin,
+                ..Default::default()
+            },
+            include_stroke: false,
+            ..Default::default()
+        }).unwrap();
+        let bbox = svg.bbox().unwrap();
+        assert!(bbox.min_x.abs() < 1e-3 && bbox.min_y.abs() < 1e-3 && (bbox.max_x - 24.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_fit_frame() {
+        // the whole frame, not the content, with the transform of the root
+        let mut svg: Svg = r#"<svg viewBox="0 0 16 16" transform="translate(1 1)"><rect width="8" height="8"/></svg>"#
+            .parse()
+            .unwrap();
+        let matrix = svg.fit(&FitOptions {
+            crop: false,
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(matrix, Matrix::new(1.5, 0.0, 0.0, 1.5, 1.5, 1.5));
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"><rect width="12" height="12" x="1.5" y="1.5"/></svg>"#
+        );
+
+        // nothing to crop to
+        let mut svg: Svg = r#"<svg width="48" height="48"></svg>"#.parse().unwrap();
+        svg.fit(&FitOptions::default()).unwrap();
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg width="48" height="48" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"></svg>"#
+        );
+
+        // a clip path would not be scaled with the content
+        let source = r#"<svg viewBox="0 0 48 48"><rect width="8" height="8" clip-path="url(#c)"/></svg>"#;
+        let mut svg: Svg = source.parse().unwrap();
+        assert!(svg.fit(&FitOptions::default()).is_err());
+        assert_eq!(svg.to_string(), source.parse::<Svg>().unwrap().to_string());
+    }
+}
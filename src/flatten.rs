@@ -0,0 +1,306 @@
+//! Bake `transform` attributes into the coordinates of the elements.
+
+use crate::bbox::Inherited;
+use crate::children::{Child, CommonKVs, Curves, DashArray, DashOffset};
+use crate::error::Error;
+use crate::length::{resolve, Axis, Length, LengthContext};
+use crate::transform::Transform;
+use crate::value::Auto;
+use crate::Svg;
+
+/// unknown elements which are rendered and take a `transform`, other ones (`<title>`, `<defs>`,
+/// `<clipPath>`...) are not drawn where they are and are left as they are
+const RENDERED: [&str; 6] = ["a", "foreignObject", "image", "switch", "text", "use"];
+
+/// the values of an element with `common`, its own transform is taken out of it
+fn take(parent: &Inherited, common: &mut CommonKVs, context: &LengthContext) -> Inherited {
+    let inherited = parent.of(common, context);
+    common.transform = None;
+    inherited
+}
+
+/// the stroke is drawn in the transformed space, keep its width and dashes when the transform is gone
+fn scale_stroke(inherited: &Inherited, common: &mut CommonKVs) {
+    let scale = inherited.matrix.scale_factor();
+    if scale == 1_f32 {
+        return;
+    }
+    let stroked = inherited.stroked();
+    if stroked || common.stroke_width.is_some() {
+        common.stroke_width = Some(Length::from(inherited.stroke.width * scale));
+    }
+    if !inherited.dashes.is_empty() && (stroked || common.stroke_dasharray.is_some()) {
+        let dashes = inherited.dashes.iter().map(|d| Length::from(d * scale)).collect();
+        common.stroke_dasharray = Some(DashArray(dashes));
+    }
+    if inherited.dash_offset != 0_f32 && (stroked || common.stroke_dashoffset.is_some()) {
+        common.stroke_dashoffset = Some(DashOffset(Length::from(inherited.dash_offset * scale)));
+    }
+}
+
+fn flatten(children: &mut [Child], parent: &Inherited, context: &LengthContext) -> Result<(), Error> {
+    for child in children.iter_mut() {
+        let inherited = match child {
+            Child::Text(_) | Child::Comment(_) => continue,
+            Child::Unknown { name, .. } if !RENDERED.contains(&name.as_str()) => continue,
+            // an unknown element can not be changed, it gets the whole transform
+            Child::Unknown { .. } if parent.matrix.is_identity() => continue,
+            Child::Unknown { attrs, .. } => {
+                let own = attrs
+                    .iter()
+                    .position(|(k, _)| k == "transform")
+                    .map(|index| (index, attrs[index].1.parse::<Transform>()));
+                let matrix = match &own {
+                    Some((_, Ok(own))) => parent.matrix.multiply(&own.matrix()),
+                    // a broken transform disables the rendering, leave it as it is
+                    Some((_, Err(_))) => continue,
+                    None => parent.matrix,
+                };
+                match own {
+                    _ if matrix.is_identity() => attrs.retain(|(k, _)| k != "transform"),
+                    Some((index, _)) => attrs[index].1 = Transform::from(matrix).to_string(),
+                    None => attrs.push(("transform".to_string(), Transform::from(matrix).to_string())),
+                }
+                continue;
+            }
+            _ => {
+                let common = child.common_mut().expect("a known element");
+                take(parent, common, context)
+            }
+        };
+        let matrix = &inherited.matrix;
+        // a paint server or clip path in `userSpaceOnUse` units would not be transformed
+        if let Some(value) = inherited.reference.as_ref().filter(|_| !matrix.is_identity()) {
+            return Err(Error::TransformedReference { value: value.clone() });
+        }
+
+        match child {
+            Child::Group(group) => {
+                scale_stroke(&inherited, &mut group.common);
+                flatten(&mut group.children, &inherited, context)?;
+                continue;
+            }
+            _ if matrix.is_identity() => continue,
+            Child::Path(path) => {
+                if let Some(d) = path.d.as_mut() {
+                    d.transform(matrix);
+                }
+            }
+            Child::Polygon(polygon) => polygon
+                .points
+                .iter_mut()
+                .flat_map(|points| points.0.iter_mut())
+                .for_each(|(x, y)| (*x, *y) = matrix.apply(*x, *y)),
+            Child::Polyline(polyline) => polyline
+                .points
+                .iter_mut()
+                .flat_map(|points| points.0.iter_mut())
+                .for_each(|(x, y)| (*x, *y) = matrix.apply(*x, *y)),
+            Child::Line(line) => {
+                let (x1, y1) = matrix.apply(
+                    resolve(line.x1, Axis::X, context),
+                    resolve(line.y1, Axis::Y, context),
+                );
+                let (x2, y2) = matrix.apply(
+                    resolve(line.x2, Axis::X, context),
+                    resolve(line.y2, Axis::Y, context),
+                );
+                line.x1 = Some(x1.into());
+                line.y1 = Some(y1.into());
+                line.x2 = Some(x2.into());
+                line.y2 = Some(y2.into());
+            }
+            Child::Circle(circle) if matrix.is_similarity() => {
+                let (cx, cy) = matrix.apply(
+                    resolve(circle.cx, Axis::X, context),
+                    resolve(circle.cy, Axis::Y, context),
+                );
+                circle.cx = Some(cx.into());
+                circle.cy = Some(cy.into());
+                if let Some(r) = circle.r {
+                    circle.r = Some((r.resolve(Axis::Other, context) * matrix.scale_factor()).into());
+                }
+            }
+            Child::Ellipse(ellipse) if matrix.is_axis_aligned() => {
+                let (cx, cy) = matrix.apply(
+                    resolve(ellipse.cx, Axis::X, context),
+                    resolve(ellipse.cy, Axis::Y, context),
+                );
+                let (rx, ry) = ellipse.radii(context);
+                ellipse.cx = Some(cx.into());
+                ellipse.cy = Some(cy.into());
+                ellipse.rx = Auto(Some((rx * matrix.a.abs()).into()));
+                ellipse.ry = Auto(Some((ry * matrix.d.abs()).into()));
+            }
+            Child::Rect(rect) if matrix.is_axis_aligned() => {
+                let x = resolve(rect.x, Axis::X, context);
+                let y = resolve(rect.y, Axis::Y, context);
+                let (x1, y1) = matrix.apply(x, y);
+                let (x2, y2) = matrix.apply(
+                    x + resolve(rect.width, Axis::X, context),
+                    y + resolve(rect.height, Axis::Y, context),
+                );
+                if rect.rx.is_some() || rect.ry.is_some() {
+                    let (rx, ry) = rect.radii(context);
+                    rect.rx = Some((rx * matrix.a.abs()).into());
+                    rect.ry = Some((ry * matrix.d.abs()).into());
+                }
+                rect.x = Some(x1.min(x2).into());
+                rect.y = Some(y1.min(y2).into());
+                rect.width = Some((x2 - x1).abs().into());
+                rect.height = Some((y2 - y1).abs().into());
+            }
+            // a skewed or non uniformly scaled shape is a path
+            Child::Circle(_) | Child::Ellipse(_) | Child::Rect(_) => {
+                if let Some(mut path) = child.to_path(context, Curves::Arcs) {
+                    if let Some(d) = path.d.as_mut() {
+                        d.transform(matrix);
+                    }
+                    *child = Child::Path(path);
+                }
+            }
+            Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => {}
+        }
+        if let Some(common) = child.common_mut() {
+            scale_stroke(&inherited, common);
+        }
+    }
+    Ok(())
+}
+
+impl Svg {
+    /// what lengths of this document are resolved against: the size of the `viewBox`,
+    /// or `width` and `height` if there is none
+    pub fn length_context(&self) -> LengthContext {
+        let default = LengthContext::default();
+        match self.view_box {
+            Some(view_box) => LengthContext::new(view_box.width, view_box.height),
+            None => LengthContext::new(
+                self.width
+                    .0
+                    .map_or(default.width, |w| w.resolve(Axis::X, &default)),
+                self.height
+                    .0
+                    .map_or(default.height, |h| h.resolve(Axis::Y, &default)),
+            ),
+        }
+    }
+    /// push every `transform` down into the coordinates, so no element has one afterwards.
+    ///
+    /// circles, ellipses and rects which are skewed or scaled non uniformly become paths,
+    /// stroke widths and dashes are scaled (by the mean of both axes if they are scaled differently),
+    /// lengths of transformed elements are resolved to user units.
+    /// unknown elements which are rendered (`<use>`, `<image>`...) can not be changed, they keep
+    /// the transform of their ancestors, other ones (`<title>`, `<defs>`...) are left as they are.
+    ///
+    /// transformed elements which use a `url(#id)` (a gradient, a clip path...) are an error and
+    /// the document is not changed, the referenced element would keep the old coordinates
+    pub fn flatten_transforms(&mut self) -> Result<(), Error> {
+        let mut flat = self.clone();
+        let context = flat.length_context();
+        let mut root = take(&Inherited::default(), &mut flat.common, &context);
+        // css may reference paint servers from anywhere
+        root.reference = root.reference.or_else(|| {
+            let sheet = flat.style_sheet();
+            let mut values = sheet.rules.iter().flat_map(|rule| &rule.declarations);
+            values.find(|d| d.value.contains("url(")).map(|d| d.value.clone())
+        });
+        flatten(&mut flat.children, &root, &context)?;
+        *self = flat;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_flatten {
+    use crate::children::{Child, Command};
+    use crate::Svg;
+
+    fn flatten(source: &str) -> Svg {
+        let mut svg: Svg = source.parse().unwrap();
+        svg.flatten_transforms().unwrap();
+        svg
+    }
+
+    #[test]
+    fn test_flatten_groups() {
+        let svg = flatten(
+            r##"<svg viewBox="0 0 24 24"><g transform="translate(10 20)" stroke="#000" stroke-width="2"><g transform="scale(2)"><path d="M1 1 h2 A1 1 0 0 1 4 4"/><circle cx="1" cy="1" r="1" stroke="none"/><rect width="1" height="2" rx="0.5"/><line x2="1" y2="1"/><polygon points="0,0 1,0 1,1"/></g></g></svg>"##,
+        );
+        assert_eq!(
+            svg.to_string(),
+            r##"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"><g stroke="#000000" stroke-width="2"><g stroke-width="4"><path d="M 12,22 h 4 A 2 2 0 0 1 18,28" stroke-width="4"/><circle cx="12" cy="22" r="2" stroke="none"/><rect width="2" height="4" rx="1" x="10" y="20" ry="1" stroke-width="4"/><line x2="12" y2="22" x1="10" y1="20" stroke-width="4"/><polygon points="10,20 12,20 12,22" stroke-width="4"/></g></g></svg>"##
+        );
+    }
+
+    #[test]
+    fn test_shapes_become_paths() {
+        let svg = flatten(
+            r#"<svg viewBox="0 0 24 24"><circle cx="1" cy="1" r="1" transform="scale(2 1)"/><ellipse rx="2" ry="1" transform="scale(1 3)"/><rect width="2" height="1" transform="skewX(45)"/><use href="x" transform="scale(2)"/><g transform="translate(1)"><use href="x" transform="scale(2)"/></g></svg>"#,
+        );
+        let Child::Path(circle) = &svg.children[0] else {
+            panic!("expect a path");
+        };
+        let d = circle.d.as_ref().unwrap();
+        assert!(matches!(d.0[1], Command::EllipticalArc(a) if a.rx == 2.0 && a.ry == 1.0 && a.x == 2.0 && a.y == 2.0));
+        assert!(circle.common.transform.is_none());
+        // scaled on the axes, still an ellipse
+        assert!(matches!(&svg.children[1], Child::Ellipse(e) if e.ry.0 == Some(3.0.into())));
+        let Child::Path(rect) = &svg.children[2] else {
+            panic!("expect a path");
+        };
+        assert_eq!(rect.d.as_ref().unwrap().to_string(), "M 0,0 L 2,0 L 3,1 L 1,1 L 0,0 Z");
+        assert!(matches!(&svg.children[3], Child::Unknown { attrs, .. } if attrs[1].1 == "scale(2)"));
+        let Child::Group(group) = &svg.children[4] else {
+            panic!("expect a group");
+        };
+        assert!(matches!(&group.children[0], Child::Unknown { attrs, .. } if attrs[1].1 == "matrix(2 0 0 2 1 0)"));
+    }
+
+    #[test]
+    fn test_not_rendered() {
+        let svg = flatten(
+            r#"<svg><g transform="scale(2)"><title>icon</title><defs><path id="a" d="M0 0 H1"/></defs><use href="a"/></g></svg>"#,
+        );
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg><g><title>icon</title><defs><path id="a" d="M 0,0 H 1"/></defs><use href="a" transform="matrix(2 0 0 2 0 0)"/></g></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        for source in [
+            r#"<svg><g transform="scale(2)" clip-path="url(#c)"><path d="M0 0 H1"/></g></svg>"#,
+            r#"<svg fill="url(#g)"><path d="M0 0 H1" transform="scale(2)"/></svg>"#,
+            r#"<svg><style>path{fill:url(#g)}</style><path d="M0 0 H1" transform="scale(2)"/></svg>"#,
+        ] {
+            let mut svg: Svg = source.parse().unwrap();
+            let before = svg.to_string();
+            let err = svg.flatten_transforms().unwrap_err();
+            assert!(matches!(err, crate::Error::TransformedReference { ref value } if value.starts_with("url(#")));
+            assert_eq!(svg.to_string(), before);
+        }
+        // without a transform around it, the reference is kept as it is
+        let svg = flatten(r#"<svg><path fill="url(#g)" d="M0 0 H1"/><g transform="scale(2)"><path d="M0 0 H1"/></g></svg>"#);
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg><path fill="url(#g)" d="M 0,0 H 1"/><g><path d="M 0,0 H 2"/></g></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_dashes() {
+        let svg = flatten(
+            r##"<svg><g transform="scale(2)" stroke="#000" stroke-dasharray="1 2" stroke-dashoffset="1"><path d="M0 0 H1"/><path d="M0 0 H1" stroke="none"/></g></svg>"##,
+        );
+        let Child::Group(group) = &svg.children[0] else {
+            panic!("expect a group");
+        };
+        assert_eq!(
+            group.children[0].to_string(),
+            r#"<path d="M 0,0 H 2" stroke-width="2" stroke-dasharray="2 4" stroke-dashoffset="2"/>"#
+        );
+        assert_eq!(group.children[1].to_string(), r#"<path d="M 0,0 H 2" stroke="none"/>"#);
+    }
+}
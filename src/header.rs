@@ -1,60 +1,94 @@
-use nom::IResult;
+use nom::error::ErrorKind as NomErrorKind;
+
+use crate::error::{attribute, ErrorKind, IResult, ParseError};
 
 use super::{encode::EnCoding, parser::parse_properties};
 use std::fmt::Display;
 use std::fmt::{self, Formatter};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Header {
     version: Option<String>,
     encoding: Option<EnCoding>,
+    /// the other pseudo-attributes (`standalone`...) in source order
+    others: Vec<(String, String)>,
 }
 
 impl Header {
+    /// parse `<?xml version="1.0" encoding="UTF-8"?>`
+    ///
+    /// return a recoverable `nom::Err::Error` if `s` does not start with a xml declaration
+    /// and a `nom::Err::Failure` if the declaration is broken
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> IResult<&str, Header> {
-        // parse `<?xml version="1.0" encoding="UTF-8"?>`
         let mut s = s.trim();
-        let _ = s.starts_with("<?xml")
-            || return Err(nom::Err::Error(nom::error::Error::new(
-                s,
-                nom::error::ErrorKind::Tag,
-            )));
-        s = s.trim_start_matches("<?xml");
+        match s.strip_prefix("<?xml") {
+            // not `<?xml-stylesheet ...?>`
+            Some(rest) if rest.starts_with(char::is_whitespace) => s = rest,
+            _ => return Err(nom::Err::Error(ParseError::new(s, ErrorKind::Nom(NomErrorKind::Tag)))),
+        }
         // parse version and encoding
-        let (mut s, kvs) = parse_properties(s).unwrap();
+        let (mut s, kvs) = parse_properties(s)?;
         let mut version = None;
         let mut encoding = None;
+        let mut others = vec![];
 
-        kvs.into_iter().for_each(|(k, v)| match k {
-            "version" => {
-                let _ = version.replace(v.to_string());
-            }
-            "encoding" => {
-                let _ = encoding.replace(v.parse().unwrap());
+        for (k, v) in kvs {
+            match k {
+                "version" => {
+                    let _ = version.replace(v.to_string());
+                }
+                "encoding" => {
+                    let _ = encoding.replace(attribute(k, v)?);
+                }
+                _ => others.push((k.to_string(), v.to_string())),
             }
-            _ => {}
-        });
+        }
 
         s = s.trim();
-        if !s.starts_with("?>") {
-            return Err(nom::Err::Error(nom::error::Error::new(
-                s,
-                nom::error::ErrorKind::Tag,
-            )));
-        } else {
-            s = s.trim_start_matches("?>");
+        match s.strip_prefix("?>") {
+            Some(rest) => s = rest,
+            None => return Err(nom::Err::Failure(ParseError::new(s, ErrorKind::InvalidHeader))),
         }
 
-        Ok((s, Header { version, encoding }))
+        Ok((
+            s,
+            Header {
+                version,
+                encoding,
+                others,
+            },
+        ))
     }
 }
 
 impl Display for Header {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.write_fmt(format_args!(
-            "<?xml version=\"{}\" encoding=\"{}\"?>",
+            "<?xml version=\"{}\" encoding=\"{}\"",
             self.version.as_ref().unwrap_or(&"1.0".to_string()),
             self.encoding.as_ref().unwrap_or(&EnCoding::default())
-        ))
+        ))?;
+        for (k, v) in &self.others {
+            write!(f, " {}=\"{}\"", k, v.replace('"', "&quot;"))?;
+        }
+        write!(f, "?>")
+    }
+}
+
+#[cfg(test)]
+mod test_header {
+    use super::*;
+
+    #[test]
+    fn test_others() {
+        let source = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><svg>"#;
+        let (rest, header) = Header::from_str(source).unwrap();
+        assert_eq!(rest, "<svg>");
+        assert_eq!(header.others, vec![("standalone".to_string(), "no".to_string())]);
+        assert_eq!(header.to_string(), r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#);
+        assert_eq!(Header::default().to_string(), r#"<?xml version="1.0" encoding="UTF-8"?>"#);
     }
 }
@@ -0,0 +1,265 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::multispace0,
+    combinator::{map, opt},
+    sequence::{delimited, pair},
+};
+
+use crate::{
+    error::{Error, IResult},
+    impl_auto_value,
+    parser::number,
+    value::{Auto, AutoValue},
+};
+
+/// The unit of a [`Length`], `None` is a plain number in user units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Unit {
+    #[default]
+    None,
+    Px,
+    Pt,
+    Pc,
+    Mm,
+    Cm,
+    In,
+    Em,
+    Ex,
+    Percent,
+}
+
+impl Unit {
+    const ALL: [(&'static str, Unit); 9] = [
+        ("px", Unit::Px),
+        ("pt", Unit::Pt),
+        ("pc", Unit::Pc),
+        ("mm", Unit::Mm),
+        ("cm", Unit::Cm),
+        ("in", Unit::In),
+        ("em", Unit::Em),
+        ("ex", Unit::Ex),
+        ("%", Unit::Percent),
+    ];
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = Unit::ALL
+            .iter()
+            .find(|(_, unit)| unit == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default();
+        f.write_str(name)
+    }
+}
+
+/// Which size of the viewport a percentage refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// `x`, `cx`, `width`, `rx`...
+    X,
+    /// `y`, `cy`, `height`, `ry`...
+    Y,
+    /// lengths without a direction, `r` or `stroke-width`:
+    /// relative to the normalized diagonal `sqrt((width² + height²) / 2)`
+    Other,
+}
+
+/// What a [`Length`] is resolved against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LengthContext {
+    /// size of the viewport (or the `viewBox`) in user units, for percentages
+    pub width: f32,
+    pub height: f32,
+    /// font size in user units, for `em` and `ex`
+    pub font_size: f32,
+    /// dots per inch, for the absolute units
+    pub dpi: f32,
+}
+
+impl LengthContext {
+    pub fn new(width: f32, height: f32) -> Self {
+        LengthContext {
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for LengthContext {
+    /// a 100 x 100 viewport, 16px font and 96 dpi like the browsers
+    fn default() -> Self {
+        LengthContext {
+            width: 100_f32,
+            height: 100_f32,
+            font_size: 16_f32,
+            dpi: 96_f32,
+        }
+    }
+}
+
+/// A `<length>` or `<percentage>` value: `24`, `1.5em`, `24px`, `50%`.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::length::{Axis, Length, LengthContext, Unit};
+///
+/// let r: Length = "50%".parse().unwrap();
+/// assert_eq!(r.unit, Unit::Percent);
+/// assert_eq!(r.resolve(Axis::X, &LengthContext::new(24.0, 24.0)), 12.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Length {
+    pub value: f32,
+    pub unit: Unit,
+}
+
+impl Length {
+    pub fn new(value: f32, unit: Unit) -> Self {
+        Length { value, unit }
+    }
+    /// nom parser of a length, a number followed by an optional unit
+    pub fn parser(s: &str) -> IResult<&str, Length> {
+        let (s, (value, unit)) = pair(
+            number,
+            opt(alt((
+                map(tag("px"), |_| Unit::Px),
+                map(tag("pt"), |_| Unit::Pt),
+                map(tag("pc"), |_| Unit::Pc),
+                map(tag("mm"), |_| Unit::Mm),
+                map(tag("cm"), |_| Unit::Cm),
+                map(tag("in"), |_| Unit::In),
+                map(tag("em"), |_| Unit::Em),
+                map(tag("ex"), |_| Unit::Ex),
+                map(tag("%"), |_| Unit::Percent),
+            ))),
+        )(s)?;
+        Ok((s, Length::new(value, unit.unwrap_or_default())))
+    }
+    /// the length in user units
+    pub fn resolve(&self, axis: Axis, context: &LengthContext) -> f32 {
+        let LengthContext {
+            width,
+            height,
+            font_size,
+            dpi,
+        } = *context;
+        let scale = match self.unit {
+            Unit::None | Unit::Px => 1_f32,
+            Unit::Pt => dpi / 72_f32,
+            Unit::Pc => dpi / 6_f32,
+            Unit::Mm => dpi / 25.4,
+            Unit::Cm => dpi / 2.54,
+            Unit::In => dpi,
+            Unit::Em => font_size,
+            // the x-height of most fonts is about half of the font size
+            Unit::Ex => font_size / 2_f32,
+            Unit::Percent => {
+                let size = match axis {
+                    Axis::X => width,
+                    Axis::Y => height,
+                    Axis::Other => ((width * width + height * height) / 2_f32).sqrt(),
+                };
+                size / 100_f32
+            }
+        };
+        self.value * scale
+    }
+}
+
+/// resolve an optional attribute, a missing one is `0`
+pub(crate) fn resolve(length: Option<Length>, axis: Axis, context: &LengthContext) -> f32 {
+    length.map_or(0_f32, |length| length.resolve(axis, context))
+}
+
+impl From<f32> for Length {
+    fn from(value: f32) -> Self {
+        Length::new(value, Unit::None)
+    }
+}
+
+impl FromStr for Length {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match delimited(multispace0, Length::parser, multispace0)(s) {
+            Ok(("", length)) => Ok(length),
+            _ => Err(Error::invalid_value(s, "a length like `24`, `1.5em` or `50%`")),
+        }
+    }
+}
+
+impl Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.value, self.unit)
+    }
+}
+
+impl_auto_value!(Length);
+
+#[cfg(test)]
+mod test_length {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            ("24", Length::new(24.0, Unit::None)),
+            (" 1.5em ", Length::new(1.5, Unit::Em)),
+            ("24px", Length::new(24.0, Unit::Px)),
+            ("50%", Length::new(50.0, Unit::Percent)),
+            ("1.5pt", Length::new(1.5, Unit::Pt)),
+            ("-1e1mm", Length::new(-10.0, Unit::Mm)),
+            (".5in", Length::new(0.5, Unit::In)),
+            ("2ex", Length::new(2.0, Unit::Ex)),
+            ("1pc", Length::new(1.0, Unit::Pc)),
+            ("1cm", Length::new(1.0, Unit::Cm)),
+        ];
+        for (input, length) in cases {
+            assert_eq!(input.parse::<Length>().unwrap(), length, "{:?}", input);
+            assert_eq!(
+                length.to_string().parse::<Length>().unwrap(),
+                length,
+                "{:?}",
+                input
+            );
+        }
+        for invalid in ["", "px", "24 px", "24PX", "24km", "1.5em2"] {
+            assert!(invalid.parse::<Length>().is_err(), "{:?}", invalid);
+        }
+        assert_eq!(Length::new(50.0, Unit::Percent).to_string(), "50%");
+        assert_eq!(Length::from(1.5).to_string(), "1.5");
+    }
+
+    #[test]
+    fn test_resolve() {
+        let context = LengthContext::new(200.0, 100.0);
+        let resolve = |s: &str, axis| s.parse::<Length>().unwrap().resolve(axis, &context);
+        assert_eq!(resolve("24", Axis::X), 24.0);
+        assert_eq!(resolve("24px", Axis::X), 24.0);
+        assert_eq!(resolve("1in", Axis::X), 96.0);
+        assert_eq!(resolve("72pt", Axis::X), 96.0);
+        assert_eq!(resolve("6pc", Axis::X), 96.0);
+        assert!((resolve("2.54cm", Axis::X) - 96.0).abs() < 1e-3);
+        assert!((resolve("25.4mm", Axis::X) - 96.0).abs() < 1e-3);
+        assert_eq!(resolve("1.5em", Axis::X), 24.0);
+        assert_eq!(resolve("1ex", Axis::X), 8.0);
+        assert_eq!(resolve("50%", Axis::X), 100.0);
+        assert_eq!(resolve("50%", Axis::Y), 50.0);
+        let diagonal = ((200_f32 * 200.0 + 100.0 * 100.0) / 2.0).sqrt();
+        assert_eq!(resolve("100%", Axis::Other), diagonal);
+
+        let print = LengthContext {
+            dpi: 300.0,
+            ..context
+        };
+        assert_eq!(Length::new(1.0, Unit::In).resolve(Axis::X, &print), 300.0);
+    }
+}
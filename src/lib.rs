@@ -1,26 +1,52 @@
+pub mod bbox;
+pub mod builder;
 pub mod children;
 pub mod encode;
+pub mod error;
+pub mod fit;
+pub mod flatten;
 pub mod header;
+pub mod length;
 pub mod macros;
+pub mod optimize;
+pub mod paint;
 pub mod parser;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "serde")]
+pub mod serialize;
+pub mod style;
+pub mod transform;
 pub mod value;
+pub mod view_box;
+pub mod write;
 
-use std::collections::HashMap;
 use std::fmt::Display;
 use std::str::FromStr;
 
-use children::Child;
+use children::{Child, CommonKVs, Curves};
+use error::{attribute_in, into_error, ErrorKind, IResult, Location, ParseError};
 use header::Header;
-use nom::bytes::complete::{tag, take_until1};
-
-use nom::sequence::{delimited, preceded};
-use parser::{parse_properties, trim};
+use length::Length;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_till, take_until};
+use nom::character::complete::multispace0;
+use nom::combinator::{opt, recognize, value};
+use nom::error::ErrorKind as NomErrorKind;
+use nom::multi::many0;
+use nom::sequence::{delimited, preceded, tuple};
+use parser::trim;
 use value::Auto;
+use view_box::{PreserveAspectRatio, ViewBox};
+
+pub use error::Error;
 
 /// # Svg
 /// use `Svg::from_path` or `&str.parse().unwrap()` to parse a `Svg` from a file path.
 /// ## Example
-/// ```rust
+/// ```rust,no_run
+/// use svg_icon::{svg, Svg};
+///
 /// let svg1 = Svg::from_path("E:/Rust/try/makepad/Gen-UI/gen/middleware/svg_icon/a.svg").unwrap();
 /// let svg_str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100" viewBox="0 0 100 100" fill="none"></svg>"#;
 /// let svg2: Svg = svg_str.parse().unwrap();
@@ -29,141 +55,180 @@ use value::Auto;
 /// };
 /// ```
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Svg {
     pub header: Option<Header>,
-    pub x: Option<f32>,
-    pub y: Option<f32>,
-    pub height: Auto<f32>,
-    pub width: Auto<f32>,
-    pub view_box: Option<(u32, u32, u32, u32)>,
-    pub fill: String,
+    /// comments, `<!DOCTYPE>` and processing instructions before `<svg>`, written back as they are
+    pub prolog: Vec<String>,
+    /// `x`, `y`, `fill`, `stroke`... and every other attribute of the `<svg>` tag
+    pub common: CommonKVs,
+    pub height: Auto<Length>,
+    pub width: Auto<Length>,
+    pub view_box: Option<ViewBox>,
+    pub preserve_aspect_ratio: Option<PreserveAspectRatio>,
     pub xmlns: String,
     pub children: Vec<Child>,
 }
 
 impl Svg {
-    pub fn from_path<P>(path: P) -> Result<Self, String>
+    pub fn from_path<P>(path: P) -> Result<Self, Error>
     where
         P: AsRef<std::path::Path>,
     {
-        let s = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-        dbg!(&s);
+        let s = std::fs::read_to_string(path)?;
         s.parse()
     }
-}
-
-impl FromStr for Svg {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    /// nom parser of a whole svg document, use `str::parse` to get a located [`Error`] instead
+    pub fn parser(s: &str) -> IResult<&str, Svg> {
         let mut svg = Svg::default();
         // parse header -----------------------------------------------------------------------
-        let (s, header) = if let Ok((s, header)) = Header::from_str(s) {
-            (s, Some(header))
-        } else {
-            (s, None)
+        let (s, header) = match Header::from_str(s) {
+            Ok((s, header)) => (s, Some(header)),
+            Err(nom::Err::Error(_)) => (s, None),
+            Err(e) => return Err(e),
         };
         svg.header = header;
+        // parse prolog ------------------------------------------------------------------------
+        let (s, prolog) = many0(trim(alt((
+            recognize(tuple((tag("<!--"), take_until("-->"), tag("-->")))),
+            // the internal subset of a doctype may have `>` in its declarations
+            recognize(tuple((
+                tag("<!DOCTYPE"),
+                take_till(|c| c == '[' || c == '>'),
+                opt(tuple((tag("["), take_until("]"), tag("]"), multispace0))),
+                tag(">"),
+            ))),
+            recognize(tuple((tag("<?"), take_until("?>"), tag("?>")))),
+        ))))(s)?;
+        svg.prolog = prolog.into_iter().map(str::to_string).collect();
         // parse svg tag -----------------------------------------------------------------------
-        let (s, props_str) = delimited(
-            preceded(trim(tag("<")), tag("svg")),
-            trim(take_until1(">")),
-            trim(tag(">")),
-        )(s)
-        .unwrap();
-
-        let kvs: Option<HashMap<&str, &str>> = if !props_str.is_empty() {
-            let (remain, props) = parse_properties(props_str).unwrap();
-            if !remain.is_empty() {
-                return Err("Invalid svg tag props!".to_string());
-            }
-            Some(HashMap::from_iter(props.into_iter()))
-        } else {
-            None
-        };
+        fn in_svg(e: nom::Err<ParseError<&str>>) -> nom::Err<ParseError<&str>> {
+            e.map(|e| e.within("svg"))
+        }
+        let (s, _) = preceded(multispace0, tag("<svg"))(s)?;
+        let (s, (mut common, kvs)) = CommonKVs::from_str(s).map_err(in_svg)?;
+        let (s, empty) = preceded(multispace0, alt((value(true, tag("/>")), value(false, tag(">")))))(s)
+            .map_err(|_: nom::Err<ParseError<&str>>| {
+                nom::Err::Failure(ParseError::new(s, ErrorKind::Nom(NomErrorKind::Tag)).within("svg"))
+            })?;
+        common.remove_others(&["xmlns", "width", "height", "viewBox", "preserveAspectRatio"]);
+        svg.common = common;
 
-        if kvs.is_some() {
-            svg.fill = kvs
-                .as_ref()
-                .unwrap()
-                .get("fill")
-                .unwrap_or(&"none")
-                .to_string();
+        if let Some(kvs) = kvs {
             svg.xmlns = kvs
-                .as_ref()
-                .unwrap()
                 .get("xmlns")
                 .unwrap_or(&"http://www.w3.org/2000/svg")
                 .to_string();
-            svg.x = kvs.as_ref().unwrap().get("x").map(|x| x.parse().unwrap());
-            svg.y = kvs.as_ref().unwrap().get("y").map(|y| y.parse().unwrap());
-            svg.height = Auto::get_from_map(&kvs.as_ref().unwrap(), "height");
-            svg.width = Auto::get_from_map(&kvs.as_ref().unwrap(), "width");
-            svg.view_box = kvs.as_ref().unwrap().get("viewBox").map(|view_box| {
-                let mut iter = view_box.split_whitespace();
-                let x = iter.next().unwrap().parse().unwrap();
-                let y = iter.next().unwrap().parse().unwrap();
-                let width = iter.next().unwrap().parse().unwrap();
-                let height = iter.next().unwrap().parse().unwrap();
-                (x, y, width, height)
-            });
+            svg.height = Auto::get_from_map(&kvs, "height").map_err(in_svg)?;
+            svg.width = Auto::get_from_map(&kvs, "width").map_err(in_svg)?;
+            svg.view_box = attribute_in(&kvs, "viewBox").map_err(in_svg)?;
+            svg.preserve_aspect_ratio =
+                attribute_in(&kvs, "preserveAspectRatio").map_err(in_svg)?;
+        }
+
+        if empty {
+            let (s, _) = multispace0(s)?;
+            return Ok((s, svg));
         }
 
         // children ----------------------------------------------------------------------------
-        let (s, children) = Child::parser(s).unwrap();
+        let (s, children) = Child::parser(s).map_err(in_svg)?;
         svg.children = children;
         let (s, _) = delimited(
             preceded(trim(tag("<")), tag("/")),
             trim(tag("svg")),
             trim(tag(">")),
         )(s)
-        .unwrap();
-        if !s.is_empty() {
-            return Err(format!("Invalid svg tag: {}", s));
+        .map_err(|_: nom::Err<ParseError<&str>>| {
+            nom::Err::Failure(
+                ParseError::new(s, ErrorKind::UnterminatedTag("svg".to_string())).within("svg"),
+            )
+        })?;
+        Ok((s, svg))
+    }
+    /// replace every shape (in groups too) with its equivalent `<path>`, see [`Child::to_path`].
+    ///
+    /// shapes inside unknown elements (`<defs>`, `<clipPath>`...) are kept,
+    /// their lengths may refer to another viewport
+    pub fn shapes_to_paths(&mut self, curves: Curves) {
+        fn convert(children: &mut [Child], context: &length::LengthContext, curves: Curves) {
+            for child in children.iter_mut() {
+                match child {
+                    Child::Group(group) => convert(&mut group.children, context, curves),
+                    Child::Path(_) | Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => {}
+                    shape => {
+                        if let Some(path) = shape.to_path(context, curves) {
+                            *shape = Child::Path(path);
+                        }
+                    }
+                }
+            }
         }
-        Ok(svg)
+        let context = self.length_context();
+        convert(&mut self.children, &context, curves);
     }
 }
 
-impl Display for Svg {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        if let Some(header) = &self.header {
-            s.push_str(&header.to_string());
-        }
-        s.push_str("<svg ");
-        if !self.fill.is_empty() {
-            s.push_str(&format!("fill=\"{}\" ", self.fill));
+impl FromStr for Svg {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (rest, svg) = Svg::parser(s).map_err(|e| into_error(s, e))?;
+        if !rest.is_empty() {
+            return Err(Error::UnexpectedInput {
+                found: rest.chars().take(16).collect(),
+                location: Location::new(s, rest, vec![]),
+            });
         }
+        Ok(svg)
+    }
+}
+
+impl Svg {
+    /// the attributes of the `<svg>` tag which are not in `common`
+    pub(crate) fn own_attributes(&self) -> Vec<(&'static str, String)> {
+        let mut own = vec![];
         if !self.xmlns.is_empty() {
-            s.push_str(&format!("xmlns=\"{}\" ", self.xmlns));
+            own.push(("xmlns", self.xmlns.clone()));
         }
-        if let Some(x) = self.x {
-            s.push_str(&format!("x=\"{}\" ", x));
+        if let Some(view_box) = self.view_box {
+            own.push(("viewBox", view_box.to_string()));
         }
-        if let Some(y) = self.y {
-            s.push_str(&format!("y=\"{}\" ", y));
+        if let Some(preserve_aspect_ratio) = self.preserve_aspect_ratio {
+            own.push(("preserveAspectRatio", preserve_aspect_ratio.to_string()));
         }
-        if let Some(view_box) = self.view_box {
-            s.push_str(&format!(
-                "viewBox=\"{} {} {} {}\" ",
-                view_box.0, view_box.1, view_box.2, view_box.3
-            ));
+        // `auto` is the default, only write it back if it was in the source
+        for (k, v) in [("height", &self.height), ("width", &self.width)] {
+            if v.0.is_some() || self.common.order.iter().any(|o| o == k) {
+                own.push((k, v.to_string()));
+            }
         }
-        s.push_str(&format!("height=\"{}\" ", self.height));
-        s.push_str(&format!("width=\"{}\" ", self.width));
+        own
+    }
+}
 
-        s.push_str(">");
+impl Display for Svg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(header) = &self.header {
+            write!(f, "{}", header)?;
+        }
+        for markup in &self.prolog {
+            write!(f, "{}", markup)?;
+        }
+        write!(f, "<svg")?;
+        self.common.write_attributes(f, self.own_attributes())?;
+        write!(f, ">")?;
         for child in &self.children {
-            s.push_str(&child.to_string());
+            write!(f, "{}", child)?;
         }
-        s.push_str("</svg>");
-        write!(f, "{}", s)
+        write!(f, "</svg>")
     }
 }
 
 #[cfg(test)]
 mod test_svg {
+    use crate::children::{Child, Curves};
 
     #[test]
     fn test_svg() {
@@ -173,13 +238,288 @@ mod test_svg {
         let svg = svg! {
             r##"<?xml version="1.0" encoding="UTF-8"?><svg width="24" height="24" viewBox="0 0 48 48" fill="none" xmlns="http://www.w3.org/2000/svg"><path d="M24 19V4" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/><path d="M12 22L24 19L36 16" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/><path d="M28 30L36 16" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/><path d="M44 30L36 16" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/><path d="M20 36L12 22" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/><path d="M4 36L12 22" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/><path fill-rule="evenodd" clip-rule="evenodd" d="M12 44C16.4183 44 20 40.4183 20 36H4C4 40.4183 7.58172 44 12 44Z" fill="#008000" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/><path fill-rule="evenodd" clip-rule="evenodd" d="M36 38C40.4183 38 44 34.4183 44 30H28C28 34.4183 31.5817 38 36 38Z" fill="#008000" stroke="#008000" stroke-width="4" stroke-linecap="round" stroke-linejoin="round"/></svg>"##
         };
-        dbg!(svg);
-        let svg =
-            Svg::from_path("E:/Rust/try/makepad/Gen-UI/gen/middleware/svg_icon/a.svg").unwrap();
+        dbg!(&svg);
+        let path = std::env::temp_dir().join("svg_icon_test_svg.svg");
+        std::fs::write(&path, svg.to_string()).unwrap();
+        let svg = Svg::from_path(&path).unwrap();
         dbg!(svg.to_string());
         dbg!(svg);
         let svg_str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100" viewBox="0 0 100 100" fill="none"></svg>"#;
         let svg2: Svg = svg_str.parse().unwrap();
         dbg!(svg2);
     }
+
+    const ICON: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg width="24" height="24" viewBox="0 0 48 48" fill="none" xmlns="http://www.w3.org/2000/svg">
+    <circle cx="24" cy="24" r="20" stroke="#000" stroke-width="4"/>
+    <path d="M24 19V4" stroke="#008000" stroke-opacity="0.5" stroke-dasharray="1 2"/>
+    <ellipse cx="1" cy="2" rx="auto" ry="3"/>
+</svg>"##;
+
+    fn parse_err(s: &str) -> crate::Error {
+        s.parse::<super::Svg>().unwrap_err()
+    }
+
+    #[test]
+    fn test_invalid_attribute() {
+        let err = parse_err("<svg>\n  <circle cx=\"1\" r=\"abc\"/>\n</svg>");
+        match &err {
+            crate::Error::InvalidAttribute {
+                name,
+                value,
+                location,
+                ..
+            } => {
+                assert_eq!(name, "r");
+                assert_eq!(value, "abc");
+                assert_eq!((location.line, location.column), (2, 21));
+                assert_eq!(location.offset, 26);
+                assert_eq!(location.element, vec!["svg", "circle"]);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert!(err.to_string().contains("line 2, column 21 in <svg > circle>"));
+
+        let err = parse_err(r#"<svg><path stroke-opacity="2"/></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "stroke-opacity"));
+        let err = parse_err(r#"<svg><path fill="blurple"/></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "fill"));
+        let err = parse_err(r#"<svg><path transform="rotate(1 2)"/></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "transform"));
+        let err = parse_err(r#"<svg width="wide"></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "width"));
+        let err = parse_err(r#"<svg viewBox="0 0 24"></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "viewBox"));
+        let err = parse_err(r#"<svg viewBox="0 0 0 24"></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "viewBox"));
+        let err = parse_err(r#"<svg preserveAspectRatio="middle"></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "preserveAspectRatio"));
+        let err = parse_err(r#"<svg><circle r="1e39"/></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "r"));
+        let err = parse_err(r#"<svg><path stroke-miterlimit="inf"/></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "stroke-miterlimit"));
+        assert!(r#"<svg><path d="M1e39 0"/></svg>"#.parse::<crate::Svg>().is_err());
+    }
+
+    #[test]
+    fn test_css_wide_keywords() {
+        let source = r#"<svg fill="inherit"><path d="M0 0" stroke="Initial" stroke-width="unset"/></svg>"#;
+        let svg: super::Svg = source.parse().unwrap();
+        assert!(svg.common.fill.is_none());
+        assert_eq!(svg.common.get("fill").as_deref(), Some("inherit"));
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg fill="inherit" xmlns="http://www.w3.org/2000/svg"><path d="M 0,0" stroke="Initial" stroke-width="unset"/></svg>"#
+        );
+        // a keyword replaces a typed value and the other way around
+        let mut common = crate::children::CommonKVs::default();
+        common.set("fill", "red").unwrap();
+        common.set("fill", "inherit").unwrap();
+        assert!(common.fill.is_none());
+        assert_eq!(common.get("fill").as_deref(), Some("inherit"));
+        common.set("fill", "none").unwrap();
+        assert!(common.others.is_empty());
+        assert_eq!(common.get("fill").as_deref(), Some("none"));
+    }
+
+    #[test]
+    fn test_structure_errors() {
+        let err = parse_err("<svg><blink r=\"x\"></svg>");
+        assert!(matches!(err, crate::Error::UnterminatedTag { ref name, .. } if name == "blink"));
+        assert_eq!(err.location().unwrap().element, vec!["svg", "blink"]);
+
+        let err = parse_err(r#"<svg><path d="M0 0"></svg>"#);
+        match err {
+            crate::Error::UnterminatedTag { name, location } => {
+                assert_eq!(name, "path");
+                assert_eq!(location.element, vec!["svg", "path"]);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let err = parse_err("<svg><path/>");
+        assert!(matches!(err, crate::Error::UnterminatedTag { ref name, .. } if name == "svg"));
+
+        let err = parse_err(r#"<svg><path d="M0 0 L 1"/></svg>"#);
+        match err {
+            crate::Error::InvalidPathData { rest, location } => {
+                assert_eq!(rest, "L 1");
+                assert_eq!(location.offset, 19);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let err = parse_err(r#"<?xml version="1.0" <svg></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidHeader { .. }));
+        let err = parse_err("<svg></svg> trailing");
+        assert!(matches!(err, crate::Error::UnexpectedInput { .. }));
+
+        let err = super::Svg::from_path("/this/file/does/not/exist.svg").unwrap_err();
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_keep_everything() {
+        // a feather icon
+        let source = r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round" class="feather feather-clock"><circle cx="12" cy="12" r="10"/><line x1="12" y1="6" x2="12" y2="12"/></svg>"#;
+        let svg: super::Svg = source.parse().unwrap();
+        assert_eq!(svg.common.fill, Some(crate::paint::Paint::None));
+        assert_eq!(svg.to_string(), source);
+
+        let source = r##"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" data-icon="x"><!-- drawn by hand --><title>Close</title><defs><linearGradient id="g"><stop offset="0" stop-color="#000"/></linearGradient></defs><path id="p" fill-rule="evenodd" d="M 0,0 L 1,1" aria-hidden="true" opacity="0.5"/><rect class="a" data-x='say "hi"' width="1" height="1"/><use xlink:href="#p"/><path d="M 0,0"><title>with a tooltip</title></path></svg>"##;
+        let svg: super::Svg = source.parse().unwrap();
+        assert_eq!(svg.children.len(), 7);
+        assert!(matches!(&svg.children[0], Child::Comment(c) if c == " drawn by hand "));
+        match &svg.children[1] {
+            Child::Unknown { name, children, .. } => {
+                assert_eq!(name, "title");
+                assert!(matches!(&children[..], [Child::Text(t)] if t == "Close"));
+            }
+            c => panic!("unexpected child: {:?}", c),
+        }
+        let Child::Path(path) = &svg.children[3] else {
+            panic!("expect a path");
+        };
+        assert_eq!(
+            path.common.others,
+            vec![
+                ("id".to_string(), "p".to_string()),
+                ("fill-rule".to_string(), "evenodd".to_string()),
+                ("aria-hidden".to_string(), "true".to_string()),
+                ("opacity".to_string(), "0.5".to_string()),
+            ]
+        );
+        assert!(matches!(&svg.children[6], Child::Unknown { name, .. } if name == "path"));
+        let output = svg.to_string();
+        assert_eq!(output, source.replace("'say \"hi\"'", "\"say &quot;hi&quot;\""));
+        // written attributes parse to the same thing again
+        let again: super::Svg = output.parse().unwrap();
+        assert_eq!(again.to_string(), output);
+
+        // text keeps its whitespace, whitespace between elements only is dropped
+        let source = "<svg>\n  <text x=\"1\">Hello <tspan>big</tspan> world <!-- c --></text>\n  <title> a  b </title>\n</svg>";
+        let svg: super::Svg = source.parse().unwrap();
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg><text x="1">Hello <tspan>big</tspan> world <!-- c --></text><title> a  b </title></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_prolog() {
+        for source in [
+            r#"<!-- c --><svg viewBox="0 0 1 1" xmlns="http://www.w3.org/2000/svg"></svg>"#,
+            r#"<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE svg><svg viewBox="0 0 1 1" xmlns="http://www.w3.org/2000/svg"></svg>"#,
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><!-- Generator: Adobe Illustrator --><!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd" [<!ENTITY ns "http://www.w3.org/2000/svg">]><svg viewBox="0 0 1 1" xmlns="http://www.w3.org/2000/svg"></svg>"#,
+            r#"<?xml-stylesheet href="a.css"?><svg viewBox="0 0 1 1" xmlns="http://www.w3.org/2000/svg"></svg>"#,
+        ] {
+            let svg: super::Svg = source.parse().unwrap();
+            assert_eq!(svg.to_string(), source);
+        }
+        let svg: super::Svg = "<?xml version=\"1.0\"?>\n<!-- c -->\n<!DOCTYPE svg>\n<svg/>\n".parse().unwrap();
+        assert_eq!(svg.prolog, vec!["<!-- c -->", "<!DOCTYPE svg>"]);
+        assert_eq!(
+            svg.to_string(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><!-- c --><!DOCTYPE svg><svg></svg>"#
+        );
+
+        // the root tag is parsed like other elements
+        let svg: super::Svg = r#"<svg viewBox="0 0 1 1" />"#.parse().unwrap();
+        assert!(svg.view_box.is_some() && svg.children.is_empty());
+        let svg: super::Svg = r#"<svg data-x="a>b" width="1"><path d="M0 0"/></svg>"#.parse().unwrap();
+        assert_eq!(svg.common.other("data-x"), Some("a>b"));
+        assert_eq!(svg.children.len(), 1);
+        let err = parse_err("<svgx></svgx>");
+        assert!(matches!(err, crate::Error::UnexpectedInput { ref found, .. } if found == "x></svgx>"));
+    }
+
+    #[test]
+    fn test_view_box() {
+        let svg: super::Svg = r#"<svg viewBox="-1,-1,26.5,26" preserveAspectRatio="xMinYMin slice"></svg>"#
+            .parse()
+            .unwrap();
+        assert_eq!(
+            svg.view_box,
+            Some(crate::view_box::ViewBox::new(-1.0, -1.0, 26.5, 26.0))
+        );
+        assert_eq!(
+            svg.preserve_aspect_ratio.unwrap().align,
+            crate::view_box::Align::XMinYMin
+        );
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg viewBox="-1 -1 26.5 26" preserveAspectRatio="xMinYMin slice" xmlns="http://www.w3.org/2000/svg"></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_lengths() {
+        use crate::length::{Axis, Length, LengthContext, Unit};
+
+        let source = r#"<svg width="1em" height="24px" viewBox="0 0 24 24"><circle r="50%" stroke-width="1.5pt" stroke-dasharray="1mm 2%"/><rect x="1in" width="10%" height="2ex"/></svg>"#;
+        let svg: super::Svg = source.parse().unwrap();
+        assert_eq!(svg.width.0, Some(Length::new(1.0, Unit::Em)));
+        assert_eq!(svg.height.0, Some(Length::new(24.0, Unit::Px)));
+        let Child::Circle(circle) = &svg.children[0] else {
+            panic!("expect a circle");
+        };
+        let context = LengthContext::new(24.0, 24.0);
+        assert_eq!(circle.r.unwrap().resolve(Axis::Other, &context), 12.0);
+        assert_eq!(circle.common.stroke_width.unwrap().resolve(Axis::Other, &context), 2.0);
+        let Child::Rect(rect) = &svg.children[1] else {
+            panic!("expect a rect");
+        };
+        assert_eq!(rect.x.unwrap().resolve(Axis::X, &context), 96.0);
+        assert!((rect.width.unwrap().resolve(Axis::X, &context) - 2.4).abs() < 1e-6);
+        assert_eq!(svg.to_string(), source.replace("0 0 24 24\"", "0 0 24 24\" xmlns=\"http://www.w3.org/2000/svg\""));
+
+        let err = parse_err(r#"<svg><circle r="5 px"/></svg>"#);
+        assert!(matches!(err, crate::Error::InvalidAttribute { ref name, .. } if name == "r"));
+    }
+
+    #[test]
+    fn test_shapes_to_paths() {
+        let mut svg: crate::Svg = r#"<svg viewBox="0 0 24 24"><circle cx="12" cy="12" r="10" id="c"/><g><ellipse cx="12" cy="12" rx="50%"/><line x1="1" y1="2" x2="3" y2="4"/><polyline points="0,0 1,1"/></g><defs><circle r="1"/></defs></svg>"#
+            .parse()
+            .unwrap();
+        svg.shapes_to_paths(Curves::Arcs);
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"><path id="c" d="M 22,12 A 10 10 0 0 1 12,22 A 10 10 0 0 1 2,12 A 10 10 0 0 1 12,2 A 10 10 0 0 1 22,12 Z"/><g><path d="M 24,12 A 12 12 0 0 1 12,24 A 12 12 0 0 1 0,12 A 12 12 0 0 1 12,0 A 12 12 0 0 1 24,12 Z"/><path d="M 1,2 L 3,4"/><path d="M 0,0 L 1,1"/></g><defs><circle r="1"/></defs></svg>"#
+        );
+
+        let mut svg: crate::Svg = r#"<svg viewBox="0 0 2 2"><circle cx="1" cy="1" r="1"/></svg>"#
+            .parse()
+            .unwrap();
+        svg.shapes_to_paths(Curves::Cubics);
+        let Child::Path(path) = &svg.children[0] else {
+            panic!("expect a path");
+        };
+        let d = path.d.as_ref().unwrap();
+        assert_eq!(d.len(), 6);
+        assert!(d.to_string().starts_with("M 2,1 C 2,1.5522847 1.5522847,2 1,2 C"));
+    }
+
+    #[test]
+    fn test_never_panic() {
+        assert!(ICON.parse::<super::Svg>().is_ok());
+        // every prefix of a valid document
+        for (i, _) in ICON.char_indices() {
+            let _ = ICON[..i].parse::<super::Svg>();
+        }
+        // every single character replaced by something which breaks the syntax
+        for (i, c) in ICON.char_indices() {
+            for replace in ['<', '>', '"', '=', '/', '?', '-', '.', 'e', ' ', '\n', '中'] {
+                let mut input = String::with_capacity(ICON.len() + 4);
+                input.push_str(&ICON[..i]);
+                input.push(replace);
+                input.push_str(&ICON[i + c.len_utf8()..]);
+                if let Err(e) = input.parse::<super::Svg>() {
+                    // locations always point into the input
+                    if let Some(location) = e.location() {
+                        assert!(location.offset <= input.len());
+                    }
+                }
+            }
+        }
+    }
 }
@@ -11,9 +11,12 @@ macro_rules! impl_auto_value {
     };
 }
 
+/// Parse a `Svg` from a string literal.
+///
+/// Panics if the svg is invalid, use `str::parse` to handle the [`Error`](crate::Error).
 #[macro_export]
 macro_rules! svg {
     ($s: expr) => {
-        format!("{}", $s).as_str().parse::<crate::Svg>().unwrap()
+        format!("{}", $s).as_str().parse::<$crate::Svg>().unwrap()
     };
 }
\ No newline at end of file
@@ -0,0 +1,276 @@
+//! Make icons smaller without changing how they look, in passes like svgo.
+//!
+//! Passes which depend on the inherited attributes (`RemoveDefaults`, `CollapseGroups` and
+//! `MergePaths`) do nothing if the document has `<style>` rules or `style` attributes,
+//! run [`Svg::apply_styles`] with `inline` before to optimize those documents too.
+pub(crate) mod attributes;
+mod groups;
+mod paths;
+
+use std::fmt::Display;
+
+use crate::children::{Child, CommonKVs};
+use crate::Svg;
+
+/// A transformation of the document which keeps its rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pass {
+    /// remove presentation attributes which have their initial or inherited value,
+    /// like `stroke-linecap="butt"` or `opacity="1"`
+    RemoveDefaults,
+    /// remove groups without children and shapes which draw nothing, unless they have an `id`
+    RemoveEmpty,
+    /// join consecutive paths with the same attributes which do not overlap
+    MergePaths,
+    /// move the attributes of a group into its only child, and replace groups
+    /// without attributes by their children
+    CollapseGroups,
+    /// round the coordinates of shapes and paths to a number of decimals
+    RoundCoordinates(u8),
+    /// replace shapes by a `<path>` when it is written shorter
+    ShapesToPaths,
+}
+
+impl Pass {
+    /// all passes in a good order, coordinates keep 3 decimals
+    pub const DEFAULT: [Pass; 6] = [
+        Pass::RoundCoordinates(3),
+        Pass::RemoveDefaults,
+        Pass::RemoveEmpty,
+        Pass::ShapesToPaths,
+        Pass::CollapseGroups,
+        Pass::MergePaths,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pass::RemoveDefaults => "remove-defaults",
+            Pass::RemoveEmpty => "remove-empty",
+            Pass::MergePaths => "merge-paths",
+            Pass::CollapseGroups => "collapse-groups",
+            Pass::RoundCoordinates(_) => "round-coordinates",
+            Pass::ShapesToPaths => "shapes-to-paths",
+        }
+    }
+    /// run the pass on `svg`
+    pub fn run(&self, svg: &mut Svg) {
+        let styled = has_styles(svg);
+        match self {
+            Pass::RemoveDefaults if !styled => attributes::remove_defaults(svg),
+            Pass::RemoveEmpty => paths::remove_empty(svg),
+            Pass::MergePaths if !styled => paths::merge_paths(svg),
+            Pass::CollapseGroups if !styled => groups::collapse_groups(&mut svg.children),
+            Pass::RoundCoordinates(decimals) => attributes::round_coordinates(svg, *decimals),
+            Pass::ShapesToPaths => paths::shapes_to_paths(svg),
+            _ => {}
+        }
+    }
+}
+
+impl Display for Pass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pass::RoundCoordinates(decimals) => write!(f, "{}({})", self.name(), decimals),
+            pass => f.write_str(pass.name()),
+        }
+    }
+}
+
+/// The size of the document before and after a pass, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassReport {
+    pub pass: Pass,
+    pub before: usize,
+    pub after: usize,
+}
+
+impl PassReport {
+    /// the bytes the pass removed, negative if it made the document larger
+    pub fn saved(&self) -> isize {
+        self.before as isize - self.after as isize
+    }
+}
+
+/// What [`Svg::optimize`] did, pass by pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub passes: Vec<PassReport>,
+}
+
+impl Report {
+    /// the bytes removed by all passes
+    pub fn saved(&self) -> isize {
+        self.passes.iter().map(PassReport::saved).sum()
+    }
+}
+
+impl Display for Report {
+    /// one line per pass: `name: before -> after bytes (saved)`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for pass in &self.passes {
+            writeln!(
+                f,
+                "{}: {} -> {} bytes ({})",
+                pass.pass,
+                pass.before,
+                pass.after,
+                -pass.saved()
+            )?;
+        }
+        write!(f, "total: {} bytes saved", self.saved())
+    }
+}
+
+/// whether css may set the attributes of some elements
+pub(crate) fn has_styles(svg: &Svg) -> bool {
+    fn styled(children: &[Child]) -> bool {
+        children.iter().any(|child| match child {
+            Child::Unknown { attrs, children, .. } => {
+                attrs.iter().any(|(k, _)| k == "style") || styled(children)
+            }
+            Child::Group(group) => group.common.other("style").is_some() || styled(&group.children),
+            child => child.common().and_then(|c| c.other("style")).is_some(),
+        })
+    }
+    !svg.style_sheet().rules.is_empty() || svg.common.other("style").is_some() || styled(&svg.children)
+}
+
+/// whether the element may be referenced, by `<use>` or a `url(#id)`
+pub(crate) fn referenced(common: &CommonKVs) -> bool {
+    common.other("id").is_some()
+}
+
+impl Svg {
+    /// run the passes in order, see [`Pass::DEFAULT`], and report the bytes each one saved
+    ///
+    /// ## Example
+    /// ```rust
+    /// use svg_icon::optimize::Pass;
+    /// use svg_icon::Svg;
+    ///
+    /// let mut svg: Svg = r#"<svg viewBox="0 0 24 24"><g><rect x="2" y="2" width="20" height="0"/><path d="M1.00001 2 L3 4" stroke-linecap="butt"/></g></svg>"#
+    ///     .parse()
+    ///     .unwrap();
+    /// let report = svg.optimize(&Pass::DEFAULT);
+    /// assert_eq!(
+    ///     svg.to_string(),
+    ///     r#"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"><path d="M 1,2 L 3,4"/></svg>"#
+    /// );
+    /// assert!(report.saved() > 50);
+    /// ```
+    pub fn optimize(&mut self, passes: &[Pass]) -> Report {
+        let mut size = self.to_string().len();
+        let passes = passes
+            .iter()
+            .map(|pass| {
+                pass.run(self);
+                let after = self.to_string().len();
+                let report = PassReport {
+                    pass: *pass,
+                    before: size,
+                    after,
+                };
+                size = after;
+                report
+            })
+            .collect();
+        Report { passes }
+    }
+}
+
+#[cfg(test)]
+mod test_optimize {
+    use super::*;
+
+    fn optimize(source: &str, passes: &[Pass]) -> String {
+        let mut svg: Svg = source.parse().unwrap();
+        svg.optimize(passes);
+        svg.to_string()
+    }
+
+    #[test]
+    fn test_remove_defaults() {
+        let source = r##"<svg opacity="1" fill="#000"><g stroke-linecap="round" fill-opacity="1.0"><path d="M0 0 H1" stroke-linecap="round"/><path d="M0 0 H1" stroke-linecap="butt" stroke-miterlimit="4"/></g><path d="M0 0 H1" stroke-linejoin="miter" fill-rule="nonzero" stroke="none"/></svg>"##;
+        assert_eq!(
+            optimize(source, &[Pass::RemoveDefaults]),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><g stroke-linecap="round"><path d="M 0,0 H 1"/><path d="M 0,0 H 1" stroke-linecap="butt"/></g><path d="M 0,0 H 1"/></svg>"#
+        );
+        // css may set other values
+        let styled = r#"<svg><path d="M0 0 H1" stroke-linecap="butt" style="stroke-linecap:round"/></svg>"#;
+        assert!(optimize(styled, &[Pass::RemoveDefaults]).contains("butt"));
+        // a referenced element inherits from where it is used
+        let used = r##"<svg><g fill="red"><path id="p" d="M0 0H1" fill="red"/><g id="q"><path d="M0 0H1" fill="red"/></g></g><use href="#p" fill="blue"/></svg>"##;
+        assert_eq!(
+            optimize(used, &Pass::DEFAULT),
+            r##"<svg><g fill="#ff0000"><path id="p" d="M 0,0 H 1" fill="#ff0000"/><g id="q"><path d="M 0,0 H 1" fill="#ff0000"/></g></g><use href="#p" fill="blue"/></svg>"##
+        );
+    }
+
+    #[test]
+    fn test_remove_empty() {
+        let source = r#"<svg><g><g></g><rect width="0" height="4"/><circle r="-1"/><path d="M1 1"/><polyline points="1 1"/></g><g id="a"/><path d="M1 1 z"/><line x2="0"/><defs></defs></svg>"#;
+        assert_eq!(
+            optimize(source, &[Pass::RemoveEmpty]),
+            r#"<svg><g id="a"></g><path d="M 1,1 z"/><line x2="0"/><defs/></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_collapse_groups() {
+        let source = r#"<svg><g><g fill="red" transform="translate(1)"><path d="M0 0 H1" fill="blue" transform="scale(2)"/></g><rect width="1" height="1"/></g><g class="a"><path d="M0 0 H1"/></g><g opacity="0.5"><path d="M0 0 H1" opacity="0.5"/></g></svg>"#;
+        assert_eq!(
+            optimize(source, &[Pass::CollapseGroups]),
+            r##"<svg><path d="M 0,0 H 1" fill="#0000ff" transform="translate(1) scale(2)"/><rect width="1" height="1"/><g class="a"><path d="M 0,0 H 1"/></g><g opacity="0.5"><path d="M 0,0 H 1" opacity="0.5"/></g></svg>"##
+        );
+    }
+
+    #[test]
+    fn test_merge_paths() {
+        let source = r#"<svg stroke="red" stroke-width="2"><path d="M0 0 H4" fill="none"/><path d="m0 8 h4" fill="none"/><path d="M0 9 H4" fill="none"/><path d="M10 10 H4"/></svg>"#;
+        // the second and the third one overlap with their strokes, the last one has other attributes
+        assert_eq!(
+            optimize(source, &[Pass::MergePaths]),
+            r##"<svg stroke="#ff0000" stroke-width="2"><path d="M 0,0 H 4 M 0,8 h 4" fill="none"/><path d="M 0,9 H 4" fill="none"/><path d="M 10,10 H 4"/></svg>"##
+        );
+        // every path draws its own inherited markers
+        let markers = r##"<svg><g marker-end="url(#m)"><path d="M0 0H1"/><path d="M5 5H6"/></g><g marker="url(#m)"><g marker-start="none" marker-mid="none" marker-end="none"><path d="M0 0H1"/><path d="M5 5H6"/></g></g></svg>"##;
+        assert_eq!(
+            optimize(markers, &[Pass::MergePaths]),
+            r##"<svg><g marker-end="url(#m)"><path d="M 0,0 H 1"/><path d="M 5,5 H 6"/></g><g marker="url(#m)"><g marker-start="none" marker-mid="none" marker-end="none"><path d="M 0,0 H 1 M 5,5 H 6"/></g></g></svg>"##
+        );
+    }
+
+    #[test]
+    fn test_round_coordinates() {
+        let source = r#"<svg><path d="M0.12345 0.5 l0.33333 0.33333 l0.33333 0.33333 H-0.00001"/><rect x="1.23456" width="1.5%" height="2"/><polygon points="0.11111 2 3 4"/></svg>"#;
+        assert_eq!(
+            optimize(source, &[Pass::RoundCoordinates(2)]),
+            r#"<svg><path d="M 0.12,0.5 l 0.34,0.33 l 0.33,0.34 H 0"/><rect x="1.23" width="1.5%" height="2"/><polygon points="0.11,2 3,4"/></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_shapes_to_paths() {
+        let source = r#"<svg><rect x="10" y="10" width="20" height="20"/><circle cx="5" cy="5" r="5"/><line x1="1" y1="1" x2="2" y2="1" stroke="red"/><rect width="50%" height="10"/></svg>"#;
+        let result = optimize(source, &[Pass::ShapesToPaths]);
+        assert_eq!(
+            result,
+            r##"<svg><path d="M 10,10 H 30 V 30 H 10 V 10 Z"/><circle cx="5" cy="5" r="5"/><path stroke="#ff0000" d="M 1,1 L 2,1"/><rect width="50%" height="10"/></svg>"##
+        );
+        // a rect draws no inherited markers, a path would
+        let markers = r#"<svg marker-start="url(#m)"><rect x="10" y="10" width="20" height="20"/></svg>"#;
+        assert!(optimize(markers, &[Pass::ShapesToPaths]).contains("<rect"));
+    }
+
+    #[test]
+    fn test_report() {
+        let mut svg: Svg = r#"<svg><g><path d="M0 0 H1.00001" stroke-opacity="1"/></g></svg>"#.parse().unwrap();
+        let report = svg.optimize(&Pass::DEFAULT);
+        assert_eq!(report.passes.len(), Pass::DEFAULT.len());
+        assert_eq!(report.passes[0].pass, Pass::RoundCoordinates(3));
+        assert!(report.passes.iter().all(|p| p.saved() >= 0));
+        assert_eq!(report.passes[5].after, svg.to_string().len());
+        assert_eq!(report.saved(), (report.passes[0].before - report.passes[5].after) as isize);
+        assert!(report.to_string().starts_with("round-coordinates(3): "));
+    }
+}
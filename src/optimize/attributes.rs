@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use crate::children::{round, Child, CommonKVs, Cursor, PathData};
+use crate::length::Length;
+use crate::Svg;
+
+use super::referenced;
+
+/// the inherited properties with their initial values
+const INHERITED: [(&str, &str); 12] = [
+    ("clip-rule", "nonzero"),
+    ("fill", "#000"),
+    ("fill-opacity", "1"),
+    ("fill-rule", "nonzero"),
+    ("stroke", "none"),
+    ("stroke-dashoffset", "0"),
+    ("stroke-linecap", "butt"),
+    ("stroke-linejoin", "miter"),
+    ("stroke-miterlimit", "4"),
+    ("stroke-opacity", "1"),
+    ("stroke-width", "1"),
+    ("visibility", "visible"),
+];
+
+/// properties which are not inherited, with their initial values
+const NOT_INHERITED: [(&str, &str); 1] = [("opacity", "1")];
+
+/// a value in the form it is written back, numbers of untyped attributes are normalized
+fn canonical(name: &str, value: &str) -> String {
+    let mut common = CommonKVs::default();
+    let written = match common.set(name, value) {
+        Ok(()) => common.get(name).unwrap_or_default(),
+        Err(_) => value.trim().to_string(),
+    };
+    written.parse::<f32>().map_or(written, |number| number.to_string())
+}
+
+/// the written values of the inherited properties
+pub(crate) type Computed = HashMap<&'static str, String>;
+
+/// the values of the inherited properties of the root element
+pub(crate) fn initial() -> Computed {
+    INHERITED
+        .iter()
+        .map(|(name, value)| (*name, canonical(name, value)))
+        .collect()
+}
+
+/// the properties of `common` which are set to their inherited or initial value,
+/// and the values for the children
+pub(crate) fn redundant(common: &CommonKVs, parent: &Computed) -> (Vec<&'static str>, Computed) {
+    let mut names = vec![];
+    let mut computed = parent.clone();
+    for (name, _) in INHERITED {
+        let Some(value) = common.get(name) else {
+            continue;
+        };
+        let value = canonical(name, &value);
+        if parent.get(name) == Some(&value) {
+            names.push(name);
+        } else {
+            computed.insert(name, value);
+        }
+    }
+    for (name, initial) in NOT_INHERITED {
+        if common.get(name).is_some_and(|value| canonical(name, &value) == canonical(name, initial)) {
+            names.push(name);
+        }
+    }
+    (names, computed)
+}
+
+/// remove the inherited properties which are set to the value of the parent,
+/// and return the values for the children
+fn remove_inherited(common: &mut CommonKVs, parent: &Computed) -> Computed {
+    let (names, computed) = redundant(common, parent);
+    for name in names {
+        common.remove(name);
+    }
+    computed
+}
+
+fn remove_in_children(children: &mut [Child], parent: &Computed) {
+    for child in children {
+        // a referenced element and its children inherit from where it is used, e.g. `<use>`
+        if child.common().is_some_and(referenced) {
+            continue;
+        }
+        match child {
+            Child::Group(group) => {
+                let computed = remove_inherited(&mut group.common, parent);
+                remove_in_children(&mut group.children, &computed);
+            }
+            // what elements inside unknown ones inherit is not known, e.g. in `<symbol>`
+            Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => {}
+            child => {
+                if let Some(common) = child.common_mut() {
+                    remove_inherited(common, parent);
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn remove_defaults(svg: &mut Svg) {
+    let computed = remove_inherited(&mut svg.common, &initial());
+    remove_in_children(&mut svg.children, &computed);
+}
+
+impl PathData {
+    /// every number rounded to `decimals`.
+    ///
+    /// relative commands are rounded from the rounded absolute points, so errors do not add up
+    ///
+    /// ## Example
+    /// ```rust
+    /// use svg_icon::children::PathData;
+    ///
+    /// let (_, d) = PathData::from_str("M0.4 0 l0.4 0 l0.4 0").unwrap();
+    /// assert_eq!(d.round(0).to_string(), "M 0,0 l 1,0 l 0,0");
+    /// ```
+    pub fn round(&self, decimals: u8) -> PathData {
+        let (mut source, mut target) = (Cursor::default(), Cursor::default());
+        self.iter()
+            .map(|command| {
+                let absolute = source.absolute(command).map_numbers(|v| round(v, Some(decimals)));
+                match command.is_relative() {
+                    true => target.relative(&absolute).map_numbers(|v| round(v, Some(decimals))),
+                    false => {
+                        target.absolute(&absolute);
+                        absolute
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+fn round_length(length: &mut Option<Length>, decimals: u8) {
+    if let Some(length) = length {
+        length.value = round(length.value, Some(decimals));
+    }
+}
+
+/// the geometry lengths of a shape
+pub(super) fn lengths(child: &mut Child) -> Vec<&mut Option<Length>> {
+    match child {
+        Child::Circle(circle) => vec![&mut circle.cx, &mut circle.cy, &mut circle.r],
+        Child::Ellipse(ellipse) => vec![&mut ellipse.cx, &mut ellipse.cy, &mut ellipse.rx.0, &mut ellipse.ry.0],
+        Child::Line(line) => vec![&mut line.x1, &mut line.y1, &mut line.x2, &mut line.y2],
+        Child::Rect(rect) => vec![
+            &mut rect.x,
+            &mut rect.y,
+            &mut rect.width,
+            &mut rect.height,
+            &mut rect.rx,
+            &mut rect.ry,
+        ],
+        _ => vec![],
+    }
+}
+
+fn round_children(children: &mut [Child], decimals: u8) {
+    for child in children {
+        if let Some(common) = child.common_mut() {
+            round_length(&mut common.x, decimals);
+            round_length(&mut common.y, decimals);
+        }
+        for length in lengths(child) {
+            round_length(length, decimals);
+        }
+        match child {
+            Child::Group(group) => round_children(&mut group.children, decimals),
+            Child::Path(path) => path.d = path.d.as_ref().map(|d| d.round(decimals)),
+            Child::Polygon(polygon) => {
+                for (x, y) in polygon.points.iter_mut().flat_map(|points| points.0.iter_mut()) {
+                    (*x, *y) = (round(*x, Some(decimals)), round(*y, Some(decimals)));
+                }
+            }
+            Child::Polyline(polyline) => {
+                for (x, y) in polyline.points.iter_mut().flat_map(|points| points.0.iter_mut()) {
+                    (*x, *y) = (round(*x, Some(decimals)), round(*y, Some(decimals)));
+                }
+            }
+            // their coordinates may be in another system, e.g. `objectBoundingBox`
+            _ => {}
+        }
+    }
+}
+
+pub(super) fn round_coordinates(svg: &mut Svg, decimals: u8) {
+    round_children(&mut svg.children, decimals);
+}
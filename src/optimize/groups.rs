@@ -0,0 +1,106 @@
+use crate::children::{Child, Group};
+use crate::transform::Transform;
+
+/// the inherited properties, which a group can give to its only child
+const INHERITED: [&str; 30] = [
+    "clip-rule",
+    "color",
+    "color-interpolation",
+    "color-rendering",
+    "cursor",
+    "direction",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "font",
+    "font-family",
+    "font-size",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "letter-spacing",
+    "paint-order",
+    "shape-rendering",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "text-anchor",
+    "text-rendering",
+    "visibility",
+    "word-spacing",
+];
+
+/// the names of all attributes of the group
+fn names(group: &Group) -> Vec<String> {
+    let common = &group.common;
+    common
+        .attributes()
+        .into_iter()
+        .map(|(name, _)| name.to_string())
+        .chain(common.others.iter().map(|(name, _)| name.clone()))
+        .collect()
+}
+
+/// move the attributes of `group` into its only child, `false` if that would change the rendering
+fn push_down(group: &mut Group) -> bool {
+    let names = names(group);
+    let [child] = group.children.as_mut_slice() else {
+        return false;
+    };
+    let Some(common) = child.common_mut() else {
+        return false;
+    };
+    let movable = names.iter().all(|name| {
+        INHERITED.contains(&name.as_str())
+            || name == "transform"
+            // the opacity of a group with one child is the opacity of the child
+            || (name == "opacity" && common.other("opacity").is_none())
+    });
+    if !movable {
+        return false;
+    }
+    for name in names {
+        match name.as_str() {
+            "transform" => {
+                let mut functions = group.common.transform.take().unwrap_or_default().0;
+                functions.extend(common.transform.take().unwrap_or_default().0);
+                common.transform = Some(Transform(functions));
+            }
+            // the value of the child wins
+            name if common.get(name).is_some() => {}
+            name => {
+                if let Some(value) = group.common.get(name) {
+                    // the value was valid on the group
+                    let _ = common.set(name, &value);
+                }
+            }
+        }
+    }
+    true
+}
+
+/// replace groups without attributes by their children and give the attributes of groups with
+/// one child to the child, from the innermost groups
+pub(super) fn collapse_groups(children: &mut Vec<Child>) {
+    let mut collapsed = Vec::with_capacity(children.len());
+    for mut child in children.drain(..) {
+        if let Child::Group(group) = &mut child {
+            collapse_groups(&mut group.children);
+            if names(group).is_empty() {
+                collapsed.append(&mut group.children);
+                continue;
+            }
+            if push_down(group) {
+                collapsed.append(&mut group.children);
+                continue;
+            }
+        }
+        collapsed.push(child);
+    }
+    *children = collapsed;
+}
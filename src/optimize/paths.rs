@@ -0,0 +1,158 @@
+use crate::bbox::{BBox, Inherited};
+use crate::children::{Child, Command, CommonKVs, Curves, Cursor, Path, PathData, Segment};
+use crate::length::{LengthContext, Unit};
+use crate::paint::Paint;
+use crate::Svg;
+
+use super::{attributes::lengths, referenced};
+
+/// attributes whose effect depends on the bounding box of the element
+const BOUNDING_BOX: [&str; 3] = ["clip-path", "mask", "filter"];
+
+fn has_any(common: &CommonKVs, names: &[&str]) -> bool {
+    names.iter().any(|name| common.other(name).is_some())
+}
+
+/// whether the element draws nothing: no path data, or only movetos
+fn is_empty(child: &Child, context: &LengthContext) -> bool {
+    match child.to_path(context, Curves::Arcs).and_then(|path| path.d) {
+        Some(d) => d.segments().all(|segment| matches!(segment, Segment::Move { .. })),
+        None => true,
+    }
+}
+
+fn remove_empty_children(children: &mut Vec<Child>, context: &LengthContext) {
+    children.retain_mut(|child| match child {
+        Child::Group(group) => {
+            remove_empty_children(&mut group.children, context);
+            !group.children.is_empty() || referenced(&group.common)
+        }
+        Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => true,
+        child => child.common().is_some_and(referenced) || !is_empty(child, context),
+    });
+}
+
+pub(super) fn remove_empty(svg: &mut Svg) {
+    let context = svg.length_context();
+    remove_empty_children(&mut svg.children, &context);
+}
+
+/// all attributes of an element, sorted to compare them
+fn sorted_attributes(common: &CommonKVs) -> Vec<(String, String)> {
+    let mut attributes: Vec<(String, String)> = common
+        .attributes()
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .chain(common.others.iter().cloned())
+        .collect();
+    attributes.sort();
+    attributes
+}
+
+/// whether a path can be joined with other ones, markers are drawn at the ends of every path
+fn mergeable(path: &Path, parent: &Inherited, context: &LengthContext) -> bool {
+    let common = &path.common;
+    let url = |paint: &Option<Paint>| matches!(paint, Some(Paint::Url { .. }));
+    path.d.as_ref().is_some_and(|d| matches!(d.iter().next(), Some(Command::MoveTo(_))))
+        && !referenced(common)
+        && !parent.of(common, context).markers.contains(&true)
+        && !has_any(common, &BOUNDING_BOX)
+        && !url(&common.fill)
+        && !url(&common.stroke)
+}
+
+/// the box around what a path draws, in its user space
+fn drawn_bbox(path: &Path, parent: &Inherited, context: &LengthContext) -> Option<BBox> {
+    let d = path.d.as_ref()?;
+    let inherited = parent.of(&path.common, context);
+    match inherited.stroked() {
+        true => d.stroke_bbox(&inherited.stroke),
+        false => d.bbox(),
+    }
+}
+
+fn overlaps(a: &BBox, b: &BBox) -> bool {
+    a.min_x < b.max_x && b.min_x < a.max_x && a.min_y < b.max_y && b.min_y < a.max_y
+}
+
+fn merge_children(children: &mut Vec<Child>, parent: &Inherited, context: &LengthContext) {
+    let mut merged: Vec<Child> = Vec::with_capacity(children.len());
+    // the boxes of the parts of the last merged path
+    let mut parts: Vec<BBox> = vec![];
+    for mut child in children.drain(..) {
+        if let Child::Group(group) = &mut child {
+            let inherited = parent.of(&group.common, context);
+            merge_children(&mut group.children, &inherited, context);
+        }
+        let Child::Path(path) = &child else {
+            parts.clear();
+            merged.push(child);
+            continue;
+        };
+        let bbox = drawn_bbox(path, parent, context);
+        if let Some(Child::Path(last)) = merged.last_mut() {
+            let joinable = mergeable(last, parent, context)
+                && mergeable(path, parent, context)
+                && sorted_attributes(&last.common) == sorted_attributes(&path.common)
+                && bbox.is_some_and(|bbox| !parts.iter().any(|part| overlaps(part, &bbox)));
+            if let (true, Some(d), Some(bbox)) = (joinable, &path.d, bbox) {
+                // a relative moveto at the start of a path is absolute
+                let mut commands = d.iter();
+                let first = commands.next().map(|first| Cursor::default().absolute(first));
+                let last_d = last.d.get_or_insert_with(PathData::new);
+                last_d.extend(first);
+                last_d.extend(commands.cloned());
+                parts.push(bbox);
+                continue;
+            }
+        }
+        parts = bbox.into_iter().collect();
+        merged.push(child);
+    }
+    *children = merged;
+}
+
+pub(super) fn merge_paths(svg: &mut Svg) {
+    let context = svg.length_context();
+    let root = Inherited::default().of(&svg.common, &context);
+    merge_children(&mut svg.children, &root, &context);
+}
+
+fn shapes_to_paths_in(children: &mut [Child], parent: &Inherited, context: &LengthContext) {
+    for child in children {
+        match child {
+            Child::Group(group) => {
+                let inherited = parent.of(&group.common, context);
+                shapes_to_paths_in(&mut group.children, &inherited, context);
+            }
+            Child::Path(_) | Child::Unknown { .. } | Child::Text(_) | Child::Comment(_) => {}
+            shape => {
+                // percentages and font relative lengths depend on where the shape is drawn
+                let absolute = lengths(shape).iter().all(|length| {
+                    length.is_none_or(|length| !matches!(length.unit, Unit::Percent | Unit::Em | Unit::Ex))
+                });
+                // a path has markers where a rect or a circle has none, also inherited ones
+                let markers = shape
+                    .common()
+                    .is_some_and(|common| parent.of(common, context).markers.contains(&true));
+                if !absolute || markers {
+                    continue;
+                }
+                let Some(mut path) = shape.to_path(context, Curves::Arcs) else {
+                    continue;
+                };
+                path.d = path.d.map(|d| d.to_shortest());
+                let path = Child::Path(path);
+                if path.to_string().len() < shape.to_string().len() {
+                    *shape = path;
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn shapes_to_paths(svg: &mut Svg) {
+    let context = svg.length_context();
+    let root = Inherited::default().of(&svg.common, &context);
+    shapes_to_paths_in(&mut svg.children, &root, &context);
+}
@@ -0,0 +1,538 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::error::Error;
+
+/// A sRGB color with alpha, 8 bits per channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+    /// `[r, g, b, a]`
+    pub fn to_rgba8(self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+    /// `[r, g, b, a]` from 0 to 1
+    pub fn to_rgba_f32(self) -> [f32; 4] {
+        self.to_rgba8().map(|c| c as f32 / 255_f32)
+    }
+    /// build a color from channels between 0 and 1, values out of range are clamped
+    pub fn from_rgba_f32(r: f32, g: f32, b: f32, a: f32) -> Self {
+        let channel = |c: f32| (c.clamp(0_f32, 1_f32) * 255_f32).round() as u8;
+        Color::rgba(channel(r), channel(g), channel(b), channel(a))
+    }
+    /// the color of a CSS color keyword like `red` or `cornflowerblue`, case insensitive
+    pub fn named(name: &str) -> Option<Color> {
+        let name = name.to_ascii_lowercase();
+        NAMED_COLORS
+            .binary_search_by_key(&name.as_str(), |(n, _)| n)
+            .ok()
+            .map(|index| {
+                let [r, g, b, a] = NAMED_COLORS[index].1;
+                Color::rgba(r, g, b, a)
+            })
+    }
+    fn hex(s: &str) -> Option<Color> {
+        if !s.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digit = |i: usize| u8::from_str_radix(&s[i..i + 1], 16).ok().map(|d| d * 17);
+        let pair = |i: usize| u8::from_str_radix(&s[i..i + 2], 16).ok();
+        match s.len() {
+            3 => Some(Color::rgb(digit(0)?, digit(1)?, digit(2)?)),
+            4 => Some(Color::rgba(digit(0)?, digit(1)?, digit(2)?, digit(3)?)),
+            6 => Some(Color::rgb(pair(0)?, pair(2)?, pair(4)?)),
+            8 => Some(Color::rgba(pair(0)?, pair(2)?, pair(4)?, pair(6)?)),
+            _ => None,
+        }
+    }
+    /// `rgb()`, `rgba()`, `hsl()` and `hsla()`, both the comma and the space separated syntax
+    fn function(s: &str) -> Option<Color> {
+        let (name, args) = s.strip_suffix(')')?.split_once('(')?;
+        let name = name.trim().to_ascii_lowercase();
+        let (args, alpha) = match args.split_once('/') {
+            // `rgb(255 0 0 / 50%)`
+            Some((args, alpha)) => (args, Some(alpha.trim())),
+            None => (args, None),
+        };
+        let mut args = if args.contains(',') {
+            args.split(',').map(str::trim).collect::<Vec<_>>()
+        } else {
+            args.split_whitespace().collect::<Vec<_>>()
+        };
+        if let Some(alpha) = alpha {
+            if args.len() != 3 {
+                return None;
+            }
+            args.push(alpha);
+        }
+        let alpha = match args.get(3) {
+            Some(a) => unit(a, 1_f32)?,
+            None => 1_f32,
+        };
+        if !(3..=4).contains(&args.len()) {
+            return None;
+        }
+        match name.as_str() {
+            "rgb" | "rgba" => Some(Color::from_rgba_f32(
+                unit(args[0], 255_f32)?,
+                unit(args[1], 255_f32)?,
+                unit(args[2], 255_f32)?,
+                alpha,
+            )),
+            "hsl" | "hsla" => {
+                let hue = args[0].strip_suffix("deg").unwrap_or(args[0]);
+                let hue = hue.parse::<f32>().ok()?.rem_euclid(360_f32) / 360_f32;
+                let saturation = percentage(args[1])?;
+                let lightness = percentage(args[2])?;
+                let [r, g, b] = hsl_to_rgb(hue, saturation, lightness);
+                Some(Color::from_rgba_f32(r, g, b, alpha))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// a number from 0 to `max` or a percentage, as a value from 0 to 1
+fn unit(s: &str, max: f32) -> Option<f32> {
+    match s.strip_suffix('%') {
+        Some(_) => percentage(s),
+        None => s.parse::<f32>().ok().map(|v| v / max),
+    }
+}
+
+/// `50%` as `0.5`
+fn percentage(s: &str) -> Option<f32> {
+    s.strip_suffix('%')?
+        .parse::<f32>()
+        .ok()
+        .map(|v| v / 100_f32)
+}
+
+/// all values are from 0 to 1
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> [f32; 3] {
+    let saturation = saturation.clamp(0_f32, 1_f32);
+    let lightness = lightness.clamp(0_f32, 1_f32);
+    let q = if lightness < 0.5 {
+        lightness * (1_f32 + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2_f32 * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1_f32);
+        if t < 1_f32 / 6_f32 {
+            p + (q - p) * 6_f32 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2_f32 / 3_f32 {
+            p + (q - p) * (2_f32 / 3_f32 - t) * 6_f32
+        } else {
+            p
+        }
+    };
+    [
+        channel(hue + 1_f32 / 3_f32),
+        channel(hue),
+        channel(hue - 1_f32 / 3_f32),
+    ]
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let color = match value.strip_prefix('#') {
+            Some(hex) => Color::hex(hex),
+            None if value.contains('(') => Color::function(value),
+            None => Color::named(value),
+        };
+        color.ok_or_else(|| Error::invalid_value(s, "a color"))
+    }
+}
+
+impl Display for Color {
+    /// `#rrggbb`, or `#rrggbbaa` when the color is not opaque
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+        if self.a != 255 {
+            write!(f, "{:02x}", self.a)?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of `fill` and `stroke`.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::paint::{Color, Paint};
+///
+/// let paint: Paint = "rgb(255 0 0 / 50%)".parse().unwrap();
+/// assert_eq!(paint, Paint::Color(Color::rgba(255, 0, 0, 128)));
+/// assert_eq!(paint.to_string(), "#ff000080");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Paint {
+    None,
+    /// the value of the `color` property, which is set by the user of the icon
+    CurrentColor,
+    Color(Color),
+    /// `url(#id)` of a gradient or pattern, the fallback is used if it can not be found
+    Url {
+        id: String,
+        fallback: Option<Box<Paint>>,
+    },
+}
+
+impl Paint {
+    /// the color to draw with, `current_color` is used for `currentColor`,
+    /// a reference can not be resolved here, its fallback is used
+    pub fn color(&self, current_color: Color) -> Option<Color> {
+        match self {
+            Paint::None => None,
+            Paint::CurrentColor => Some(current_color),
+            Paint::Color(color) => Some(*color),
+            Paint::Url { fallback, .. } => fallback.as_ref()?.color(current_color),
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Paint::Color(color)
+    }
+}
+
+impl FromStr for Paint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        if value.starts_with("url(") {
+            let (url, fallback) = value
+                .split_once(')')
+                .ok_or_else(|| Error::invalid_value(s, "`url(#id)`"))?;
+            let id = url["url(".len()..].trim().trim_matches(|c| c == '"' || c == '\'');
+            let id = id
+                .strip_prefix('#')
+                .ok_or_else(|| Error::invalid_value(s, "`url(#id)`"))?;
+            let fallback = match fallback.trim() {
+                "" => None,
+                fallback => match fallback.parse()? {
+                    Paint::Url { .. } => {
+                        return Err(Error::invalid_value(s, "a color as fallback"))
+                    }
+                    paint => Some(Box::new(paint)),
+                },
+            };
+            return Ok(Paint::Url {
+                id: id.to_string(),
+                fallback,
+            });
+        }
+        match value.to_ascii_lowercase().as_str() {
+            "none" => Ok(Paint::None),
+            "currentcolor" => Ok(Paint::CurrentColor),
+            _ => value
+                .parse()
+                .map(Paint::Color)
+                .map_err(|_| Error::invalid_value(s, "`none`, `currentColor`, a color or `url(#id)`")),
+        }
+    }
+}
+
+impl Display for Paint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Paint::None => write!(f, "none"),
+            Paint::CurrentColor => write!(f, "currentColor"),
+            Paint::Color(color) => write!(f, "{}", color),
+            Paint::Url { id, fallback } => {
+                write!(f, "url(#{})", id)?;
+                if let Some(fallback) = fallback {
+                    write!(f, " {}", fallback)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The `fill-rule` property: which areas of a path are inside.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+impl FromStr for FillRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "nonzero" => Ok(FillRule::NonZero),
+            "evenodd" => Ok(FillRule::EvenOdd),
+            _ => Err(Error::invalid_value(s, "`nonzero` or `evenodd`")),
+        }
+    }
+}
+
+impl Display for FillRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FillRule::NonZero => write!(f, "nonzero"),
+            FillRule::EvenOdd => write!(f, "evenodd"),
+        }
+    }
+}
+
+/// CSS color keywords sorted by name
+const NAMED_COLORS: [(&str, [u8; 4]); 149] = [
+    ("aliceblue", [240, 248, 255, 255]),
+    ("antiquewhite", [250, 235, 215, 255]),
+    ("aqua", [0, 255, 255, 255]),
+    ("aquamarine", [127, 255, 212, 255]),
+    ("azure", [240, 255, 255, 255]),
+    ("beige", [245, 245, 220, 255]),
+    ("bisque", [255, 228, 196, 255]),
+    ("black", [0, 0, 0, 255]),
+    ("blanchedalmond", [255, 235, 205, 255]),
+    ("blue", [0, 0, 255, 255]),
+    ("blueviolet", [138, 43, 226, 255]),
+    ("brown", [165, 42, 42, 255]),
+    ("burlywood", [222, 184, 135, 255]),
+    ("cadetblue", [95, 158, 160, 255]),
+    ("chartreuse", [127, 255, 0, 255]),
+    ("chocolate", [210, 105, 30, 255]),
+    ("coral", [255, 127, 80, 255]),
+    ("cornflowerblue", [100, 149, 237, 255]),
+    ("cornsilk", [255, 248, 220, 255]),
+    ("crimson", [220, 20, 60, 255]),
+    ("cyan", [0, 255, 255, 255]),
+    ("darkblue", [0, 0, 139, 255]),
+    ("darkcyan", [0, 139, 139, 255]),
+    ("darkgoldenrod", [184, 134, 11, 255]),
+    ("darkgray", [169, 169, 169, 255]),
+    ("darkgreen", [0, 100, 0, 255]),
+    ("darkgrey", [169, 169, 169, 255]),
+    ("darkkhaki", [189, 183, 107, 255]),
+    ("darkmagenta", [139, 0, 139, 255]),
+    ("darkolivegreen", [85, 107, 47, 255]),
+    ("darkorange", [255, 140, 0, 255]),
+    ("darkorchid", [153, 50, 204, 255]),
+    ("darkred", [139, 0, 0, 255]),
+    ("darksalmon", [233, 150, 122, 255]),
+    ("darkseagreen", [143, 188, 143, 255]),
+    ("darkslateblue", [72, 61, 139, 255]),
+    ("darkslategray", [47, 79, 79, 255]),
+    ("darkslategrey", [47, 79, 79, 255]),
+    ("darkturquoise", [0, 206, 209, 255]),
+    ("darkviolet", [148, 0, 211, 255]),
+    ("deeppink", [255, 20, 147, 255]),
+    ("deepskyblue", [0, 191, 255, 255]),
+    ("dimgray", [105, 105, 105, 255]),
+    ("dimgrey", [105, 105, 105, 255]),
+    ("dodgerblue", [30, 144, 255, 255]),
+    ("firebrick", [178, 34, 34, 255]),
+    ("floralwhite", [255, 250, 240, 255]),
+    ("forestgreen", [34, 139, 34, 255]),
+    ("fuchsia", [255, 0, 255, 255]),
+    ("gainsboro", [220, 220, 220, 255]),
+    ("ghostwhite", [248, 248, 255, 255]),
+    ("gold", [255, 215, 0, 255]),
+    ("goldenrod", [218, 165, 32, 255]),
+    ("gray", [128, 128, 128, 255]),
+    ("green", [0, 128, 0, 255]),
+    ("greenyellow", [173, 255, 47, 255]),
+    ("grey", [128, 128, 128, 255]),
+    ("honeydew", [240, 255, 240, 255]),
+    ("hotpink", [255, 105, 180, 255]),
+    ("indianred", [205, 92, 92, 255]),
+    ("indigo", [75, 0, 130, 255]),
+    ("ivory", [255, 255, 240, 255]),
+    ("khaki", [240, 230, 140, 255]),
+    ("lavender", [230, 230, 250, 255]),
+    ("lavenderblush", [255, 240, 245, 255]),
+    ("lawngreen", [124, 252, 0, 255]),
+    ("lemonchiffon", [255, 250, 205, 255]),
+    ("lightblue", [173, 216, 230, 255]),
+    ("lightcoral", [240, 128, 128, 255]),
+    ("lightcyan", [224, 255, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210, 255]),
+    ("lightgray", [211, 211, 211, 255]),
+    ("lightgreen", [144, 238, 144, 255]),
+    ("lightgrey", [211, 211, 211, 255]),
+    ("lightpink", [255, 182, 193, 255]),
+    ("lightsalmon", [255, 160, 122, 255]),
+    ("lightseagreen", [32, 178, 170, 255]),
+    ("lightskyblue", [135, 206, 250, 255]),
+    ("lightslategray", [119, 136, 153, 255]),
+    ("lightslategrey", [119, 136, 153, 255]),
+    ("lightsteelblue", [176, 196, 222, 255]),
+    ("lightyellow", [255, 255, 224, 255]),
+    ("lime", [0, 255, 0, 255]),
+    ("limegreen", [50, 205, 50, 255]),
+    ("linen", [250, 240, 230, 255]),
+    ("magenta", [255, 0, 255, 255]),
+    ("maroon", [128, 0, 0, 255]),
+    ("mediumaquamarine", [102, 205, 170, 255]),
+    ("mediumblue", [0, 0, 205, 255]),
+    ("mediumorchid", [186, 85, 211, 255]),
+    ("mediumpurple", [147, 112, 219, 255]),
+    ("mediumseagreen", [60, 179, 113, 255]),
+    ("mediumslateblue", [123, 104, 238, 255]),
+    ("mediumspringgreen", [0, 250, 154, 255]),
+    ("mediumturquoise", [72, 209, 204, 255]),
+    ("mediumvioletred", [199, 21, 133, 255]),
+    ("midnightblue", [25, 25, 112, 255]),
+    ("mintcream", [245, 255, 250, 255]),
+    ("mistyrose", [255, 228, 225, 255]),
+    ("moccasin", [255, 228, 181, 255]),
+    ("navajowhite", [255, 222, 173, 255]),
+    ("navy", [0, 0, 128, 255]),
+    ("oldlace", [253, 245, 230, 255]),
+    ("olive", [128, 128, 0, 255]),
+    ("olivedrab", [107, 142, 35, 255]),
+    ("orange", [255, 165, 0, 255]),
+    ("orangered", [255, 69, 0, 255]),
+    ("orchid", [218, 112, 214, 255]),
+    ("palegoldenrod", [238, 232, 170, 255]),
+    ("palegreen", [152, 251, 152, 255]),
+    ("paleturquoise", [175, 238, 238, 255]),
+    ("palevioletred", [219, 112, 147, 255]),
+    ("papayawhip", [255, 239, 213, 255]),
+    ("peachpuff", [255, 218, 185, 255]),
+    ("peru", [205, 133, 63, 255]),
+    ("pink", [255, 192, 203, 255]),
+    ("plum", [221, 160, 221, 255]),
+    ("powderblue", [176, 224, 230, 255]),
+    ("purple", [128, 0, 128, 255]),
+    ("rebeccapurple", [102, 51, 153, 255]),
+    ("red", [255, 0, 0, 255]),
+    ("rosybrown", [188, 143, 143, 255]),
+    ("royalblue", [65, 105, 225, 255]),
+    ("saddlebrown", [139, 69, 19, 255]),
+    ("salmon", [250, 128, 114, 255]),
+    ("sandybrown", [244, 164, 96, 255]),
+    ("seagreen", [46, 139, 87, 255]),
+    ("seashell", [255, 245, 238, 255]),
+    ("sienna", [160, 82, 45, 255]),
+    ("silver", [192, 192, 192, 255]),
+    ("skyblue", [135, 206, 235, 255]),
+    ("slateblue", [106, 90, 205, 255]),
+    ("slategray", [112, 128, 144, 255]),
+    ("slategrey", [112, 128, 144, 255]),
+    ("snow", [255, 250, 250, 255]),
+    ("springgreen", [0, 255, 127, 255]),
+    ("steelblue", [70, 130, 180, 255]),
+    ("tan", [210, 180, 140, 255]),
+    ("teal", [0, 128, 128, 255]),
+    ("thistle", [216, 191, 216, 255]),
+    ("tomato", [255, 99, 71, 255]),
+    ("transparent", [0, 0, 0, 0]),
+    ("turquoise", [64, 224, 208, 255]),
+    ("violet", [238, 130, 238, 255]),
+    ("wheat", [245, 222, 179, 255]),
+    ("white", [255, 255, 255, 255]),
+    ("whitesmoke", [245, 245, 245, 255]),
+    ("yellow", [255, 255, 0, 255]),
+    ("yellowgreen", [154, 205, 50, 255]),
+];
+
+#[cfg(test)]
+mod test_paint {
+    use super::*;
+
+    #[test]
+    fn test_named_colors_sorted() {
+        assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(Color::named("RebeccaPurple"), Some(Color::rgb(102, 51, 153)));
+        assert_eq!(Color::named("transparent"), Some(Color::TRANSPARENT));
+        assert_eq!(Color::named("reddish"), None);
+    }
+
+    #[test]
+    fn test_colors() {
+        let red = Color::rgb(255, 0, 0);
+        let cases = [
+            ("red", red),
+            ("#f00", red),
+            ("#F00F", red),
+            ("#ff0000", red),
+            ("#ff000080", Color::rgba(255, 0, 0, 128)),
+            ("rgb(255, 0, 0)", red),
+            ("rgb(100%,0%,0%)", red),
+            ("rgba(255, 0, 0, 0.5)", Color::rgba(255, 0, 0, 128)),
+            ("rgb(255 0 0 / 50%)", Color::rgba(255, 0, 0, 128)),
+            ("RGB(255 0 0)", red),
+            ("hsl(0, 100%, 50%)", red),
+            ("hsl(120deg 100% 25%)", Color::rgb(0, 128, 0)),
+            ("hsla(240, 100%, 50%, 0)", Color::rgba(0, 0, 255, 0)),
+            ("hsl(-120 100% 50%)", Color::rgb(0, 0, 255)),
+        ];
+        for (input, color) in cases {
+            assert_eq!(input.parse::<Color>().unwrap(), color, "{:?}", input);
+        }
+        for invalid in ["", "#ff", "#ggg", "rgb(1, 2)", "rgb(1 2 3 4 5)", "hsl(0, 1, 2)", "foo(1 2 3)", "#中文"] {
+            assert!(invalid.parse::<Color>().is_err(), "{:?}", invalid);
+        }
+        assert_eq!(red.to_rgba_f32(), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(Color::rgba(1, 2, 3, 4).to_rgba8(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_paint() {
+        let cases = [
+            ("none", Paint::None, "none"),
+            ("currentcolor", Paint::CurrentColor, "currentColor"),
+            ("Red", Paint::Color(Color::rgb(255, 0, 0)), "#ff0000"),
+            (
+                "url(#grad)",
+                Paint::Url {
+                    id: "grad".to_string(),
+                    fallback: None,
+                },
+                "url(#grad)",
+            ),
+            (
+                "url('#grad') #000",
+                Paint::Url {
+                    id: "grad".to_string(),
+                    fallback: Some(Box::new(Paint::Color(Color::BLACK))),
+                },
+                "url(#grad) #000000",
+            ),
+        ];
+        for (input, paint, output) in cases {
+            assert_eq!(input.parse::<Paint>().unwrap(), paint, "{:?}", input);
+            assert_eq!(paint.to_string(), output);
+        }
+        for invalid in ["", "url(grad)", "url(#a) url(#b)", "blurple"] {
+            assert!(invalid.parse::<Paint>().is_err(), "{:?}", invalid);
+        }
+        let white = Color::WHITE;
+        assert_eq!(Paint::CurrentColor.color(white), Some(white));
+        assert_eq!("url(#a) red".parse::<Paint>().unwrap().color(white), Some(Color::rgb(255, 0, 0)));
+        assert_eq!(Paint::None.color(white), None);
+    }
+}
@@ -1,7 +1,9 @@
 use nom::{
-    branch::alt, bytes::complete::{tag, take_until, take_while_m_n}, character::complete::{alphanumeric1, multispace0, space1}, combinator::{map, recognize}, multi::many0, number::complete::float, sequence::{delimited, pair, preceded, separated_pair}, IResult
+    branch::alt, bytes::complete::{tag, take_until, take_while, take_while_m_n}, character::complete::{alphanumeric1, char, digit0, digit1, multispace0, multispace1, one_of, satisfy}, combinator::{map, map_opt, opt, recognize}, multi::many0, sequence::{delimited, pair, preceded, separated_pair, tuple}
 };
 
+use crate::error::IResult;
+
 /// ## normal parser for easy string and split string
 /// depend on what split sign
 pub fn parse_normal(input: &str, sign: char) -> IResult<&str, &str> {
@@ -23,18 +25,19 @@ where
     delimited(multispace0, parser, multispace0)
 }
 
+/// ## xml name parser for element and attribute names
+/// such as `path`, `stroke-width`, `xmlns:xlink`, `data-icon_name`
 pub fn parse_normal_key(input: &str) -> IResult<&str, &str> {
     let (input, value) = recognize(pair(
-        alphanumeric1,
-        take_while_m_n(0, usize::MAX, |c: char| c == '-' || c.is_alphanumeric()),
+        satisfy(|c: char| c.is_alphabetic() || c == '_' || c == ':'),
+        take_while(|c: char| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')),
     ))(input)?;
     Ok((input, value))
 }
 
-#[allow(dead_code)]
 pub fn parse_property(input: &str) -> IResult<&str, (&str, &str)> {
     let (input, key) = parse_normal_key(input)?;
-    let (input, value) = preceded(tag("="), parse_string)(input)?;
+    let (input, value) = preceded(trim(tag("=")), parse_string)(input)?;
     Ok((input, (key, value)))
 }
 
@@ -42,14 +45,65 @@ pub fn parse_properties(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
     many0(trim(parse_property))(input)
 }
 
+/// a quoted attribute value, both `"value"` and `'value'`
 pub fn parse_string(input: &str) -> IResult<&str, &str> {
-    delimited(tag("\""), take_until("\""), tag("\""))(input)
+    alt((
+        delimited(tag("\""), take_until("\""), tag("\"")),
+        delimited(tag("'"), take_until("'"), tag("'")),
+    ))(input)
 }
 
+/// ## number parser follow the svg path grammar
+/// `sign? (digits ("." digits?)? | "." digits) exponent?`
+///
+/// it does not need any separator before a sign or a second decimal point,
+/// so `10-5` is `10` then `-5` and `1.5.5` is `1.5` then `.5`.
+/// numbers out of the range of f32 (`1e39`) are an error, they could not be written back
+pub fn number(input: &str) -> IResult<&str, f32> {
+    map_opt(
+        recognize(tuple((
+            opt(one_of("+-")),
+            alt((
+                recognize(pair(digit1, opt(pair(char('.'), digit0)))),
+                recognize(pair(char('.'), digit1)),
+            )),
+            opt(tuple((one_of("eE"), opt(one_of("+-")), digit1))),
+        ))),
+        |v: &str| v.parse::<f32>().ok().filter(|v| v.is_finite()),
+    )(input)
+}
+
+/// `comma-wsp: (wsp+ ","? wsp*) | ("," wsp*)`
+pub fn comma_wsp(input: &str) -> IResult<&str, &str> {
+    recognize(alt((
+        recognize(tuple((multispace1, opt(char(',')), multispace0))),
+        recognize(pair(char(','), multispace0)),
+    )))(input)
+}
+
+/// optional separator between two arguments of a path command
+pub fn sep(input: &str) -> IResult<&str, Option<&str>> {
+    opt(comma_wsp)(input)
+}
+
+/// a coordinate pair, `x,y` | `x y` | `x-y`
 pub fn point(input: &str) -> IResult<&str, (f32, f32)> {
-    trim(separated_pair(float, alt((tag(","), space1)), float))(input)
+    separated_pair(number, sep, number)(input)
 }
 
+/// a single `0` or `1` flag, arc flags may be packed: `a1 1 0 00.5.5`
 pub fn bool_flag(input: &str) -> IResult<&str, bool> {
-    map(trim(alt((tag("0"), tag("1")))), |v| v == "1")(input)
+    map(one_of("01"), |v| v == '1')(input)
+}
+
+/// ## command letter parser
+/// match the upper or lower case of `letter` and return whether the command is relative
+pub fn command<'a>(letter: char) -> impl FnMut(&'a str) -> IResult<&'a str, bool> {
+    map(
+        trim(alt((
+            char(letter.to_ascii_uppercase()),
+            char(letter.to_ascii_lowercase()),
+        ))),
+        |c: char| c.is_ascii_lowercase(),
+    )
 }
\ No newline at end of file
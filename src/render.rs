@@ -0,0 +1,439 @@
+//! Rasterize icons into RGBA pixels and PNG files, with the `render` feature.
+//!
+//! Shapes, paths and groups are drawn with solid colors: `fill`, `fill-rule`, `fill-opacity`,
+//! the `stroke` properties with dashes, `opacity`, `color` and `display="none"`.
+//! Gradients and patterns are drawn with their fallback color, text and unknown elements
+//! (`<use>`, `<image>`, clip paths, masks...) are not drawn.
+mod png;
+mod raster;
+mod stroke;
+
+pub use crate::paint::FillRule;
+
+use crate::bbox::Inherited;
+use crate::children::{Child, CommonKVs, Curves, Opacity, PathData};
+use crate::error::Error;
+use crate::length::{Axis, Length, LengthContext, Unit};
+use crate::paint::Color;
+use crate::transform::Matrix;
+use crate::view_box::ViewBox;
+use crate::Svg;
+
+/// the largest distance of the drawn curves to the exact ones, in pixels
+const TOLERANCE: f32 = 0.1;
+
+/// the largest width and height of a rendered image in pixels,
+/// larger sizes are scaled down with their aspect ratio
+pub const MAX_SIZE: u32 = 4096;
+
+/// An image of `width` x `height` pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pixmap {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA8 pixels, not premultiplied, row by row from the top left
+    pub data: Vec<u8>,
+}
+
+impl Pixmap {
+    /// the color of the pixel at `(x, y)`, `None` out of the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let [r, g, b, a] = self.data[index..index + 4] else {
+            return None;
+        };
+        Some(Color::rgba(r, g, b, a))
+    }
+    /// the PNG file of the image
+    pub fn encode_png(&self) -> Vec<u8> {
+        png::encode(self.width, self.height, &self.data)
+    }
+    pub fn save_png<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        std::fs::write(path, self.encode_png())?;
+        Ok(())
+    }
+}
+
+/// How [`Svg::render`] draws an icon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    /// the size of the image in pixels, if only one is set the other one keeps the
+    /// aspect ratio of the icon, if none is set the size of the icon is used.
+    /// both are at most [`MAX_SIZE`]
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// the value of `currentColor` if the icon sets no `color`
+    pub current_color: Color,
+    /// the color below the icon
+    pub background: Color,
+}
+
+impl Default for RenderOptions {
+    /// the size of the icon, black `currentColor` on a transparent background
+    fn default() -> Self {
+        RenderOptions {
+            width: None,
+            height: None,
+            current_color: Color::BLACK,
+            background: Color::TRANSPARENT,
+        }
+    }
+}
+
+/// Premultiplied RGBA pixels from 0 to 1, drawn into and composited.
+struct Layer {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Layer {
+    fn new(width: usize, height: usize) -> Self {
+        Layer {
+            width,
+            height,
+            pixels: vec![[0_f32; 4]; width * height],
+        }
+    }
+    /// draw `color` over the pixels of row `y` by the coverage of each one
+    fn paint_row(&mut self, y: usize, coverage: &[f32], color: [f32; 4]) {
+        let row = &mut self.pixels[y * self.width..(y + 1) * self.width];
+        for (pixel, c) in row.iter_mut().zip(coverage) {
+            if *c > 0_f32 {
+                let alpha = color[3] * c;
+                for i in 0..4 {
+                    pixel[i] = color[i] * c + pixel[i] * (1_f32 - alpha);
+                }
+            }
+        }
+    }
+    /// fill the polygons, in pixels
+    fn fill(&mut self, polygons: &[Vec<(f32, f32)>], rule: FillRule, color: [f32; 4]) {
+        let (width, height) = (self.width, self.height);
+        raster::fill(polygons, rule, width, height, |y, coverage| {
+            self.paint_row(y, coverage, color)
+        });
+    }
+    /// draw `other` over this layer with `opacity`
+    fn composite(&mut self, other: &Layer, opacity: f32) {
+        for (pixel, top) in self.pixels.iter_mut().zip(&other.pixels) {
+            let alpha = top[3] * opacity;
+            for i in 0..4 {
+                pixel[i] = top[i] * opacity + pixel[i] * (1_f32 - alpha);
+            }
+        }
+    }
+    fn into_pixmap(self) -> Pixmap {
+        let data = self
+            .pixels
+            .iter()
+            .flat_map(|[r, g, b, a]| match *a > 0_f32 {
+                true => Color::from_rgba_f32(r / a, g / a, b / a, *a).to_rgba8(),
+                false => [0; 4],
+            })
+            .collect();
+        Pixmap {
+            width: self.width as u32,
+            height: self.height as u32,
+            data,
+        }
+    }
+}
+
+/// a color with an opacity, premultiplied
+fn premultiply(color: Color, opacity: f32) -> [f32; 4] {
+    let [r, g, b, a] = color.to_rgba_f32();
+    let a = a * opacity;
+    [r * a, g * a, b * a, a]
+}
+
+/// how much the matrix stretches lengths at most
+fn max_scale(matrix: &Matrix) -> f32 {
+    matrix.a.hypot(matrix.b).max(matrix.c.hypot(matrix.d))
+}
+
+fn draw_path(layer: &mut Layer, d: &PathData, inherited: &Inherited) {
+    let matrix = &inherited.matrix;
+    if let Some(color) = inherited.fill.color(inherited.color) {
+        let mut device = d.clone();
+        device.transform(matrix);
+        let polygons: Vec<_> = device.flatten(TOLERANCE).map(|subpath| subpath.points).collect();
+        layer.fill(&polygons, inherited.fill_rule, premultiply(color, inherited.fill_opacity));
+    }
+    if let Some(color) = inherited.stroke_paint.color(inherited.color) {
+        // the stroke is built in user space, where its width is, then mapped to pixels
+        let scale = max_scale(matrix);
+        if scale <= 0_f32 || !scale.is_finite() {
+            return;
+        }
+        let tolerance = TOLERANCE / scale;
+        let subpaths: Vec<_> = d.flatten(tolerance).collect();
+        let subpaths = match inherited.dashes.is_empty() {
+            true => subpaths,
+            false => stroke::dash(&subpaths, &inherited.dashes, inherited.dash_offset, tolerance),
+        };
+        let mut polygons = stroke::stroke(&subpaths, &inherited.stroke, tolerance);
+        for point in polygons.iter_mut().flatten() {
+            *point = matrix.apply(point.0, point.1);
+        }
+        layer.fill(&polygons, FillRule::NonZero, premultiply(color, inherited.stroke_opacity));
+    }
+}
+
+/// draw an element with `common` by `draw`, unless it has `display="none"`.
+/// a translucent element is drawn alone, then over the others
+fn draw_element(layer: &mut Layer, common: &CommonKVs, draw: impl FnOnce(&mut Layer)) {
+    if common.other("display").map(str::trim) == Some("none") {
+        return;
+    }
+    let opacity = common
+        .other("opacity")
+        .and_then(|o| o.parse::<Opacity>().ok())
+        .map_or(1_f32, |o| o.0);
+    if opacity >= 1_f32 {
+        draw(layer);
+    } else if opacity > 0_f32 {
+        let mut group = Layer::new(layer.width, layer.height);
+        draw(&mut group);
+        layer.composite(&group, opacity);
+    }
+}
+
+fn draw_children(layer: &mut Layer, children: &[Child], parent: &Inherited, context: &LengthContext) {
+    for child in children {
+        let Some(common) = child.common() else {
+            continue;
+        };
+        let inherited = parent.of(common, context);
+        draw_element(layer, common, |target| match child {
+            Child::Group(group) => draw_children(target, &group.children, &inherited, context),
+            child => {
+                if let Some(d) = child.to_path(context, Curves::Arcs).and_then(|path| path.d) {
+                    draw_path(target, &d, &inherited);
+                }
+            }
+        });
+    }
+}
+
+impl Svg {
+    /// the size of the icon in user units: `width` and `height`, else the size of the `viewBox`.
+    /// a missing or percentage side follows the aspect ratio of the `viewBox`, the viewport
+    /// a percentage is of is not known here
+    fn intrinsic_size(&self) -> (f32, f32) {
+        let context = self.length_context();
+        let absolute = |length: Option<Length>, axis| {
+            length
+                .filter(|l| l.unit != Unit::Percent)
+                .map(|l| l.resolve(axis, &LengthContext::default()))
+        };
+        match (absolute(self.width.0, Axis::X), absolute(self.height.0, Axis::Y)) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, width * context.height / context.width),
+            (None, Some(height)) => (height * context.width / context.height, height),
+            (None, None) => (context.width, context.height),
+        }
+    }
+    /// draw the icon into pixels, the `viewBox` fills the image following `preserveAspectRatio`.
+    ///
+    /// `<style>` rules and `style` attributes are applied first, see [`Svg::apply_styles`]
+    ///
+    /// ## Example
+    /// ```rust
+    /// use svg_icon::paint::Color;
+    /// use svg_icon::render::RenderOptions;
+    /// use svg_icon::Svg;
+    ///
+    /// let svg: Svg = r#"<svg viewBox="0 0 24 24"><rect x="12" width="12" height="24" fill="red"/></svg>"#.parse().unwrap();
+    /// let pixmap = svg.render(&RenderOptions {
+    ///     width: Some(48),
+    ///     ..Default::default()
+    /// });
+    /// assert_eq!((pixmap.width, pixmap.height), (48, 48));
+    /// assert_eq!(pixmap.pixel(0, 0), Some(Color::TRANSPARENT));
+    /// assert_eq!(pixmap.pixel(47, 47), Some(Color::rgb(255, 0, 0)));
+    /// let png = pixmap.encode_png();
+    /// assert_eq!(&png[1..4], b"PNG");
+    /// ```
+    pub fn render(&self, options: &RenderOptions) -> Pixmap {
+        let (intrinsic_width, intrinsic_height) = self.intrinsic_size();
+        let ratio = match intrinsic_width > 0_f32 {
+            true => intrinsic_height / intrinsic_width,
+            false => 1_f32,
+        };
+        let (width, height) = match (options.width, options.height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, (width as f32 * ratio).round() as u32),
+            (None, Some(height)) => ((height as f32 / ratio).round() as u32, height),
+            (None, None) => (intrinsic_width.ceil() as u32, intrinsic_height.ceil() as u32),
+        };
+        let scale = (MAX_SIZE as f32 / width.max(height) as f32).min(1_f32);
+        let (width, height) = (
+            ((width as f32 * scale).round() as u32).clamp(1, MAX_SIZE),
+            ((height as f32 * scale).round() as u32).clamp(1, MAX_SIZE),
+        );
+
+        let viewport = ViewBox::new(0_f32, 0_f32, width as f32, height as f32);
+        let view_box = self
+            .view_box
+            .unwrap_or_else(|| ViewBox::new(0_f32, 0_f32, intrinsic_width, intrinsic_height));
+        let matrix = self
+            .preserve_aspect_ratio
+            .unwrap_or_default()
+            .matrix(&view_box, &viewport);
+
+        let mut svg = self.clone();
+        svg.apply_styles(false);
+        let context = svg.length_context();
+        let root = Inherited {
+            matrix,
+            color: options.current_color,
+            ..Default::default()
+        }
+        .of(&svg.common, &context);
+
+        let mut layer = Layer::new(width as usize, height as usize);
+        let background = premultiply(options.background, 1_f32);
+        layer.pixels.iter_mut().for_each(|pixel| *pixel = background);
+        draw_element(&mut layer, &svg.common, |target| {
+            draw_children(target, &svg.children, &root, &context)
+        });
+        layer.into_pixmap()
+    }
+}
+
+#[cfg(test)]
+mod test_render {
+    use super::*;
+
+    fn render(source: &str, size: u32) -> Pixmap {
+        let svg: Svg = source.parse().unwrap();
+        svg.render(&RenderOptions {
+            width: Some(size),
+            height: Some(size),
+            ..Default::default()
+        })
+    }
+
+    fn pixel(pixmap: &Pixmap, x: u32, y: u32) -> [u8; 4] {
+        pixmap.pixel(x, y).unwrap().to_rgba8()
+    }
+
+    #[test]
+    fn test_fill() {
+        let pixmap = render(
+            r##"<svg viewBox="0 0 10 10"><rect width="5" height="10" fill="#00f"/><circle cx="5" cy="5" r="2" fill="red" fill-opacity="0.5"/></svg>"##,
+            10,
+        );
+        assert_eq!(pixel(&pixmap, 0, 0), [0, 0, 255, 255]);
+        assert_eq!(pixel(&pixmap, 9, 0), [0, 0, 0, 0]);
+        // half red over blue, and half red over nothing
+        assert_eq!(pixel(&pixmap, 4, 5), [128, 0, 128, 255]);
+        assert_eq!(pixel(&pixmap, 5, 5), [255, 0, 0, 128]);
+        // anti-aliased edges
+        let edge = render(r#"<svg viewBox="0 0 2 2"><rect width="1.5" height="2"/></svg>"#, 2);
+        assert_eq!(pixel(&edge, 1, 0), [0, 0, 0, 128]);
+    }
+
+    #[test]
+    fn test_fill_rule() {
+        let source = |rule: &str| {
+            format!(
+                r#"<svg viewBox="0 0 3 3"><path fill-rule="{}" d="M0 0h3v3h-3z M1 1h1v1h-1z"/></svg>"#,
+                rule
+            )
+        };
+        assert_eq!(pixel(&render(&source("nonzero"), 3), 1, 1)[3], 255);
+        assert_eq!(pixel(&render(&source("evenodd"), 3), 1, 1)[3], 0);
+        assert_eq!(pixel(&render(&source("evenodd"), 3), 0, 0)[3], 255);
+    }
+
+    #[test]
+    fn test_stroke() {
+        // a 2 units wide line along y = 5, from x = 2 to 8
+        let source = |extra: &str| {
+            format!(
+                r#"<svg viewBox="0 0 10 10"><path d="M2 5H8" stroke="currentColor" stroke-width="2" fill="none" {}/></svg>"#,
+                extra
+            )
+        };
+        let pixmap = render(&source(""), 10);
+        assert_eq!(pixel(&pixmap, 4, 4), [0, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 4, 3), [0, 0, 0, 0]);
+        assert_eq!(pixel(&pixmap, 1, 4), [0, 0, 0, 0]);
+        // square caps reach one more unit, dashes leave gaps
+        let pixmap = render(&source(r#"stroke-linecap="square" color="lime""#), 10);
+        assert_eq!(pixel(&pixmap, 1, 4), [0, 255, 0, 255]);
+        let pixmap = render(&source(r#"stroke-dasharray="1""#), 10);
+        assert_eq!(pixel(&pixmap, 2, 4)[3], 255);
+        assert_eq!(pixel(&pixmap, 3, 4)[3], 0);
+        // dashes too fine to be seen are a solid line
+        let pixmap = render(&source(r#"stroke-dasharray="0.00001""#), 10);
+        assert_eq!(pixel(&pixmap, 3, 4)[3], 255);
+        // the stroke is scaled by the transforms and the viewBox
+        let pixmap = render(&source(r#"transform="scale(0.5)""#), 20);
+        assert_eq!(pixel(&pixmap, 4, 4)[3], 255);
+        assert_eq!(pixel(&pixmap, 4, 3)[3], 0);
+        assert_eq!(pixel(&pixmap, 7, 4)[3], 255);
+        assert_eq!(pixel(&pixmap, 8, 4)[3], 0);
+    }
+
+    #[test]
+    fn test_opacity_and_size() {
+        // the group is drawn as a whole: overlapping children do not add up
+        let pixmap = render(
+            r#"<svg viewBox="0 0 4 4"><g opacity="0.5"><rect width="4" height="4"/><rect width="2" height="2"/></g><rect x="3" y="3" width="1" height="1" display="none"/></svg>"#,
+            4,
+        );
+        assert_eq!(pixel(&pixmap, 0, 0), [0, 0, 0, 128]);
+        assert_eq!(pixel(&pixmap, 3, 3), [0, 0, 0, 128]);
+
+        // the size of the icon, a wide viewBox is centered
+        let svg: Svg = r#"<svg width="8" height="4" viewBox="0 0 4 4" style="fill:#fff"><rect width="4" height="4"/></svg>"#
+            .parse()
+            .unwrap();
+        let pixmap = svg.render(&RenderOptions {
+            background: Color::BLACK,
+            ..Default::default()
+        });
+        assert_eq!((pixmap.width, pixmap.height), (8, 4));
+        assert_eq!(pixel(&pixmap, 1, 0), [0, 0, 0, 255]);
+        assert_eq!(pixel(&pixmap, 2, 0), [255, 255, 255, 255]);
+        let pixmap = svg.render(&RenderOptions {
+            height: Some(8),
+            ..Default::default()
+        });
+        assert_eq!((pixmap.width, pixmap.height), (16, 8));
+
+        // percentages keep the aspect ratio of the viewBox
+        for source in [
+            r#"<svg width="100%" height="100%" viewBox="0 0 24 24"></svg>"#,
+            r#"<svg width="100%" viewBox="0 0 24 24"></svg>"#,
+            r#"<svg width="50%" height="24" viewBox="0 0 24 24"></svg>"#,
+        ] {
+            let svg: Svg = source.parse().unwrap();
+            let pixmap = svg.render(&RenderOptions::default());
+            assert_eq!((pixmap.width, pixmap.height), (24, 24), "{}", source);
+        }
+        let svg: Svg = r#"<svg width="48" height="50%" viewBox="0 0 24 12"></svg>"#.parse().unwrap();
+        let pixmap = svg.render(&RenderOptions::default());
+        assert_eq!((pixmap.width, pixmap.height), (48, 24));
+
+        // huge sizes are scaled down to `MAX_SIZE`
+        let svg: Svg = r#"<svg width="1000000" height="500000"></svg>"#.parse().unwrap();
+        let pixmap = svg.render(&RenderOptions::default());
+        assert_eq!((pixmap.width, pixmap.height), (MAX_SIZE, MAX_SIZE / 2));
+        let pixmap = svg.render(&RenderOptions {
+            width: Some(u32::MAX),
+            height: Some(1),
+            ..Default::default()
+        });
+        assert_eq!((pixmap.width, pixmap.height), (MAX_SIZE, 1));
+    }
+}
@@ -0,0 +1,342 @@
+//! A PNG encoder: truecolor with alpha, 8 bits per channel, deflate with fixed Huffman codes.
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// the distance a match may be back in the data
+const WINDOW: usize = 1 << 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// how many earlier positions with the same hash are tried
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0_u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |c, byte| {
+        CRC_TABLE[((c ^ *byte as u32) & 0xFF) as usize] ^ (c >> 8)
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    // the sums do not overflow within 5552 bytes
+    for chunk in bytes.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// Bits packed from the least significant one, as deflate wants them.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+    /// a Huffman code, which is written from its most significant bit
+    fn code(&mut self, code: u32, length: u32) {
+        self.bits(code.reverse_bits() >> (32 - length), length);
+    }
+    /// a literal/length symbol of the fixed Huffman code
+    fn symbol(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xC0 + symbol - 280, 8),
+        }
+    }
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// the index in `bases` of the range containing `value`
+fn range(bases: &[u16], value: usize) -> usize {
+    bases.partition_point(|base| *base as usize <= value) - 1
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+    (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+}
+
+/// a raw deflate stream of one block with the fixed Huffman codes, repeats are found
+/// with hash chains
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    // the last block, fixed Huffman codes
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut previous = vec![usize::MAX; WINDOW];
+    let insert = |head: &mut [usize], previous: &mut [usize], at: usize| {
+        if at + MIN_MATCH <= data.len() {
+            let h = hash(&data[at..]);
+            previous[at % WINDOW] = head[h];
+            head[h] = at;
+        }
+    };
+    let mut at = 0;
+    while at < data.len() {
+        let (mut best, mut distance) = (0, 0);
+        if at + MIN_MATCH <= data.len() {
+            let max = MAX_MATCH.min(data.len() - at);
+            let mut candidate = head[hash(&data[at..])];
+            for _ in 0..MAX_CHAIN {
+                if candidate == usize::MAX || at - candidate > WINDOW - 1 {
+                    break;
+                }
+                let length = data[candidate..]
+                    .iter()
+                    .zip(&data[at..at + max])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if length > best {
+                    (best, distance) = (length, at - candidate);
+                    if length == max {
+                        break;
+                    }
+                }
+                let next = previous[candidate % WINDOW];
+                // the chain only goes back
+                if next == usize::MAX || next >= candidate {
+                    break;
+                }
+                candidate = next;
+            }
+        }
+        if best >= MIN_MATCH {
+            let index = range(&LENGTH_BASES, best);
+            writer.symbol(257 + index as u16);
+            writer.bits((best - LENGTH_BASES[index] as usize) as u32, LENGTH_EXTRA[index] as u32);
+            let index = range(&DISTANCE_BASES, distance);
+            writer.code(index as u32, 5);
+            writer.bits(
+                (distance - DISTANCE_BASES[index] as usize) as u32,
+                DISTANCE_EXTRA[index] as u32,
+            );
+            for i in at..at + best {
+                insert(&mut head, &mut previous, i);
+            }
+            at += best;
+        } else {
+            writer.symbol(data[at] as u16);
+            insert(&mut head, &mut previous, at);
+            at += 1;
+        }
+    }
+    writer.symbol(256);
+    writer.finish()
+}
+
+/// the zlib stream of `data`
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x9C];
+    bytes.extend(deflate(data));
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// the rows with a filter byte in front, the filter of each row is the one with the
+/// smallest sum of absolute differences
+fn filter(width: usize, rgba: &[u8]) -> Vec<u8> {
+    let stride = width * 4;
+    let mut filtered = Vec::with_capacity(rgba.len() + rgba.len() / stride.max(1));
+    let zeros = vec![0_u8; stride];
+    let mut candidates = vec![vec![0_u8; stride]; 5];
+    for (y, row) in rgba.chunks(stride).enumerate() {
+        let above = if y == 0 { &zeros[..] } else { &rgba[(y - 1) * stride..y * stride] };
+        for i in 0..stride {
+            let left = if i >= 4 { row[i - 4] } else { 0 };
+            let upper_left = if i >= 4 { above[i - 4] } else { 0 };
+            candidates[0][i] = row[i];
+            candidates[1][i] = row[i].wrapping_sub(left);
+            candidates[2][i] = row[i].wrapping_sub(above[i]);
+            candidates[3][i] = row[i].wrapping_sub(((left as u16 + above[i] as u16) / 2) as u8);
+            candidates[4][i] = row[i].wrapping_sub(paeth(left, above[i], upper_left));
+        }
+        let (best, candidate) = candidates
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, c)| c.iter().map(|b| (*b as i8).unsigned_abs() as u32).sum::<u32>())
+            .unwrap();
+        filtered.push(best as u8);
+        filtered.extend_from_slice(candidate);
+    }
+    filtered
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// the PNG file of an image of straight (not premultiplied) RGBA pixels, row by row
+pub(crate) fn encode(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let mut png = SIGNATURE.to_vec();
+    let mut header = vec![];
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // 8 bits, truecolor with alpha, deflate, adaptive filters, no interlace
+    header.extend([8, 6, 0, 0, 0]);
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib(&filter(width as usize, rgba)));
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+#[cfg(test)]
+mod test_png {
+    use super::*;
+
+    /// an inflater of the blocks with fixed Huffman codes which `deflate` writes
+    fn inflate(bytes: &[u8]) -> Vec<u8> {
+        let mut position = 0;
+        let mut bits = |count: u32| {
+            let mut value = 0;
+            for i in 0..count {
+                let bit = (bytes[position / 8] >> (position % 8)) & 1;
+                value |= (bit as u32) << i;
+                position += 1;
+            }
+            value
+        };
+        assert_eq!(bits(1), 1);
+        assert_eq!(bits(2), 1);
+        let mut out: Vec<u8> = vec![];
+        loop {
+            // read the code from its most significant bit
+            let mut code = 0;
+            let mut length = 0;
+            let symbol = loop {
+                code = code << 1 | bits(1);
+                length += 1;
+                match (length, code) {
+                    (7, 0..=0x17) => break code + 256,
+                    (8, 0x30..=0xBF) => break code - 0x30,
+                    (8, 0xC0..=0xC7) => break code - 0xC0 + 280,
+                    (9, 0x190..=0x1FF) => break code - 0x190 + 144,
+                    _ => assert!(length < 9),
+                }
+            };
+            match symbol {
+                0..=255 => out.push(symbol as u8),
+                256 => return out,
+                _ => {
+                    let index = (symbol - 257) as usize;
+                    let length = LENGTH_BASES[index] as usize + bits(LENGTH_EXTRA[index] as u32) as usize;
+                    let index = (0..5).fold(0, |c, _| c << 1 | bits(1)) as usize;
+                    let distance = DISTANCE_BASES[index] as usize + bits(DISTANCE_EXTRA[index] as u32) as usize;
+                    for _ in 0..length {
+                        out.push(out[out.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn test_deflate() {
+        let mut data = b"abcabcabcabc hello hello hello".to_vec();
+        data.extend((0..2000).map(|i| (i * 7 % 13) as u8));
+        data.extend(vec![42; 1000]);
+        let compressed = deflate(&data);
+        assert!(compressed.len() < data.len() / 4);
+        assert_eq!(inflate(&compressed), data);
+        assert_eq!(inflate(&deflate(&[])), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_encode() {
+        let rgba: Vec<u8> = (0..3 * 2).flat_map(|i| [i * 40, 255 - i * 40, 0, 255]).collect();
+        let png = encode(3, 2, &rgba);
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 3, 0, 0, 0, 2]);
+        assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+        // the image data: a filter byte and the pixels of each row
+        let length = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let zlib = &png[41..41 + length];
+        let filtered = inflate(&zlib[2..zlib.len() - 4]);
+        assert_eq!(filtered.len(), 2 * (1 + 3 * 4));
+        assert_eq!(filtered, filter(3, &rgba));
+        assert_eq!(zlib[zlib.len() - 4..], adler32(&filtered).to_be_bytes());
+    }
+}
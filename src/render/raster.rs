@@ -0,0 +1,186 @@
+use super::FillRule;
+
+type Point = (f32, f32);
+
+/// sub scanlines sampled in each row of pixels, the vertical anti-aliasing
+const SAMPLES: usize = 16;
+
+/// A line of a polygon going down, `winding` is `1` if it went down before sorting, `-1` if up.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    winding: i32,
+}
+
+impl Edge {
+    fn new((x0, y0): Point, (x1, y1): Point) -> Option<Edge> {
+        match y0.partial_cmp(&y1)? {
+            std::cmp::Ordering::Less => Some(Edge { x0, y0, x1, y1, winding: 1 }),
+            std::cmp::Ordering::Greater => Some(Edge {
+                x0: x1,
+                y0: y1,
+                x1: x0,
+                y1: y0,
+                winding: -1,
+            }),
+            // a horizontal line crosses no scanline
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+    fn x_at(&self, y: f32) -> f32 {
+        self.x0 + (y - self.y0) * (self.x1 - self.x0) / (self.y1 - self.y0)
+    }
+}
+
+/// the coverage of the polygons in a `width` x `height` grid of pixels, every polygon is closed.
+///
+/// `row` is called with the index and the coverage (from 0 to 1) of every row the polygons touch.
+/// the horizontal coverage is exact, the vertical one is sampled on [`SAMPLES`] scanlines
+pub(crate) fn fill(
+    polygons: &[Vec<Point>],
+    rule: FillRule,
+    width: usize,
+    height: usize,
+    mut row: impl FnMut(usize, &[f32]),
+) {
+    let mut edges: Vec<Edge> = polygons
+        .iter()
+        .filter(|polygon| polygon.len() > 1)
+        .flat_map(|polygon| {
+            let closing = (polygon[polygon.len() - 1], polygon[0]);
+            polygon
+                .windows(2)
+                .map(|pair| (pair[0], pair[1]))
+                .chain(std::iter::once(closing))
+        })
+        .filter_map(|(from, to)| Edge::new(from, to))
+        .filter(|edge| edge.x0.is_finite() && edge.x1.is_finite() && edge.y1.is_finite())
+        .collect();
+    if edges.is_empty() || width == 0 {
+        return;
+    }
+    edges.sort_by(|a, b| a.y0.total_cmp(&b.y0));
+    let top = edges[0].y0.floor().max(0_f32) as usize;
+    let bottom = edges
+        .iter()
+        .map(|edge| edge.y1)
+        .fold(f32::MIN, f32::max)
+        .ceil()
+        .min(height as f32)
+        .max(0_f32) as usize;
+
+    // differences of coverage, summed up along the row
+    let mut deltas = vec![0_f32; width + 2];
+    let mut coverage = vec![0_f32; width];
+    let mut crossings: Vec<(f32, i32)> = vec![];
+    let mut active: Vec<Edge> = vec![];
+    let mut next = 0;
+    let weight = 1_f32 / SAMPLES as f32;
+    for y in top..bottom {
+        let (row_top, row_bottom) = (y as f32, (y + 1) as f32);
+        while next < edges.len() && edges[next].y0 < row_bottom {
+            active.push(edges[next]);
+            next += 1;
+        }
+        active.retain(|edge| edge.y1 > row_top);
+        if active.is_empty() {
+            continue;
+        }
+        deltas.iter_mut().for_each(|d| *d = 0_f32);
+        for sample in 0..SAMPLES {
+            let scanline = row_top + (sample as f32 + 0.5_f32) * weight;
+            crossings.clear();
+            crossings.extend(
+                active
+                    .iter()
+                    .filter(|edge| edge.y0 <= scanline && scanline < edge.y1)
+                    .map(|edge| (edge.x_at(scanline), edge.winding)),
+            );
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                let inside = match rule {
+                    FillRule::NonZero => winding != 0,
+                    FillRule::EvenOdd => winding % 2 != 0,
+                };
+                if inside {
+                    span(&mut deltas, pair[0].0, pair[1].0, width, weight);
+                }
+            }
+        }
+        let mut sum = 0_f32;
+        for (c, d) in coverage.iter_mut().zip(&deltas) {
+            sum += d;
+            *c = sum.clamp(0_f32, 1_f32);
+        }
+        row(y, &coverage);
+    }
+}
+
+/// add the coverage of the span from `a` to `b` on one scanline
+fn span(deltas: &mut [f32], a: f32, b: f32, width: usize, weight: f32) {
+    let (a, b) = (a.clamp(0_f32, width as f32), b.clamp(0_f32, width as f32));
+    if a >= b {
+        return;
+    }
+    let (ia, ib) = (a.floor(), b.floor());
+    let (fa, fb) = (a - ia, b - ib);
+    let (ia, ib) = (ia as usize, ib as usize);
+    deltas[ia] += weight * (1_f32 - fa);
+    deltas[ia + 1] += weight * fa;
+    deltas[ib] -= weight * (1_f32 - fb);
+    deltas[ib + 1] -= weight * fb;
+}
+
+#[cfg(test)]
+mod test_raster {
+    use super::*;
+
+    fn coverage(polygons: &[Vec<Point>], rule: FillRule, size: usize) -> Vec<f32> {
+        let mut all = vec![0_f32; size * size];
+        fill(polygons, rule, size, size, |y, row| {
+            all[y * size..(y + 1) * size].copy_from_slice(row)
+        });
+        all
+    }
+
+    #[test]
+    fn test_fill() {
+        // half a pixel on each side of a square
+        let square = vec![(0.5, 0.5), (2.5, 0.5), (2.5, 2.5), (0.5, 2.5)];
+        let all = coverage(&[square], FillRule::NonZero, 4);
+        let expected = [
+            0.25, 0.5, 0.25, 0.0, //
+            0.5, 1.0, 0.5, 0.0, //
+            0.25, 0.5, 0.25, 0.0, //
+            0.0, 0.0, 0.0, 0.0,
+        ];
+        for (c, e) in all.iter().zip(expected) {
+            assert!((c - e).abs() < 1e-5, "{:?}", all);
+        }
+        // a triangle covers half of its pixel, the area of a polygon is kept
+        let triangle = vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)];
+        assert!((coverage(&[triangle], FillRule::NonZero, 1)[0] - 0.5).abs() < 0.05);
+        // out of the grid
+        let outside = vec![(-4.0, -4.0), (-1.0, -4.0), (-1.0, 8.0)];
+        assert!(coverage(&[outside], FillRule::NonZero, 2).iter().all(|c| *c == 0.0));
+    }
+
+    #[test]
+    fn test_fill_rules() {
+        let outer = vec![(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0)];
+        let inner = vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)];
+        let reversed: Vec<Point> = inner.iter().rev().copied().collect();
+        let center = |polygons: &[Vec<Point>], rule| coverage(polygons, rule, 3)[4];
+        // the same direction: a hole only with evenodd
+        assert_eq!(center(&[outer.clone(), inner.clone()], FillRule::NonZero), 1.0);
+        assert_eq!(center(&[outer.clone(), inner], FillRule::EvenOdd), 0.0);
+        // the opposite direction: a hole with both rules
+        assert_eq!(center(&[outer.clone(), reversed.clone()], FillRule::NonZero), 0.0);
+        assert_eq!(center(&[outer, reversed], FillRule::EvenOdd), 0.0);
+    }
+}
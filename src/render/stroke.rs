@@ -0,0 +1,368 @@
+use std::f32::consts::PI;
+
+use crate::bbox::Stroke;
+use crate::children::{LineCap, LineJoin, Subpath};
+
+type Point = (f32, f32);
+
+/// points closer than this are one point
+const EPSILON: f32 = 1e-5;
+/// a subpath which would be split into more dashes than this is drawn without dashes
+const MAX_DASHES: usize = 1 << 16;
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// `point + vector * scale`
+fn offset(point: Point, vector: Point, scale: f32) -> Point {
+    (point.0 + vector.0 * scale, point.1 + vector.1 * scale)
+}
+
+/// the unit vector from `a` to `b`
+fn direction(a: Point, b: Point) -> Point {
+    let length = distance(a, b);
+    ((b.0 - a.0) / length, (b.1 - a.1) / length)
+}
+
+/// the points of the polygons which cover the stroke of the subpaths,
+/// they all turn the same way so they are filled with `nonzero`.
+///
+/// `tolerance` is the largest error of round caps and joins
+pub(crate) fn stroke(subpaths: &[Subpath], stroke: &Stroke, tolerance: f32) -> Vec<Vec<Point>> {
+    let mut stroker = Stroker {
+        stroke,
+        radius: stroke.width / 2_f32,
+        tolerance,
+        polygons: vec![],
+    };
+    if stroker.radius > 0_f32 {
+        for subpath in subpaths {
+            stroker.subpath(subpath);
+        }
+    }
+    stroker.polygons
+}
+
+struct Stroker<'a> {
+    stroke: &'a Stroke,
+    radius: f32,
+    tolerance: f32,
+    polygons: Vec<Vec<Point>>,
+}
+
+impl Stroker<'_> {
+    /// add a polygon, reversed if it turns the other way
+    fn push(&mut self, mut polygon: Vec<Point>) {
+        let area: f32 = polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+            .sum();
+        if area < 0_f32 {
+            polygon.reverse();
+        }
+        self.polygons.push(polygon);
+    }
+    fn disc(&mut self, center: Point) {
+        let step = 2_f32 * (1_f32 - self.tolerance / self.radius).max(-1_f32).acos();
+        let count = (2_f32 * PI / step).ceil().clamp(8_f32, 256_f32) as usize;
+        let disc = (0..count)
+            .map(|i| {
+                let (sin, cos) = (2_f32 * PI * i as f32 / count as f32).sin_cos();
+                (center.0 + self.radius * cos, center.1 + self.radius * sin)
+            })
+            .collect();
+        self.push(disc);
+    }
+    /// the normal of a direction, as long as half of the stroke width
+    fn normal(&self, (x, y): Point) -> Point {
+        (-y * self.radius, x * self.radius)
+    }
+    fn subpath(&mut self, subpath: &Subpath) {
+        let mut points: Vec<Point> = Vec::with_capacity(subpath.points.len() + 1);
+        for point in &subpath.points {
+            if points.last().is_none_or(|last| distance(*last, *point) > EPSILON) {
+                points.push(*point);
+            }
+        }
+        if subpath.closed && points.len() > 1 && distance(points[0], points[points.len() - 1]) <= EPSILON {
+            points.pop();
+        }
+        if points.len() == 1 {
+            // a subpath without length only draws its caps, aligned with the x axis
+            match self.stroke.linecap {
+                LineCap::Butt => {}
+                LineCap::Round => self.disc(points[0]),
+                LineCap::Square => {
+                    let (x, y, r) = (points[0].0, points[0].1, self.radius);
+                    self.push(vec![(x - r, y - r), (x + r, y - r), (x + r, y + r), (x - r, y + r)]);
+                }
+            }
+            return;
+        }
+        if subpath.closed {
+            points.push(points[0]);
+        }
+        let directions: Vec<Point> = points.windows(2).map(|pair| direction(pair[0], pair[1])).collect();
+        for (pair, d) in points.windows(2).zip(&directions) {
+            let n = self.normal(*d);
+            self.push(vec![
+                offset(pair[0], n, 1_f32),
+                offset(pair[1], n, 1_f32),
+                offset(pair[1], n, -1_f32),
+                offset(pair[0], n, -1_f32),
+            ]);
+        }
+        for (index, pair) in directions.windows(2).enumerate() {
+            self.join(points[index + 1], pair[0], pair[1]);
+        }
+        let (first, last) = (directions[0], directions[directions.len() - 1]);
+        if subpath.closed {
+            self.join(points[0], last, first);
+        } else {
+            self.cap(points[0], (-first.0, -first.1));
+            self.cap(points[points.len() - 1], last);
+        }
+    }
+    /// a cap at the end `point` of an open subpath, `out` points out of the path
+    fn cap(&mut self, point: Point, out: Point) {
+        match self.stroke.linecap {
+            LineCap::Butt => {}
+            LineCap::Round => self.disc(point),
+            LineCap::Square => {
+                let n = self.normal(out);
+                let end = offset(point, out, self.radius);
+                self.push(vec![
+                    offset(point, n, 1_f32),
+                    offset(end, n, 1_f32),
+                    offset(end, n, -1_f32),
+                    offset(point, n, -1_f32),
+                ]);
+            }
+        }
+    }
+    /// the join at `point` from the direction `before` into the direction `after`
+    fn join(&mut self, point: Point, before: Point, after: Point) {
+        let cross = before.0 * after.1 - before.1 * after.0;
+        let dot = before.0 * after.0 + before.1 * after.1;
+        if cross.abs() < EPSILON && dot > 0_f32 {
+            // no turn
+            return;
+        }
+        if self.stroke.linejoin == LineJoin::Round {
+            self.disc(point);
+            return;
+        }
+        // the outer side of the turn
+        let side = if cross > 0_f32 { -1_f32 } else { 1_f32 };
+        let (a, b) = (
+            offset(point, self.normal(before), side),
+            offset(point, self.normal(after), side),
+        );
+        let bevel = vec![point, a, b];
+        if self.stroke.linejoin == LineJoin::Bevel {
+            self.push(bevel);
+            return;
+        }
+        // the ratio of the miter length to the stroke width is 1 / cos(φ / 2), φ being the turn
+        let cos = ((1_f32 + dot) / 2_f32).max(0_f32).sqrt();
+        let (ta, tb) = (sub(a, point), sub(b, point));
+        let sum = (ta.0 + tb.0, ta.1 + tb.1);
+        let bisector = match sum.0.hypot(sum.1) < EPSILON {
+            // a half turn
+            true => before,
+            false => direction((0_f32, 0_f32), sum),
+        };
+        if cos > 0_f32 && 1_f32 / cos <= self.stroke.miterlimit {
+            let tip = offset(point, bisector, self.radius / cos);
+            self.push(vec![point, a, tip, b]);
+        } else if self.stroke.linejoin == LineJoin::Miter {
+            self.push(bevel);
+        } else {
+            // clipped at half of the miter limit times the stroke width from `point`
+            let clip = self.radius * self.stroke.miterlimit;
+            let along = |p: Point| {
+                let v = sub(p, point);
+                v.0 * bisector.0 + v.1 * bisector.1
+            };
+            let tip = offset(point, bisector, self.radius / cos.max(EPSILON));
+            let cut = |p: Point| {
+                let t = ((clip - along(p)) / (along(tip) - along(p))).clamp(0_f32, 1_f32);
+                offset(p, sub(tip, p), t)
+            };
+            if along(a) >= clip {
+                self.push(bevel);
+            } else {
+                self.push(vec![point, a, cut(a), cut(b), b]);
+            }
+        }
+    }
+}
+
+/// split the subpaths into dashes, `dashes` are the lengths of the dashes and the gaps,
+/// starting `dash_offset` into the pattern. each subpath starts the pattern again.
+///
+/// a pattern shorter than `tolerance` can not be seen and a subpath with more than
+/// [`MAX_DASHES`] dashes would take too long, they are drawn without dashes
+pub(crate) fn dash(subpaths: &[Subpath], dashes: &[f32], dash_offset: f32, tolerance: f32) -> Vec<Subpath> {
+    let mut pattern = dashes.to_vec();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_slice(dashes);
+    }
+    let total: f32 = pattern.iter().sum();
+    if pattern.is_empty()
+        || !(total >= tolerance && total.is_finite())
+        || pattern.iter().any(|d| *d < 0_f32 || !d.is_finite())
+    {
+        return subpaths.to_vec();
+    }
+    let mut start = dash_offset.rem_euclid(total);
+    let mut first = 0;
+    while start >= pattern[first] {
+        start -= pattern[first];
+        first = (first + 1) % pattern.len();
+    }
+
+    let mut result = vec![];
+    for subpath in subpaths {
+        match dash_subpath(subpath, &pattern, first, start) {
+            Some(dashes) => result.extend(dashes),
+            None => result.push(subpath.clone()),
+        }
+    }
+    result
+}
+
+/// the dashes of one subpath, `None` if there are more than [`MAX_DASHES`]
+fn dash_subpath(subpath: &Subpath, pattern: &[f32], first: usize, start: f32) -> Option<Vec<Subpath>> {
+    let mut points = subpath.points.clone();
+    if subpath.closed && !points.is_empty() {
+        points.push(points[0]);
+    }
+    let mut result = vec![];
+    let (mut index, mut left) = (first, pattern[first] - start);
+    let mut steps = 0;
+    let mut current = Subpath::default();
+    if index % 2 == 0 && !points.is_empty() {
+        current.points.push(points[0]);
+    }
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let length = distance(a, b);
+        let mut done = 0_f32;
+        while length - done > left {
+            // also ends the loop when `done += left` is too small to change `done`
+            steps += 1;
+            if steps > 2 * MAX_DASHES {
+                return None;
+            }
+            done += left;
+            let point = offset(a, sub(b, a), done / length);
+            if index % 2 == 0 {
+                current.points.push(point);
+                result.push(std::mem::take(&mut current));
+            } else {
+                current.points.push(point);
+            }
+            index = (index + 1) % pattern.len();
+            left = pattern[index];
+        }
+        left -= length - done;
+        if index % 2 == 0 {
+            current.points.push(b);
+        }
+    }
+    if !current.points.is_empty() {
+        result.push(current);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod test_stroke {
+    use super::*;
+
+    fn open(points: &[Point]) -> Subpath {
+        Subpath {
+            points: points.to_vec(),
+            closed: false,
+        }
+    }
+
+    fn area(polygon: &[Point]) -> f32 {
+        polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+            .sum::<f32>()
+            / 2.0
+    }
+
+    #[test]
+    fn test_stroke() {
+        let style = Stroke {
+            width: 2.0,
+            ..Default::default()
+        };
+        let polygons = stroke(&[open(&[(0.0, 0.0), (10.0, 0.0)])], &style, 0.1);
+        assert_eq!(polygons.len(), 1);
+        assert!((area(&polygons[0]) - 20.0).abs() < 1e-4);
+        // a right angle with a miter: two sides and a square join, all turning the same way
+        let polygons = stroke(&[open(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)])], &style, 0.1);
+        assert_eq!(polygons.len(), 3);
+        assert!(polygons.iter().all(|p| area(p) > 0.0));
+        assert!((area(&polygons[2]) - 1.0).abs() < 1e-4);
+        // a sharp turn beyond the miter limit is beveled
+        let polygons = stroke(&[open(&[(0.0, 0.0), (10.0, 0.0), (0.0, 1.0)])], &style, 0.1);
+        assert_eq!(polygons[2].len(), 3);
+        // square caps make the line one width longer, a lone point with round caps is a disc
+        let square = Stroke {
+            linecap: LineCap::Square,
+            ..style
+        };
+        let polygons = stroke(&[open(&[(0.0, 0.0), (10.0, 0.0)])], &square, 0.1);
+        assert!((polygons.iter().map(|p| area(p)).sum::<f32>() - 24.0).abs() < 1e-4);
+        let round = Stroke {
+            linecap: LineCap::Round,
+            ..style
+        };
+        let polygons = stroke(&[open(&[(5.0, 5.0), (5.0, 5.0)])], &round, 0.01);
+        assert!((area(&polygons[0]) - PI).abs() < 0.05);
+        assert!(stroke(&[open(&[(5.0, 5.0)])], &style, 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_dash() {
+        let line = open(&[(0.0, 0.0), (10.0, 0.0)]);
+        let dashes = dash(std::slice::from_ref(&line), &[3.0, 1.0], 0.0, 0.1);
+        let starts: Vec<Point> = dashes.iter().map(|d| d.points[0]).collect();
+        assert_eq!(starts, [(0.0, 0.0), (4.0, 0.0), (8.0, 0.0)]);
+        assert_eq!(dashes[2].points, [(8.0, 0.0), (10.0, 0.0)]);
+        // an odd list is repeated, the offset shifts the pattern
+        let dashes = dash(std::slice::from_ref(&line), &[2.0], 1.0, 0.1);
+        assert_eq!(dashes[0].points, [(0.0, 0.0), (1.0, 0.0)]);
+        assert_eq!(dashes[1].points, [(3.0, 0.0), (5.0, 0.0)]);
+        // dashes go around corners and along the closing line
+        let square = Subpath {
+            points: vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)],
+            closed: true,
+        };
+        let dashes = dash(&[square], &[3.0, 1.0], 0.0, 0.1);
+        assert_eq!(dashes[0].points, [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0)]);
+        assert_eq!(dashes[1].points, [(2.0, 2.0), (0.0, 2.0), (0.0, 1.0)]);
+        // an invalid pattern draws the whole line
+        assert_eq!(dash(std::slice::from_ref(&line), &[0.0, 0.0], 0.0, 0.1), [line]);
+        // a pattern finer than the tolerance, or too many dashes, draws the whole line
+        let long = open(&[(0.0, 0.0), (1000.0, 0.0)]);
+        assert_eq!(dash(std::slice::from_ref(&long), &[0.00001], 0.0, 0.1), std::slice::from_ref(&long));
+        assert_eq!(dash(std::slice::from_ref(&long), &[0.00001], 0.0, 0.0), std::slice::from_ref(&long));
+        let far = open(&[(0.0, 0.0), (1e30, 0.0)]);
+        assert_eq!(dash(std::slice::from_ref(&far), &[1.0], 0.0, 0.1), [far]);
+        assert_eq!(dash(&[long], &[1.0], 0.0, 0.1).len(), 500);
+    }
+}
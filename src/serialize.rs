@@ -0,0 +1,245 @@
+//! `Serialize` and `Deserialize` for the document model, with the `serde` feature.
+//!
+//! The shape is stable, it only grows with new optional fields and variants:
+//!
+//! - structs are objects with their field names (`view_box`, `stroke_width`...), every field is
+//!   written and missing fields of elements and [`CommonKVs`](crate::children::CommonKVs) take
+//!   their default value when they are read
+//! - enums are camelCase: keywords are strings (`"round"`, `"xMidYMid"`, `"percent"`), variants
+//!   with data are objects with one key (`{"color":{"r":255,"g":0,"b":0,"a":255}}`, `{"translate":[1,2]}`)
+//! - children are `{"circle":{...}}`, `{"path":{...}}`, `{"unknown":{"name":"title","attrs":[],"children":[]}}`,
+//!   `{"text":"..."}` and `{"comment":"..."}`
+//! - `Auto` values are `null` for `auto`, lengths are `{"value":24,"unit":"none"}`
+//! - path commands are objects tagged by their letter, `{"command":"L","x":1,"y":2,"relative":false}`,
+//!   closepath is `{"command":"Z","relative":false}`
+//! - path data is a list of commands, or a string in [`Compact`] form; both are read
+//! - the xml encoding is its name, `"UTF-8"`
+//!
+//! ## Example
+//! ```rust
+//! use svg_icon::serialize::Compact;
+//! use svg_icon::Svg;
+//!
+//! let svg: Svg = r#"<svg viewBox="0 0 24 24"><path d="M1 2 l3 4" stroke-width="2"/></svg>"#.parse().unwrap();
+//! let json = serde_json::to_string(&Compact(&svg)).unwrap();
+//! assert!(json.contains(r#""d":"M 1,2 l 3,4""#));
+//!
+//! let back: Svg = serde_json::from_str(&json).unwrap();
+//! assert_eq!(back.to_string(), svg.to_string());
+//! ```
+
+use std::cell::Cell;
+use std::fmt;
+
+use serde::de::{Error as _, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::children::{Command, CubicBezier, LineTo, PathData, QuadraticBezier, A, C, H, L, M, Q, S, T, V, Z};
+use crate::encode::EnCoding;
+
+thread_local! {
+    /// whether path data is written as a string, set while a [`Compact`] value is serialized
+    static COMPACT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Serialize a value with its path data as strings, `"M 0,0 L 1,1"`, instead of lists of commands.
+///
+/// Deserializing it is the same as deserializing the value, both forms of path data are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Compact<V>(pub V);
+
+/// restores the previous form when the serialization ends, even by a panic
+struct CompactGuard(bool);
+
+impl Drop for CompactGuard {
+    fn drop(&mut self) {
+        COMPACT.with(|compact| compact.set(self.0));
+    }
+}
+
+impl<V: Serialize> Serialize for Compact<V> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let _guard = CompactGuard(COMPACT.with(|compact| compact.replace(true)));
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for Compact<V> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        V::deserialize(deserializer).map(Compact)
+    }
+}
+
+/// the flat form of a [`Command`], tagged by its letter
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "command")]
+enum Tagged {
+    M(M),
+    L(L),
+    H(H),
+    V(V),
+    C(C),
+    S(S),
+    Q(Q),
+    T(T),
+    A(A),
+    Z { relative: bool },
+}
+
+impl From<&Command> for Tagged {
+    fn from(command: &Command) -> Self {
+        match *command {
+            Command::MoveTo(m) => Tagged::M(m),
+            Command::LineTo(LineTo::L(l)) => Tagged::L(l),
+            Command::LineTo(LineTo::H(h)) => Tagged::H(h),
+            Command::LineTo(LineTo::V(v)) => Tagged::V(v),
+            Command::CubicBezier(CubicBezier::C(c)) => Tagged::C(c),
+            Command::CubicBezier(CubicBezier::S(s)) => Tagged::S(s),
+            Command::QuadraticBezier(QuadraticBezier::Q(q)) => Tagged::Q(q),
+            Command::QuadraticBezier(QuadraticBezier::T(t)) => Tagged::T(t),
+            Command::EllipticalArc(a) => Tagged::A(a),
+            Command::ClosePath(Z(relative)) => Tagged::Z { relative },
+        }
+    }
+}
+
+impl From<Tagged> for Command {
+    fn from(tagged: Tagged) -> Self {
+        match tagged {
+            Tagged::M(m) => m.into(),
+            Tagged::L(l) => l.into(),
+            Tagged::H(h) => h.into(),
+            Tagged::V(v) => v.into(),
+            Tagged::C(c) => c.into(),
+            Tagged::S(s) => s.into(),
+            Tagged::Q(q) => q.into(),
+            Tagged::T(t) => t.into(),
+            Tagged::A(a) => a.into(),
+            Tagged::Z { relative } => Z(relative).into(),
+        }
+    }
+}
+
+impl Serialize for Command {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        Tagged::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Command {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        Tagged::deserialize(deserializer).map(Command::from)
+    }
+}
+
+impl Serialize for PathData {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        match COMPACT.with(Cell::get) {
+            true => serializer.collect_str(self),
+            false => serializer.collect_seq(self.iter()),
+        }
+    }
+}
+
+struct PathDataVisitor;
+
+impl<'de> Visitor<'de> for PathDataVisitor {
+    type Value = PathData;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of path commands or a path data string")
+    }
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<PathData, E> {
+        match PathData::from_str(s) {
+            Ok(("", d)) => Ok(d),
+            _ => Err(E::custom(format!("invalid path data `{}`", s))),
+        }
+    }
+    fn visit_seq<Seq: SeqAccess<'de>>(self, mut seq: Seq) -> Result<PathData, Seq::Error> {
+        let mut d = PathData::new();
+        while let Some(command) = seq.next_element::<Command>()? {
+            d.push(command);
+        }
+        Ok(d)
+    }
+}
+
+impl<'de> Deserialize<'de> for PathData {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        deserializer.deserialize_any(PathDataVisitor)
+    }
+}
+
+impl Serialize for EnCoding {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for EnCoding {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        String::deserialize(deserializer)?.parse().map_err(De::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test_serialize {
+    use serde_json::json;
+
+    use super::*;
+    use crate::children::{Child, Path};
+    use crate::Svg;
+
+    const ICON: &str = r##"<?xml version="1.0" encoding="UTF-8"?><svg width="24" height="auto" viewBox="0 0 48 48" preserveAspectRatio="xMinYMax slice" fill="none" xmlns="http://www.w3.org/2000/svg"><title>icon</title><!-- a comment --><g transform="translate(1 2) rotate(45 24 24)" stroke="url(#a) red" stroke-dasharray="1 2%" stroke-opacity="0.5"><path d="M24 19V4 h1 C0 0 1 1 2 2 s1 1 2 2 Q1 1 2 2 t3 3 a1 2 30 1 0 2 0Z" stroke-linejoin="miter-clip" id="p"/><circle cx="1" cy="2" r="3em"/><ellipse rx="auto" ry="2"/><rect x="1" width="2" height="3" rx="1"/><line x2="4" stroke-linecap="round" stroke-miterlimit="8"/><polygon points="0,0 1,0 1,1"/><polyline points="0,0 1,1" fill="currentColor"/></g></svg>"##;
+
+    #[test]
+    fn test_round_trip() {
+        let svg: Svg = ICON.parse().unwrap();
+        for json in [
+            serde_json::to_string(&svg).unwrap(),
+            serde_json::to_string(&Compact(&svg)).unwrap(),
+        ] {
+            let back: Svg = serde_json::from_str(&json).unwrap();
+            assert_eq!(back.to_string(), svg.to_string());
+            assert_eq!(serde_json::to_string(&back).unwrap(), serde_json::to_string(&svg).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_shape() {
+        let svg: Svg = r##"<svg width="24" viewBox="0 0 24 24"><path d="M1 2 z" stroke="#f00"/></svg>"##.parse().unwrap();
+        let value = serde_json::to_value(&svg).unwrap();
+        assert_eq!(value["width"], json!({"value": 24.0, "unit": "none"}));
+        assert_eq!(value["height"], json!(null));
+        assert_eq!(value["view_box"], json!({"min_x": 0.0, "min_y": 0.0, "width": 24.0, "height": 24.0}));
+        let path = &value["children"][0]["path"];
+        assert_eq!(
+            path["d"],
+            json!([{"command": "M", "x": 1.0, "y": 2.0, "relative": false}, {"command": "Z", "relative": true}])
+        );
+        assert_eq!(path["common"]["stroke"], json!({"color": {"r": 255, "g": 0, "b": 0, "a": 255}}));
+        let compact = serde_json::to_value(Compact(&svg)).unwrap();
+        assert_eq!(compact["children"][0]["path"]["d"], json!("M 1,2 z"));
+        // the form is only changed inside `Compact`
+        assert!(serde_json::to_value(&svg).unwrap()["children"][0]["path"]["d"].is_array());
+    }
+
+    #[test]
+    fn test_partial() {
+        let json = json!({
+            "xmlns": "http://www.w3.org/2000/svg",
+            "children": [
+                {"path": {"d": "M0 0 L1 1", "common": {"fill": "currentColor", "stroke_linecap": "round"}}},
+                {"text": "hi"}
+            ]
+        });
+        let svg: Svg = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            svg.to_string(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><path d="M 0,0 L 1,1" fill="currentColor" stroke-linecap="round"/>hi</svg>"#
+        );
+        assert!(matches!(&svg.children[1], Child::Text(text) if text == "hi"));
+        assert!(serde_json::from_value::<Path>(json!({"d": "M0 0 X"})).is_err());
+        assert!(serde_json::from_value::<Path>(json!({"d": [{"command": "X"}]})).is_err());
+    }
+}
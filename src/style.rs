@@ -0,0 +1,544 @@
+//! A small css parser for `style` attributes and `<style>` elements.
+//!
+//! Only what icons need is supported: declarations, rules with type, class, id and universal
+//! selectors (also compound like `path.cls-1`), selector lists and `!important`.
+//! Rules with other selectors and at-rules are skipped.
+
+use std::fmt::Display;
+
+use crate::children::{Child, CommonKVs};
+use crate::Svg;
+
+/// `name: value` in a rule or a `style` attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub name: String,
+    pub value: String,
+    pub important: bool,
+}
+
+impl Display for Declaration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.name, self.value)?;
+        if self.important {
+            write!(f, " !important")?;
+        }
+        Ok(())
+    }
+}
+
+/// parse the declarations of a `style` attribute or a rule, broken ones are ignored like css does
+pub fn parse_declarations(s: &str) -> Vec<Declaration> {
+    s.split(';')
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim();
+            let (value, important) = match value.rsplit_once('!') {
+                Some((value, important)) if important.trim().eq_ignore_ascii_case("important") => {
+                    (value.trim(), true)
+                }
+                _ => (value, false),
+            };
+            if name.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some(Declaration {
+                name,
+                value: value.to_string(),
+                important,
+            })
+        })
+        .collect()
+}
+
+/// write declarations back as a `style` attribute
+pub fn write_declarations(declarations: &[Declaration]) -> String {
+    declarations
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// A compound selector: `*`, `path`, `.cls-1`, `#id`, `path.a.b#id`...
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selector {
+    /// `None` for `*` or no type at all
+    pub element: Option<String>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+}
+
+impl Selector {
+    /// `None` if the selector uses anything which is not supported (combinators, pseudo classes...)
+    pub fn parse(s: &str) -> Option<Selector> {
+        let s = s.trim();
+        let is_name = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+        let mut selector = Selector::default();
+        let mut rest = s.strip_prefix('*').unwrap_or(s);
+        let end = rest.find(|c| !is_name(c)).unwrap_or(rest.len());
+        if end > 0 {
+            if s.starts_with('*') {
+                return None;
+            }
+            selector.element = Some(rest[..end].to_string());
+            rest = &rest[end..];
+        }
+        while let Some(c) = rest.chars().next() {
+            if c != '.' && c != '#' {
+                return None;
+            }
+            let name = &rest[1..];
+            let end = name.find(|c| !is_name(c)).unwrap_or(name.len());
+            if end == 0 {
+                return None;
+            }
+            match c {
+                '.' => selector.classes.push(name[..end].to_string()),
+                _ => selector.id = Some(name[..end].to_string()),
+            }
+            rest = &name[end..];
+        }
+        if s.is_empty() {
+            return None;
+        }
+        Some(selector)
+    }
+    /// `(ids, classes, types)`
+    pub fn specificity(&self) -> (usize, usize, usize) {
+        (
+            self.id.iter().count(),
+            self.classes.len(),
+            self.element.iter().count(),
+        )
+    }
+    pub fn matches(&self, element: &str, id: Option<&str>, classes: &[&str]) -> bool {
+        self.element.as_ref().is_none_or(|e| e == element)
+            && self.id.as_ref().is_none_or(|i| Some(i.as_str()) == id)
+            && self.classes.iter().all(|c| classes.contains(&c.as_str()))
+    }
+}
+
+/// One selector of a rule and its declarations, a selector list is split into several rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub selector: Selector,
+    pub declarations: Vec<Declaration>,
+}
+
+/// The rules of all `<style>` elements, in source order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleSheet {
+    pub rules: Vec<Rule>,
+    /// whether rules or at-rules were skipped, the stylesheet can not be dropped after inlining then
+    pub skipped: bool,
+}
+
+impl StyleSheet {
+    pub fn parse(css: &str) -> StyleSheet {
+        let mut sheet = StyleSheet::default();
+        sheet.extend(css);
+        sheet
+    }
+    /// add the rules of another `<style>` element
+    pub fn extend(&mut self, css: &str) {
+        let css = strip_comments(css);
+        let mut rest = css.as_str();
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            let Some(open) = rest.find(['{', ';']) else {
+                self.skipped = true;
+                break;
+            };
+            let prelude = rest[..open].trim();
+            if rest[open..].starts_with(';') {
+                // `@import ...;` or garbage
+                self.skipped = true;
+                rest = &rest[open + 1..];
+                continue;
+            }
+            let close = block_end(rest, open);
+            let body = &rest[open + 1..close.min(rest.len())];
+            rest = rest.get(close + 1..).unwrap_or_default();
+            if prelude.starts_with('@') {
+                self.skipped = true;
+                continue;
+            }
+            let declarations = parse_declarations(body);
+            for selector in prelude.split(',') {
+                match Selector::parse(selector) {
+                    Some(selector) => self.rules.push(Rule {
+                        selector,
+                        declarations: declarations.clone(),
+                    }),
+                    None => self.skipped = true,
+                }
+            }
+        }
+    }
+    /// declarations which apply to an element in cascade order, the later one wins
+    pub fn cascade(
+        &self,
+        element: &str,
+        id: Option<&str>,
+        classes: &[&str],
+        style: &[Declaration],
+    ) -> Vec<Declaration> {
+        let mut matched = vec![];
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.selector.matches(element, id, classes) {
+                for declaration in &rule.declarations {
+                    matched.push((
+                        declaration.important,
+                        false,
+                        rule.selector.specificity(),
+                        index,
+                        declaration,
+                    ));
+                }
+            }
+        }
+        // a `style` attribute wins over every rule with the same importance
+        for declaration in style {
+            matched.push((declaration.important, true, (0, 0, 0), 0, declaration));
+        }
+        matched.sort_by_key(|(important, inline, specificity, index, _)| {
+            (*important, *inline, *specificity, *index)
+        });
+        matched.into_iter().map(|(.., d)| d.clone()).collect()
+    }
+}
+
+/// the index of the `}` which closes the block opened at `open`, or the end of `s`
+fn block_end(s: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (index, c) in s[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + index;
+                }
+            }
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+fn strip_comments(css: &str) -> String {
+    let css = css.replace("<![CDATA[", "").replace("]]>", "");
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css.as_str();
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+/// properties which can also be written as an attribute, only these are applied to elements
+pub const PRESENTATION_ATTRIBUTES: [&str; 55] = [
+    "alignment-baseline",
+    "baseline-shift",
+    "clip",
+    "clip-path",
+    "clip-rule",
+    "color",
+    "color-interpolation",
+    "color-interpolation-filters",
+    "color-profile",
+    "color-rendering",
+    "cursor",
+    "direction",
+    "display",
+    "dominant-baseline",
+    "enable-background",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "filter",
+    "flood-color",
+    "flood-opacity",
+    "font-family",
+    "font-size",
+    "font-size-adjust",
+    "font-stretch",
+    "font-style",
+    "font-variant",
+    "font-weight",
+    "image-rendering",
+    "kerning",
+    "letter-spacing",
+    "lighting-color",
+    "marker-end",
+    "marker-mid",
+    "marker-start",
+    "mask",
+    "opacity",
+    "overflow",
+    "pointer-events",
+    "shape-rendering",
+    "stop-color",
+    "stop-opacity",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "text-anchor",
+    "text-decoration",
+    "text-rendering",
+    "visibility",
+    "word-spacing",
+];
+
+/// the attributes of an element which the cascade reads and writes
+enum Attributes<'a> {
+    Common(&'a mut CommonKVs),
+    Raw(&'a mut Vec<(String, String)>),
+}
+
+impl Attributes<'_> {
+    fn get(&self, name: &str) -> Option<&str> {
+        match self {
+            Attributes::Common(common) => common.other(name),
+            Attributes::Raw(attrs) => attrs
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str()),
+        }
+    }
+    /// `false` if the value is invalid, the declaration is ignored then
+    fn set(&mut self, name: &str, value: &str) -> bool {
+        match self {
+            Attributes::Common(common) => common.set(name, value).is_ok(),
+            Attributes::Raw(attrs) => {
+                match attrs.iter_mut().find(|(k, _)| k == name) {
+                    Some((_, v)) => *v = value.to_string(),
+                    None => attrs.push((name.to_string(), value.to_string())),
+                }
+                true
+            }
+        }
+    }
+    fn remove(&mut self, name: &str) {
+        match self {
+            Attributes::Common(common) => common.remove_others(&[name]),
+            Attributes::Raw(attrs) => attrs.retain(|(k, _)| k != name),
+        }
+    }
+}
+
+/// resolve the stylesheet and the `style` attribute of one element into its attributes
+fn apply(sheet: &StyleSheet, element: &str, mut attributes: Attributes<'_>, inline: bool) {
+    let style = attributes.get("style").map(parse_declarations).unwrap_or_default();
+    let id = attributes.get("id").map(str::to_string);
+    let classes = attributes.get("class").unwrap_or_default().to_string();
+    let classes = classes.split_whitespace().collect::<Vec<_>>();
+
+    let cascade = sheet.cascade(element, id.as_deref(), &classes, &style);
+    let mut rest = vec![];
+    for (index, declaration) in cascade.iter().enumerate() {
+        let presentation = PRESENTATION_ATTRIBUTES.contains(&declaration.name.as_str());
+        if presentation && attributes.set(&declaration.name, &declaration.value) {
+            continue;
+        }
+        // a later declaration of the same property wins over this one
+        if !cascade[index + 1..].iter().any(|d| d.name == declaration.name) {
+            rest.push(declaration.clone());
+        }
+    }
+    if inline {
+        // what can not be an attribute, from `style` or from the stylesheet, is kept in `style`
+        match rest.is_empty() {
+            true => attributes.remove("style"),
+            false => {
+                attributes.set("style", &write_declarations(&rest));
+            }
+        }
+    }
+}
+
+fn apply_children(sheet: &StyleSheet, children: &mut Vec<Child>, inline: bool) {
+    for child in children.iter_mut() {
+        let name = child.name().to_string();
+        match child {
+            Child::Unknown {
+                attrs, children, ..
+            } => {
+                apply(sheet, &name, Attributes::Raw(attrs), inline);
+                apply_children(sheet, children, inline);
+            }
+            Child::Group(group) => {
+                apply(sheet, &name, Attributes::Common(&mut group.common), inline);
+                apply_children(sheet, &mut group.children, inline);
+            }
+            Child::Text(_) | Child::Comment(_) => {}
+            child => {
+                if let Some(common) = child.common_mut() {
+                    apply(sheet, &name, Attributes::Common(common), inline);
+                }
+            }
+        }
+    }
+    if inline && !sheet.skipped {
+        children.retain(|child| child.name() != "style");
+    }
+}
+
+/// collect the css of all `<style>` elements
+fn collect(children: &[Child], sheet: &mut StyleSheet) {
+    for child in children {
+        match child {
+            Child::Unknown { name, children, .. } if name == "style" => {
+                for child in children {
+                    if let Child::Text(css) = child {
+                        sheet.extend(css);
+                    }
+                }
+            }
+            Child::Unknown { children, .. } => collect(children, sheet),
+            Child::Group(group) => collect(&group.children, sheet),
+            _ => {}
+        }
+    }
+}
+
+impl Svg {
+    /// the rules of all `<style>` elements of the document
+    pub fn style_sheet(&self) -> StyleSheet {
+        let mut sheet = StyleSheet::default();
+        collect(&self.children, &mut sheet);
+        sheet
+    }
+    /// resolve `<style>` rules and `style` attributes into the attributes of every element,
+    /// css declarations win over presentation attributes and invalid values are ignored.
+    ///
+    /// with `inline` the applied declarations are removed from `style` attributes and the
+    /// `<style>` elements are removed (if all of their rules could be applied),
+    /// the declarations which are not presentation attributes or whose value can not be read
+    /// are kept in the `style` attribute of the elements they apply to.
+    pub fn apply_styles(&mut self, inline: bool) {
+        let sheet = self.style_sheet();
+        apply(&sheet, "svg", Attributes::Common(&mut self.common), inline);
+        apply_children(&sheet, &mut self.children, inline);
+    }
+}
+
+#[cfg(test)]
+mod test_style {
+    use super::*;
+
+    #[test]
+    fn test_declarations() {
+        let declarations = parse_declarations(" fill: #000 ; STROKE-width:2 !important;;broken; :x");
+        assert_eq!(
+            declarations,
+            vec![
+                Declaration {
+                    name: "fill".to_string(),
+                    value: "#000".to_string(),
+                    important: false,
+                },
+                Declaration {
+                    name: "stroke-width".to_string(),
+                    value: "2".to_string(),
+                    important: true,
+                },
+            ]
+        );
+        assert_eq!(write_declarations(&declarations), "fill:#000;stroke-width:2 !important");
+    }
+
+    #[test]
+    fn test_selectors() {
+        let selector = Selector::parse("path.a.b#c").unwrap();
+        assert_eq!(selector.element.as_deref(), Some("path"));
+        assert_eq!(selector.classes, vec!["a", "b"]);
+        assert_eq!(selector.id.as_deref(), Some("c"));
+        assert_eq!(selector.specificity(), (1, 2, 1));
+        assert!(selector.matches("path", Some("c"), &["b", "a", "d"]));
+        assert!(!selector.matches("path", Some("c"), &["a"]));
+        assert!(!selector.matches("rect", Some("c"), &["a", "b"]));
+
+        assert_eq!(Selector::parse("*").unwrap(), Selector::default());
+        assert!(Selector::parse("*.cls-1").unwrap().matches("g", None, &["cls-1"]));
+        for unsupported in ["", "a→b", "g path", "g > path", "a:hover", "[fill]", ".", "*path"] {
+            assert_eq!(Selector::parse(unsupported), None, "{:?}", unsupported);
+        }
+    }
+
+    #[test]
+    fn test_style_sheet() {
+        let sheet = StyleSheet::parse(
+            "<![CDATA[ /* colors */ .cls-1, #a { fill: #333 } @media print { .cls-1 { fill: red } } path{stroke:red} g path {fill:blue} ]]>",
+        );
+        assert_eq!(sheet.rules.len(), 3);
+        assert!(sheet.skipped);
+        let cascade = sheet.cascade(
+            "path",
+            Some("a"),
+            &["cls-1"],
+            &parse_declarations("stroke: blue"),
+        );
+        let values = cascade
+            .iter()
+            .map(|d| format!("{}", d))
+            .collect::<Vec<_>>();
+        // type < class < id < style attribute
+        assert_eq!(values, vec!["stroke:red", "fill:#333", "fill:#333", "stroke:blue"]);
+
+        let sheet = StyleSheet::parse("#a { fill: red !important } .b { fill: blue }");
+        assert!(!sheet.skipped);
+        let cascade = sheet.cascade("path", Some("a"), &["b"], &parse_declarations("fill: green"));
+        assert_eq!(cascade.last().unwrap().value, "red");
+    }
+
+    #[test]
+    fn test_apply_styles() {
+        use crate::paint::{Color, Paint};
+
+        let source = r##"<svg viewBox="0 0 24 24"><style>.cls-1{fill:#333;stroke-width:2}path{stroke:red}</style><path class="cls-1" fill="#fff" style="stroke-width:3;transition:fill 1s" d="M 0,0"/><circle style="fill:nope" r="1"/><linearGradient><stop style="stop-color:red"/></linearGradient></svg>"##;
+        let mut svg: Svg = source.parse().unwrap();
+        let mut kept = svg.clone();
+        kept.apply_styles(false);
+        let Child::Path(path) = &kept.children[1] else {
+            panic!("expect a path");
+        };
+        assert_eq!(path.common.fill, Some(Paint::Color(Color::rgb(0x33, 0x33, 0x33))));
+        assert_eq!(path.common.stroke, Some(Paint::Color(Color::rgb(255, 0, 0))));
+        assert_eq!(path.common.stroke_width, Some(3.0.into()));
+        assert_eq!(path.common.other("style"), Some("stroke-width:3;transition:fill 1s"));
+        assert_eq!(kept.children.len(), 4);
+
+        svg.apply_styles(true);
+        assert_eq!(
+            svg.to_string(),
+            r##"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"><path class="cls-1" fill="#333333" style="transition:fill 1s" d="M 0,0" stroke="#ff0000" stroke-width="3"/><circle style="fill:nope" r="1"/><linearGradient><stop stop-color="red"/></linearGradient></svg>"##
+        );
+
+        // declarations of the removed stylesheet which are not attributes move to `style`
+        let mut svg: Svg = r##"<svg><style>path{mix-blend-mode:multiply;stroke:nope}.a{fill:#000;fill:var(--c)}</style><path class="a" d="M 0,0"/><circle style="opacity:.5;isolation:isolate" r="1"/></svg>"##.parse().unwrap();
+        svg.apply_styles(true);
+        assert_eq!(
+            svg.to_string(),
+            r##"<svg><path class="a" d="M 0,0" fill="#000000" style="mix-blend-mode:multiply;stroke:nope;fill:var(--c)"/><circle style="isolation:isolate" r="1" opacity=".5"/></svg>"##
+        );
+        let kept = svg.to_string();
+        svg.apply_styles(true);
+        assert_eq!(svg.to_string(), kept);
+    }
+}
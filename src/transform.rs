@@ -0,0 +1,601 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, multispace0},
+    combinator::opt,
+    multi::many0,
+    sequence::{delimited, pair, preceded, terminated},
+};
+
+use crate::{
+    children::{
+        Command, CubicBezier, LineTo, PathData, QuadraticBezier, A, C, H, L, M, Q, S, T, V,
+    },
+    error::{Error, IResult},
+    parser::{comma_wsp, number, sep},
+};
+
+/// A 2D affine matrix `[a c e; b d f; 0 0 1]`, like `matrix(a b c d e f)`.
+///
+/// A point is mapped to `(a * x + c * y + e, b * x + d * y + f)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Matrix::IDENTITY
+    }
+}
+
+impl Matrix {
+    pub const IDENTITY: Matrix = Matrix::new(1_f32, 0_f32, 0_f32, 1_f32, 0_f32, 0_f32);
+
+    pub const fn new(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Self {
+        Matrix { a, b, c, d, e, f }
+    }
+    pub fn translate(tx: f32, ty: f32) -> Self {
+        Matrix::new(1_f32, 0_f32, 0_f32, 1_f32, tx, ty)
+    }
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Matrix::new(sx, 0_f32, 0_f32, sy, 0_f32, 0_f32)
+    }
+    /// rotate `angle` degrees around the origin
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Matrix::new(cos, sin, -sin, cos, 0_f32, 0_f32)
+    }
+    /// rotate `angle` degrees around `(cx, cy)`
+    pub fn rotate_around(angle: f32, cx: f32, cy: f32) -> Self {
+        Matrix::translate(cx, cy)
+            .multiply(&Matrix::rotate(angle))
+            .multiply(&Matrix::translate(-cx, -cy))
+    }
+    pub fn skew_x(angle: f32) -> Self {
+        Matrix::new(1_f32, 0_f32, angle.to_radians().tan(), 1_f32, 0_f32, 0_f32)
+    }
+    pub fn skew_y(angle: f32) -> Self {
+        Matrix::new(1_f32, angle.to_radians().tan(), 0_f32, 1_f32, 0_f32, 0_f32)
+    }
+    /// `self × other`: the result applies `other` first, then `self`
+    pub fn multiply(&self, other: &Matrix) -> Matrix {
+        Matrix::new(
+            self.a * other.a + self.c * other.b,
+            self.b * other.a + self.d * other.b,
+            self.a * other.c + self.c * other.d,
+            self.b * other.c + self.d * other.d,
+            self.a * other.e + self.c * other.f + self.e,
+            self.b * other.e + self.d * other.f + self.f,
+        )
+    }
+    pub fn determinant(&self) -> f32 {
+        self.a * self.d - self.b * self.c
+    }
+    /// `None` if the matrix can not be inverted, e.g. `scale(0)`
+    pub fn inverse(&self) -> Option<Matrix> {
+        let det = self.determinant();
+        if det == 0_f32 || !det.is_finite() {
+            return None;
+        }
+        Some(Matrix::new(
+            self.d / det,
+            -self.b / det,
+            -self.c / det,
+            self.a / det,
+            (self.c * self.f - self.d * self.e) / det,
+            (self.b * self.e - self.a * self.f) / det,
+        ))
+    }
+    pub fn is_identity(&self) -> bool {
+        *self == Matrix::IDENTITY
+    }
+    /// whether shapes keep their form: only a rotation, a uniform scale, a translation and mirroring
+    pub fn is_similarity(&self) -> bool {
+        (self.a == self.d && self.b == -self.c) || (self.a == -self.d && self.b == self.c)
+    }
+    /// how much lengths are scaled, exact for a similarity, the mean of both axes else
+    pub fn scale_factor(&self) -> f32 {
+        self.determinant().abs().sqrt()
+    }
+    /// whether horizontal and vertical lines stay horizontal and vertical
+    pub fn is_axis_aligned(&self) -> bool {
+        self.b == 0_f32 && self.c == 0_f32
+    }
+    /// map a point
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+    /// map a vector (a relative coordinate), the translation is not applied
+    pub fn apply_vector(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.a * x + self.c * y, self.b * x + self.d * y)
+    }
+    /// map the ellipse of an arc, returns `(rx, ry, angle)`
+    fn apply_ellipse(&self, rx: f32, ry: f32, angle: f32) -> (f32, f32, f32) {
+        let rotation = self.b.atan2(self.a).to_degrees();
+        let scale = self.scale_factor();
+        // rotations and uniform scales keep the shape, only the angle changes
+        if self.a == self.d && self.b == -self.c {
+            return (rx * scale, ry * scale, angle + rotation);
+        }
+        if self.a == -self.d && self.b == self.c {
+            return (rx * scale, ry * scale, rotation - angle);
+        }
+        // the columns of `self × rotate(angle) × scale(rx, ry)` are the axes of the new ellipse,
+        // its radii are the singular values of that matrix
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let (k11, k21) = self.apply_vector(rx * cos, rx * sin);
+        let (k12, k22) = self.apply_vector(-ry * sin, ry * cos);
+        let p = k11 * k11 + k12 * k12;
+        let q = k11 * k21 + k12 * k22;
+        let r = k21 * k21 + k22 * k22;
+        let mean = (p + r) / 2_f32;
+        let delta = (((p - r) / 2_f32).powi(2) + q * q).sqrt();
+        let major = (mean + delta).max(0_f32).sqrt();
+        let minor = (mean - delta).max(0_f32).sqrt();
+        let angle = if q != 0_f32 {
+            (mean + delta - p).atan2(q).to_degrees()
+        } else if p >= r {
+            0_f32
+        } else {
+            90_f32
+        };
+        (major, minor, angle)
+    }
+    /// map every command of a path, relative commands stay relative.
+    ///
+    /// `H` and `V` become `L` unless the matrix keeps the axes,
+    /// arc radii and rotation follow the transformed ellipse and the sweep flips when mirrored
+    pub fn apply_commands(&self, commands: &[Command]) -> Vec<Command> {
+        let mut current = (0_f32, 0_f32);
+        let mut start = (0_f32, 0_f32);
+        let point = |x: f32, y: f32, relative: bool| match relative {
+            true => self.apply_vector(x, y),
+            false => self.apply(x, y),
+        };
+        // the end point in source coordinates
+        let end = |current: (f32, f32), x: f32, y: f32, relative: bool| match relative {
+            true => (current.0 + x, current.1 + y),
+            false => (x, y),
+        };
+
+        let mut result = Vec::with_capacity(commands.len());
+        for command in commands {
+            let mapped: Command = match command {
+                Command::MoveTo(m) => {
+                    current = end(current, m.x, m.y, m.relative);
+                    start = current;
+                    // a first `m` is from the origin, which the translation moves too
+                    let (x, y) = match result.is_empty() {
+                        true => self.apply(m.x, m.y),
+                        false => point(m.x, m.y, m.relative),
+                    };
+                    M { x, y, ..*m }.into()
+                }
+                Command::LineTo(LineTo::L(l)) => {
+                    current = end(current, l.x, l.y, l.relative);
+                    let (x, y) = point(l.x, l.y, l.relative);
+                    L { x, y, ..*l }.into()
+                }
+                Command::LineTo(LineTo::H(h)) => {
+                    let (x, y) = match h.relative {
+                        true => (h.x, 0_f32),
+                        false => (h.x, current.1),
+                    };
+                    current = end(current, x, y, h.relative);
+                    let (x, y) = point(x, y, h.relative);
+                    match self.is_axis_aligned() {
+                        true => H { x, ..*h }.into(),
+                        false => L {
+                            x,
+                            y,
+                            relative: h.relative,
+                        }
+                        .into(),
+                    }
+                }
+                Command::LineTo(LineTo::V(v)) => {
+                    let (x, y) = match v.relative {
+                        true => (0_f32, v.y),
+                        false => (current.0, v.y),
+                    };
+                    current = end(current, x, y, v.relative);
+                    let (x, y) = point(x, y, v.relative);
+                    match self.is_axis_aligned() {
+                        true => V { y, ..*v }.into(),
+                        false => L {
+                            x,
+                            y,
+                            relative: v.relative,
+                        }
+                        .into(),
+                    }
+                }
+                Command::CubicBezier(CubicBezier::C(c)) => {
+                    current = end(current, c.x, c.y, c.relative);
+                    let (x1, y1) = point(c.x1, c.y1, c.relative);
+                    let (x2, y2) = point(c.x2, c.y2, c.relative);
+                    let (x, y) = point(c.x, c.y, c.relative);
+                    C {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        x,
+                        y,
+                        relative: c.relative,
+                    }
+                    .into()
+                }
+                Command::CubicBezier(CubicBezier::S(s)) => {
+                    current = end(current, s.x, s.y, s.relative);
+                    let (x2, y2) = point(s.x2, s.y2, s.relative);
+                    let (x, y) = point(s.x, s.y, s.relative);
+                    S {
+                        x2,
+                        y2,
+                        x,
+                        y,
+                        relative: s.relative,
+                    }
+                    .into()
+                }
+                Command::QuadraticBezier(QuadraticBezier::Q(q)) => {
+                    current = end(current, q.x, q.y, q.relative);
+                    let (x1, y1) = point(q.x1, q.y1, q.relative);
+                    let (x, y) = point(q.x, q.y, q.relative);
+                    Q {
+                        x1,
+                        y1,
+                        x,
+                        y,
+                        relative: q.relative,
+                    }
+                    .into()
+                }
+                Command::QuadraticBezier(QuadraticBezier::T(t)) => {
+                    current = end(current, t.x, t.y, t.relative);
+                    let (x, y) = point(t.x, t.y, t.relative);
+                    T { x, y, ..*t }.into()
+                }
+                Command::EllipticalArc(a) => {
+                    current = end(current, a.x, a.y, a.relative);
+                    let (rx, ry, angle) = self.apply_ellipse(a.rx, a.ry, a.angle);
+                    let (x, y) = point(a.x, a.y, a.relative);
+                    A {
+                        rx,
+                        ry,
+                        angle,
+                        // a mirrored arc turns the other way
+                        sweep_flag: a.sweep_flag != (self.determinant() < 0_f32),
+                        x,
+                        y,
+                        ..*a
+                    }
+                    .into()
+                }
+                Command::ClosePath(z) => {
+                    current = start;
+                    Command::ClosePath(*z)
+                }
+            };
+            result.push(mapped);
+        }
+        result
+    }
+}
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "matrix({} {} {} {} {} {})",
+            self.a, self.b, self.c, self.d, self.e, self.f
+        )
+    }
+}
+
+/// One function of a `transform` list, angles are in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum TransformFunction {
+    Matrix(Matrix),
+    Translate(f32, f32),
+    Scale(f32, f32),
+    Rotate { angle: f32, cx: f32, cy: f32 },
+    SkewX(f32),
+    SkewY(f32),
+}
+
+impl TransformFunction {
+    pub fn matrix(&self) -> Matrix {
+        match *self {
+            TransformFunction::Matrix(matrix) => matrix,
+            TransformFunction::Translate(tx, ty) => Matrix::translate(tx, ty),
+            TransformFunction::Scale(sx, sy) => Matrix::scale(sx, sy),
+            TransformFunction::Rotate { angle, cx, cy } => Matrix::rotate_around(angle, cx, cy),
+            TransformFunction::SkewX(angle) => Matrix::skew_x(angle),
+            TransformFunction::SkewY(angle) => Matrix::skew_y(angle),
+        }
+    }
+    /// `name(args)`, the arguments may be separated by whitespace and/or commas
+    fn parser(s: &str) -> IResult<&str, TransformFunction> {
+        let (s, name) = alt((
+            tag("matrix"),
+            tag("translate"),
+            tag("scale"),
+            tag("rotate"),
+            tag("skewX"),
+            tag("skewY"),
+        ))(s)?;
+        let (rest, (first, mut args)) = delimited(
+            terminated(preceded(multispace0, char('(')), multispace0),
+            pair(opt(number), many0(preceded(sep, number))),
+            preceded(multispace0, char(')')),
+        )(s)?;
+        args.splice(0..0, first);
+        let function = match (name, &args[..]) {
+            ("matrix", &[a, b, c, d, e, f]) => {
+                TransformFunction::Matrix(Matrix::new(a, b, c, d, e, f))
+            }
+            ("translate", &[tx]) => TransformFunction::Translate(tx, 0_f32),
+            ("translate", &[tx, ty]) => TransformFunction::Translate(tx, ty),
+            ("scale", &[s]) => TransformFunction::Scale(s, s),
+            ("scale", &[sx, sy]) => TransformFunction::Scale(sx, sy),
+            ("rotate", &[angle]) => TransformFunction::Rotate {
+                angle,
+                cx: 0_f32,
+                cy: 0_f32,
+            },
+            ("rotate", &[angle, cx, cy]) => TransformFunction::Rotate { angle, cx, cy },
+            ("skewX", &[angle]) => TransformFunction::SkewX(angle),
+            ("skewY", &[angle]) => TransformFunction::SkewY(angle),
+            // a wrong number of arguments
+            _ => {
+                return Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                    s,
+                    nom::error::ErrorKind::Count,
+                )))
+            }
+        };
+        Ok((rest, function))
+    }
+}
+
+impl Display for TransformFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            TransformFunction::Matrix(matrix) => write!(f, "{}", matrix),
+            TransformFunction::Translate(tx, 0_f32) => write!(f, "translate({})", tx),
+            TransformFunction::Translate(tx, ty) => write!(f, "translate({} {})", tx, ty),
+            TransformFunction::Scale(sx, sy) if sx == sy => write!(f, "scale({})", sx),
+            TransformFunction::Scale(sx, sy) => write!(f, "scale({} {})", sx, sy),
+            TransformFunction::Rotate { angle, cx, cy } if cx == 0_f32 && cy == 0_f32 => {
+                write!(f, "rotate({})", angle)
+            }
+            TransformFunction::Rotate { angle, cx, cy } => {
+                write!(f, "rotate({} {} {})", angle, cx, cy)
+            }
+            TransformFunction::SkewX(angle) => write!(f, "skewX({})", angle),
+            TransformFunction::SkewY(angle) => write!(f, "skewY({})", angle),
+        }
+    }
+}
+
+/// The `transform` attribute: a list of functions applied from right to left.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::transform::Transform;
+///
+/// let transform: Transform = "translate(2 3) scale(2)".parse().unwrap();
+/// assert_eq!(transform.apply(1.0, 1.0), (4.0, 5.0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform(pub Vec<TransformFunction>);
+
+impl Transform {
+    /// all functions composed into one matrix
+    pub fn matrix(&self) -> Matrix {
+        self.0
+            .iter()
+            .fold(Matrix::IDENTITY, |matrix, function| {
+                matrix.multiply(&function.matrix())
+            })
+    }
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        self.matrix().apply(x, y)
+    }
+    pub fn apply_commands(&self, commands: &[Command]) -> Vec<Command> {
+        self.matrix().apply_commands(commands)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    fn parser(s: &str) -> IResult<&str, Transform> {
+        let (s, _) = multispace0(s)?;
+        let (s, first) = match TransformFunction::parser(s) {
+            Ok((s, first)) => (s, first),
+            Err(nom::Err::Error(_)) => return Ok((s, Transform::default())),
+            Err(e) => return Err(e),
+        };
+        let (s, others) = many0(preceded(
+            alt((comma_wsp, multispace0)),
+            TransformFunction::parser,
+        ))(s)?;
+        let (s, _) = multispace0(s)?;
+        let mut functions = vec![first];
+        functions.extend(others);
+        Ok((s, Transform(functions)))
+    }
+}
+
+impl From<Matrix> for Transform {
+    fn from(matrix: Matrix) -> Self {
+        Transform(vec![TransformFunction::Matrix(matrix)])
+    }
+}
+
+impl From<TransformFunction> for Transform {
+    fn from(function: TransformFunction) -> Self {
+        Transform(vec![function])
+    }
+}
+
+impl FromStr for Transform {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Transform::parser(s) {
+            Ok(("", transform)) => Ok(transform),
+            _ => Err(Error::invalid_value(s, "a list of transform functions")),
+        }
+    }
+}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, function) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", function)?;
+        }
+        Ok(())
+    }
+}
+
+impl PathData {
+    /// map every command with `matrix`, see [`Matrix::apply_commands`]
+    pub fn transform(&mut self, matrix: &Matrix) {
+        self.0 = matrix.apply_commands(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod test_transform {
+    use super::*;
+
+    fn close(a: (f32, f32), b: (f32, f32)) -> bool {
+        (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_parse() {
+        let transform: Transform = "translate(2 3) rotate(45 12 12) scale(2)".parse().unwrap();
+        assert_eq!(
+            transform.0,
+            vec![
+                TransformFunction::Translate(2.0, 3.0),
+                TransformFunction::Rotate {
+                    angle: 45.0,
+                    cx: 12.0,
+                    cy: 12.0
+                },
+                TransformFunction::Scale(2.0, 2.0),
+            ]
+        );
+        assert_eq!(transform.to_string(), "translate(2 3) rotate(45 12 12) scale(2)");
+
+        let cases = [
+            ("matrix(1,0,0,1,5,6)", "matrix(1 0 0 1 5 6)"),
+            ("translate(-2)", "translate(-2)"),
+            ("scale(1 , 2),skewX(30)", "scale(1 2) skewX(30)"),
+            (" rotate( 90 ) skewY(-10) ", "rotate(90) skewY(-10)"),
+            ("", ""),
+        ];
+        for (input, output) in cases {
+            assert_eq!(input.parse::<Transform>().unwrap().to_string(), output);
+        }
+        for invalid in ["rotate(1 2)", "scale()", "translate(1 2 3)", "move(1)", "scale(1", "scale(1) x"] {
+            assert!(invalid.parse::<Transform>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_matrix() {
+        let transform: Transform = "translate(2 3) rotate(90) scale(2)".parse().unwrap();
+        assert!(close(transform.apply(1.0, 0.0), (2.0, 5.0)));
+        let rotate = Matrix::rotate_around(90.0, 12.0, 12.0);
+        assert!(close(rotate.apply(24.0, 12.0), (12.0, 24.0)));
+        assert!(close(Matrix::skew_x(45.0).apply(0.0, 1.0), (1.0, 1.0)));
+        assert!(close(Matrix::skew_y(45.0).apply(1.0, 0.0), (1.0, 1.0)));
+
+        let matrix = transform.matrix();
+        let inverse = matrix.inverse().unwrap();
+        assert!(close(inverse.apply(2.0, 5.0), (1.0, 0.0)));
+        let identity = matrix.multiply(&inverse);
+        assert!(close((identity.a, identity.b), (1.0, 0.0)));
+        assert!(close((identity.e, identity.f), (0.0, 0.0)));
+        assert_eq!(Matrix::scale(0.0, 1.0).inverse(), None);
+        assert!(Transform::default().matrix().is_identity());
+    }
+
+    #[test]
+    fn test_apply_commands() {
+        let (_, d) = PathData::from_str("M1 1 H 3 v2 l1 1 C 0 0 1 1 2 2 a1 2 0 0 1 2 0 Z").unwrap();
+        // keeps the axes
+        let moved = Matrix::translate(10.0, 20.0).apply_commands(&d.0);
+        assert_eq!(
+            PathData(moved).to_string(),
+            "M 11,21 H 13 v 2 l 1,1 C 10,20 11,21 12,22 a 1 2 0 0 1 2,0 Z"
+        );
+        // H and V become L when rotated, the arc is rotated too
+        let rotated = Matrix::rotate(90.0).apply_commands(&d.0);
+        let mut path = PathData(rotated);
+        for c in path.iter_mut() {
+            // round away float noise for the comparison
+            if let Command::LineTo(LineTo::L(l)) = c {
+                l.x = l.x.round();
+                l.y = l.y.round();
+            }
+            if let Command::EllipticalArc(a) = c {
+                a.angle = a.angle.round();
+                a.x = a.x.round();
+                a.y = a.y.round();
+            }
+        }
+        assert!(matches!(path.0[1], Command::LineTo(LineTo::L(L { x, y, relative: false })) if x == -1.0 && y == 3.0));
+        assert!(matches!(path.0[2], Command::LineTo(LineTo::L(L { x, y, relative: true })) if x == -2.0 && y == 0.0));
+        match &path.0[5] {
+            Command::EllipticalArc(a) => {
+                // the radii stay, the ellipse is rotated
+                assert!(close((a.rx, a.ry), (1.0, 2.0)));
+                assert_eq!(a.angle, 90.0);
+                assert_eq!((a.x, a.y), (0.0, 2.0));
+                assert!(a.sweep_flag);
+            }
+            c => panic!("unexpected command {:?}", c),
+        }
+        // mirrored arcs turn the other way
+        let mirrored = Matrix::scale(-1.0, 1.0).apply_commands(&d.0);
+        assert!(matches!(&mirrored[5], Command::EllipticalArc(a) if !a.sweep_flag && a.x == -2.0));
+        // a non uniform scale changes the radii
+        let (_, arc) = PathData::from_str("M0 0 A 1 1 0 0 0 2 0").unwrap();
+        let scaled = Matrix::scale(2.0, 3.0).apply_commands(&arc.0);
+        match &scaled[1] {
+            Command::EllipticalArc(a) => {
+                assert!(close((a.rx, a.ry), (3.0, 2.0)));
+                assert_eq!(a.angle, 90.0);
+            }
+            c => panic!("unexpected command {:?}", c),
+        }
+        // a leading `m` is from the origin and is translated, the next ones are not
+        let (_, d) = PathData::from_str("m10 10 l1 1 m2 2 z").unwrap();
+        let moved = Matrix::translate(5.0, 5.0).apply_commands(&d.0);
+        assert_eq!(PathData(moved).to_string(), "m 15,15 l 1,1 m 2,2 z");
+    }
+}
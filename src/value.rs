@@ -2,6 +2,7 @@ use std::{
     collections::HashMap, fmt::{Debug, Display}, str::FromStr
 };
 
+use crate::error::{attribute, Error, ParseError};
 use crate::impl_auto_value;
 
 pub trait AutoValue: PartialEq + ToString + FromStr + Debug {
@@ -10,14 +11,18 @@ pub trait AutoValue: PartialEq + ToString + FromStr + Debug {
 
 /// Value types for SVG values that can be auto or other types.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Auto<T: AutoValue>(pub Option<T>);
 
 impl<T: AutoValue> Auto<T>{
-    pub fn get_from_map(map: &HashMap<&str, &str>, k: &str) -> Auto<T>{
-        if let Some(v) = map.get(k){
-            Auto::from_str(v).unwrap()
-        }else{
-            Auto(None)
+    /// parse the value of attribute `k`, `Auto(None)` if it is not in the map
+    pub fn get_from_map<'a>(
+        map: &HashMap<&str, &'a str>,
+        k: &str,
+    ) -> Result<Auto<T>, nom::Err<ParseError<&'a str>>> {
+        match map.get(k) {
+            Some(v) => attribute(k, v),
+            None => Ok(Auto(None)),
         }
     }
 }
@@ -39,7 +44,7 @@ impl<T: AutoValue> Default for Auto<T> {
 }
 
 impl<T: AutoValue> std::str::FromStr for Auto<T> {
-    type Err = String;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
@@ -48,7 +53,7 @@ impl<T: AutoValue> std::str::FromStr for Auto<T> {
         } else {
             match s.parse::<T>() {
                 Ok(f) => Ok(Auto(Some(f))),
-                Err(_) => Err(format!("parse error{:?}", s)),
+                Err(_) => Err(Error::invalid_value(s, "`auto` or a value")),
             }
         }
     }
@@ -56,10 +61,9 @@ impl<T: AutoValue> std::str::FromStr for Auto<T> {
 
 impl<T: AutoValue> Display for Auto<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.0.is_none() {
-            write!(f, "auto")
-        } else {
-            write!(f, "{}", self.0.as_ref().unwrap().to_string())
+        match self.0.as_ref() {
+            Some(v) => write!(f, "{}", v.to_string()),
+            None => write!(f, "auto"),
         }
     }
 }
@@ -0,0 +1,315 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::{character::complete::multispace0, sequence::preceded};
+
+use crate::{
+    error::{Error, IResult},
+    parser::{number, sep},
+    transform::Matrix,
+};
+
+/// The `viewBox` attribute of `<svg>`: the rectangle of user space which is mapped to the viewport.
+///
+/// The four numbers can be separated by whitespace and/or commas: `0 0 24 24`, `0,0,24,24`, `-1 -1 26.5 26`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ViewBox {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ViewBox {
+    pub fn new(min_x: f32, min_y: f32, width: f32, height: f32) -> Self {
+        ViewBox {
+            min_x,
+            min_y,
+            width,
+            height,
+        }
+    }
+    fn numbers(s: &str) -> IResult<&str, (f32, f32, f32, f32)> {
+        let (s, min_x) = preceded(multispace0, number)(s)?;
+        let (s, min_y) = preceded(sep, number)(s)?;
+        let (s, width) = preceded(sep, number)(s)?;
+        let (s, height) = preceded(sep, number)(s)?;
+        let (s, _) = multispace0(s)?;
+        Ok((s, (min_x, min_y, width, height)))
+    }
+}
+
+impl FromStr for ViewBox {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min_x, min_y, width, height) = match ViewBox::numbers(s) {
+            Ok(("", numbers)) => numbers,
+            _ => return Err(Error::invalid_value(s, "4 numbers")),
+        };
+        // a width or height of zero disables rendering, negative values are an error,
+        // both can not be mapped to a viewport
+        if width <= 0_f32 || height <= 0_f32 {
+            return Err(Error::invalid_value(s, "a positive width and height"));
+        }
+        Ok(ViewBox::new(min_x, min_y, width, height))
+    }
+}
+
+impl Display for ViewBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.min_x, self.min_y, self.width, self.height
+        )
+    }
+}
+
+/// How the `viewBox` is aligned in the viewport when their aspect ratios differ.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Align {
+    /// do not keep the aspect ratio, stretch the `viewBox` to fill the viewport
+    None,
+    XMinYMin,
+    XMidYMin,
+    XMaxYMin,
+    XMinYMid,
+    #[default]
+    XMidYMid,
+    XMaxYMid,
+    XMinYMax,
+    XMidYMax,
+    XMaxYMax,
+}
+
+impl Align {
+    const ALL: [(&'static str, Align); 10] = [
+        ("none", Align::None),
+        ("xMinYMin", Align::XMinYMin),
+        ("xMidYMin", Align::XMidYMin),
+        ("xMaxYMin", Align::XMaxYMin),
+        ("xMinYMid", Align::XMinYMid),
+        ("xMidYMid", Align::XMidYMid),
+        ("xMaxYMid", Align::XMaxYMid),
+        ("xMinYMax", Align::XMinYMax),
+        ("xMidYMax", Align::XMidYMax),
+        ("xMaxYMax", Align::XMaxYMax),
+    ];
+}
+
+impl Align {
+    /// the part of the free space before the `viewBox`, on the x and the y axis
+    fn offsets(&self) -> (f32, f32) {
+        let (min, mid, max) = (0_f32, 0.5_f32, 1_f32);
+        match self {
+            Align::None | Align::XMinYMin => (min, min),
+            Align::XMidYMin => (mid, min),
+            Align::XMaxYMin => (max, min),
+            Align::XMinYMid => (min, mid),
+            Align::XMidYMid => (mid, mid),
+            Align::XMaxYMid => (max, mid),
+            Align::XMinYMax => (min, max),
+            Align::XMidYMax => (mid, max),
+            Align::XMaxYMax => (max, max),
+        }
+    }
+}
+
+impl FromStr for Align {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Align::ALL
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, align)| *align)
+            .ok_or_else(|| Error::invalid_value(s, "`none` or `x(Min|Mid|Max)Y(Min|Mid|Max)`"))
+    }
+}
+
+impl Display for Align {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (name, _) = Align::ALL
+            .iter()
+            .find(|(_, align)| align == self)
+            .expect("every align has a name");
+        f.write_str(name)
+    }
+}
+
+/// `meet` scales the `viewBox` to be fully visible, `slice` scales it to cover the whole viewport.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum MeetOrSlice {
+    #[default]
+    Meet,
+    Slice,
+}
+
+impl FromStr for MeetOrSlice {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "meet" => Ok(MeetOrSlice::Meet),
+            "slice" => Ok(MeetOrSlice::Slice),
+            _ => Err(Error::invalid_value(s, "`meet` or `slice`")),
+        }
+    }
+}
+
+impl Display for MeetOrSlice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeetOrSlice::Meet => write!(f, "meet"),
+            MeetOrSlice::Slice => write!(f, "slice"),
+        }
+    }
+}
+
+/// The `preserveAspectRatio` attribute: `defer? <align> <meetOrSlice>?`, default `xMidYMid meet`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreserveAspectRatio {
+    /// only meaningful for `<image>`, kept to write it back
+    pub defer: bool,
+    pub align: Align,
+    pub meet_or_slice: MeetOrSlice,
+}
+
+impl FromStr for PreserveAspectRatio {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace().peekable();
+        let defer = words.next_if_eq(&"defer").is_some();
+        let align = match words.next() {
+            Some(align) => align.parse()?,
+            None => return Err(Error::invalid_value(s, "an align value")),
+        };
+        let meet_or_slice = match words.next() {
+            Some(meet_or_slice) => meet_or_slice.parse()?,
+            None => MeetOrSlice::default(),
+        };
+        if words.next().is_some() {
+            return Err(Error::invalid_value(s, "`defer? <align> <meetOrSlice>?`"));
+        }
+        Ok(PreserveAspectRatio {
+            defer,
+            align,
+            meet_or_slice,
+        })
+    }
+}
+
+impl PreserveAspectRatio {
+    /// the transform which maps `view_box` into the rectangle `viewport`,
+    /// following "the equivalent transform of an element with a viewBox" of svg 2.
+    ///
+    /// a side of `view_box` without size takes the scale of the other one (or 1)
+    pub fn matrix(&self, view_box: &ViewBox, viewport: &ViewBox) -> Matrix {
+        let scale = |size: f32, target: f32| (size > 0_f32).then(|| target / size);
+        let (sx, sy) = match (
+            scale(view_box.width, viewport.width),
+            scale(view_box.height, viewport.height),
+        ) {
+            (Some(sx), Some(sy)) => match (self.align, self.meet_or_slice) {
+                (Align::None, _) => (sx, sy),
+                (_, MeetOrSlice::Meet) => (sx.min(sy), sx.min(sy)),
+                (_, MeetOrSlice::Slice) => (sx.max(sy), sx.max(sy)),
+            },
+            (Some(s), None) | (None, Some(s)) => (s, s),
+            (None, None) => (1_f32, 1_f32),
+        };
+        let (ax, ay) = self.align.offsets();
+        let tx = viewport.min_x - view_box.min_x * sx + (viewport.width - view_box.width * sx) * ax;
+        let ty = viewport.min_y - view_box.min_y * sy + (viewport.height - view_box.height * sy) * ay;
+        Matrix::new(sx, 0_f32, 0_f32, sy, tx, ty)
+    }
+}
+
+impl Display for PreserveAspectRatio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.defer {
+            write!(f, "defer ")?;
+        }
+        write!(f, "{}", self.align)?;
+        if self.meet_or_slice != MeetOrSlice::default() {
+            write!(f, " {}", self.meet_or_slice)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_view_box {
+    use super::*;
+
+    #[test]
+    fn test_view_box() {
+        let cases = [
+            ("0 0 24 24", ViewBox::new(0.0, 0.0, 24.0, 24.0)),
+            ("-1 -1 26 26", ViewBox::new(-1.0, -1.0, 26.0, 26.0)),
+            ("0 0 24.5 24", ViewBox::new(0.0, 0.0, 24.5, 24.0)),
+            ("0,0,24,24", ViewBox::new(0.0, 0.0, 24.0, 24.0)),
+            (" 0, 0 1e2 .5 ", ViewBox::new(0.0, 0.0, 100.0, 0.5)),
+        ];
+        for (input, view_box) in cases {
+            assert_eq!(input.parse::<ViewBox>().unwrap(), view_box, "{:?}", input);
+        }
+        assert_eq!(
+            ViewBox::new(-1.0, 0.0, 24.5, 24.0).to_string(),
+            "-1 0 24.5 24"
+        );
+        for invalid in ["", "0 0 24", "0 0 24 24 1", "0 0 a 24", "0 0 0 24", "0 0 24 -1"] {
+            assert!(invalid.parse::<ViewBox>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_preserve_aspect_ratio() {
+        let par: PreserveAspectRatio = "xMinYMax slice".parse().unwrap();
+        assert_eq!(par.align, Align::XMinYMax);
+        assert_eq!(par.meet_or_slice, MeetOrSlice::Slice);
+        assert!(!par.defer);
+        assert_eq!(par.to_string(), "xMinYMax slice");
+
+        let par: PreserveAspectRatio = "defer none".parse().unwrap();
+        assert!(par.defer);
+        assert_eq!(par.align, Align::None);
+        assert_eq!(par.to_string(), "defer none");
+
+        assert_eq!(
+            "xMidYMid".parse::<PreserveAspectRatio>().unwrap(),
+            PreserveAspectRatio::default()
+        );
+        for invalid in ["", "xmidymid", "defer", "none meet slice", "xMidYMid cover"] {
+            assert!(invalid.parse::<PreserveAspectRatio>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_matrix() {
+        let from = ViewBox::new(10.0, 10.0, 20.0, 10.0);
+        let to = ViewBox::new(0.0, 0.0, 24.0, 24.0);
+        let matrix = |s: &str| s.parse::<PreserveAspectRatio>().unwrap().matrix(&from, &to);
+        // scaled by 1.2, centered vertically
+        let meet = matrix("xMidYMid");
+        assert_eq!(meet.apply(10.0, 10.0), (0.0, 6.0));
+        assert_eq!(meet.apply(30.0, 20.0), (24.0, 18.0));
+        assert_eq!(matrix("xMinYMax").apply(10.0, 20.0), (0.0, 24.0));
+        // scaled by 2.4, cut on the left and the right
+        let slice = matrix("xMidYMid slice");
+        assert_eq!(slice.apply(20.0, 15.0), (12.0, 12.0));
+        assert_eq!(slice.apply(10.0, 10.0), (-12.0, 0.0));
+        assert_eq!(matrix("none").apply(30.0, 20.0), (24.0, 24.0));
+        // a flat box keeps its aspect ratio
+        let line = ViewBox::new(0.0, 0.0, 12.0, 0.0);
+        let flat = PreserveAspectRatio::default().matrix(&line, &to);
+        assert_eq!(flat.apply(12.0, 0.0), (24.0, 12.0));
+    }
+}
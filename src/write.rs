@@ -0,0 +1,496 @@
+//! Write documents with another layout than `Display`: indented, minified, with rounded numbers...
+//!
+//! see [`WriteOptions`] and [`Svg::write_to`]
+
+use std::io;
+
+use crate::children::{round, Child, Path};
+use crate::header::Header;
+use crate::length::Length;
+use crate::optimize::attributes::{initial, redundant, Computed};
+use crate::optimize::{has_styles, referenced};
+use crate::parser::number;
+use crate::view_box::PreserveAspectRatio;
+use crate::Svg;
+
+/// attributes whose numbers are rounded to the precision, the path data has its own encoder
+const NUMERIC: [&str; 25] = [
+    "x",
+    "y",
+    "width",
+    "height",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "points",
+    "viewBox",
+    "transform",
+    "opacity",
+    "fill-opacity",
+    "stroke-opacity",
+    "stroke-width",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-miterlimit",
+    "font-size",
+    "offset",
+];
+
+/// How elements are put on lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Indent {
+    /// the whole document on a single line
+    #[default]
+    None,
+    /// one element per line, indented by this number of spaces per level
+    Spaces(usize),
+    /// one element per line, indented by a tab per level
+    Tab,
+}
+
+/// How elements without children are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelfClosing {
+    /// `<path/>`
+    #[default]
+    Compact,
+    /// `<path />`
+    Spaced,
+    /// `<path></path>`
+    Never,
+}
+
+/// The quotes around attribute values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Quote {
+    /// `d="..."`, a `"` in a value is written `&quot;`
+    #[default]
+    Double,
+    /// `d='...'`, a `'` in a value is written `&apos;`
+    Single,
+}
+
+/// The order of the attributes of an element.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AttributeOrder {
+    /// the order of the source, attributes which were added follow them
+    #[default]
+    Source,
+    /// sorted by name
+    Alphabetical,
+    /// these names first in this order, then the other attributes in source order
+    Given(Vec<String>),
+}
+
+/// Whether the document starts with `<?xml version="1.0" encoding="UTF-8"?>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum XmlDeclaration {
+    /// only if the source had one
+    #[default]
+    Keep,
+    /// the one of the source, or the default one
+    Always,
+    Never,
+}
+
+/// How [`Svg::write_to`] writes a document, the default writes what `Display` writes.
+///
+/// ## Example
+/// ```rust
+/// use svg_icon::write::{Indent, WriteOptions};
+/// use svg_icon::Svg;
+///
+/// let svg: Svg = r#"<svg viewBox="0 0 24 24"><g><path d="M 0.5,0.5 L 2.12345,0.5"/></g></svg>"#.parse().unwrap();
+/// let mut out = vec![];
+/// svg.write_to(&mut out, &WriteOptions::default().indent(Indent::Spaces(2)).precision(2)).unwrap();
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     "<svg viewBox=\"0 0 24 24\" xmlns=\"http://www.w3.org/2000/svg\">\n  <g>\n    <path d=\"M 0.5,0.5 L 2.12,0.5\"/>\n  </g>\n</svg>\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    pub indent: Indent,
+    pub self_closing: SelfClosing,
+    /// the decimals numbers are rounded to, all of them with `None`
+    pub precision: Option<u8>,
+    /// write path data with [`PathData::encode`](crate::children::PathData::encode)
+    pub compact_path_data: bool,
+    pub attribute_order: AttributeOrder,
+    /// leave out attributes which have the value they would have without them: inherited
+    /// properties set to the value of the parent, `opacity="1"`, zero positions, `width="auto"`...
+    /// nothing is left out of documents with styles, css may depend on them
+    pub omit_defaults: bool,
+    pub quote: Quote,
+    pub xml_declaration: XmlDeclaration,
+}
+
+impl WriteOptions {
+    /// one element per line, indented by two spaces
+    pub fn pretty() -> Self {
+        WriteOptions::default().indent(Indent::Spaces(2))
+    }
+    /// the smallest output: a single line, compact path data, 3 decimals, no defaults and no
+    /// xml declaration
+    pub fn minify() -> Self {
+        WriteOptions::default()
+            .precision(3)
+            .compact_path_data(true)
+            .omit_defaults(true)
+            .xml_declaration(XmlDeclaration::Never)
+    }
+    pub fn indent(mut self, indent: Indent) -> Self {
+        self.indent = indent;
+        self
+    }
+    pub fn self_closing(mut self, self_closing: SelfClosing) -> Self {
+        self.self_closing = self_closing;
+        self
+    }
+    pub fn precision(mut self, decimals: u8) -> Self {
+        self.precision = Some(decimals);
+        self
+    }
+    pub fn compact_path_data(mut self, compact: bool) -> Self {
+        self.compact_path_data = compact;
+        self
+    }
+    pub fn attribute_order(mut self, order: AttributeOrder) -> Self {
+        self.attribute_order = order;
+        self
+    }
+    pub fn omit_defaults(mut self, omit: bool) -> Self {
+        self.omit_defaults = omit;
+        self
+    }
+    pub fn quote(mut self, quote: Quote) -> Self {
+        self.quote = quote;
+        self
+    }
+    pub fn xml_declaration(mut self, declaration: XmlDeclaration) -> Self {
+        self.xml_declaration = declaration;
+        self
+    }
+    /// the value of attribute `name` of `child` as it is written
+    fn value(&self, child: Option<&Child>, name: &str, value: String) -> String {
+        match (child, name, self.precision) {
+            (Some(Child::Path(Path { d: Some(d), .. })), "d", precision) if self.compact_path_data => {
+                d.encode(precision)
+            }
+            (Some(Child::Path(Path { d: Some(d), .. })), "d", Some(decimals)) => d.round(decimals).to_string(),
+            (_, name, Some(decimals)) if NUMERIC.contains(&name) => round_numbers(&value, decimals),
+            _ => value,
+        }
+    }
+}
+
+/// every number of `value` rounded to `decimals`, numbers in names (`url(#a1)`, `#f00`) are kept
+fn round_numbers(value: &str, decimals: u8) -> String {
+    let mut rounded = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut previous: Option<char> = None;
+    while let Some(c) = rest.chars().next() {
+        let in_name = previous.is_some_and(|p| p.is_ascii_alphanumeric() || p == '#' || p == '.');
+        if !in_name || c == '-' || c == '+' {
+            if let Ok((after, n)) = number(rest) {
+                rounded.push_str(&round(n, Some(decimals)).to_string());
+                previous = rest[..rest.len() - after.len()].chars().last();
+                rest = after;
+                continue;
+            }
+        }
+        rounded.push(c);
+        previous = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    rounded
+}
+
+/// the attributes of an element in the order `Display` writes them
+fn attributes(child: &Child) -> Vec<(String, String)> {
+    match child {
+        Child::Circle(c) => c.common.ordered_attributes(c.own_attributes()),
+        Child::Ellipse(e) => e.common.ordered_attributes(e.own_attributes()),
+        Child::Group(g) => g.common.ordered_attributes(vec![]),
+        Child::Line(l) => l.common.ordered_attributes(l.own_attributes()),
+        Child::Path(p) => p.common.ordered_attributes(p.own_attributes()),
+        Child::Polygon(p) => p.common.ordered_attributes(p.own_attributes()),
+        Child::Polyline(p) => p.common.ordered_attributes(p.own_attributes()),
+        Child::Rect(r) => r.common.ordered_attributes(r.own_attributes()),
+        Child::Unknown { attrs, .. } => attrs.clone(),
+        Child::Text(_) | Child::Comment(_) => vec![],
+    }
+}
+
+/// the names of the lengths which are zero, their default value
+fn zeros(lengths: &[(&'static str, Option<Length>)]) -> Vec<&'static str> {
+    lengths
+        .iter()
+        .filter(|(_, length)| length.is_some_and(|length| length.value == 0_f32))
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// the geometry attributes of a known element which have their default value
+fn geometry_defaults(child: &Child) -> Vec<&'static str> {
+    let mut names = child
+        .common()
+        .map(|common| zeros(&[("x", common.x), ("y", common.y)]))
+        .unwrap_or_default();
+    names.extend(match child {
+        Child::Circle(c) => zeros(&[("cx", c.cx), ("cy", c.cy)]),
+        Child::Ellipse(e) => zeros(&[("cx", e.cx), ("cy", e.cy)]),
+        Child::Line(l) => zeros(&[("x1", l.x1), ("y1", l.y1), ("x2", l.x2), ("y2", l.y2)]),
+        Child::Rect(r) => {
+            let mut names = zeros(&[("x", r.x), ("y", r.y)]);
+            // without `rx` the radius is `ry`, only both can be left out
+            let radii = zeros(&[("rx", r.rx), ("ry", r.ry)]);
+            if radii.len() == [r.rx, r.ry].iter().flatten().count() {
+                names.extend(radii);
+            }
+            names
+        }
+        _ => vec![],
+    });
+    names
+}
+
+/// writes elements to `out`
+struct Writer<'a, W> {
+    out: &'a mut W,
+    options: &'a WriteOptions,
+}
+
+impl<W: io::Write> Writer<'_, W> {
+    /// a line break and the indentation of `depth`, nothing on a single line
+    fn new_line(&mut self, depth: usize) -> io::Result<()> {
+        match self.options.indent {
+            Indent::None => Ok(()),
+            Indent::Spaces(spaces) => write!(self.out, "\n{}", " ".repeat(spaces * depth)),
+            Indent::Tab => write!(self.out, "\n{}", "\t".repeat(depth)),
+        }
+    }
+    fn attributes(&mut self, child: Option<&Child>, attrs: Vec<(String, String)>, omitted: &[&str]) -> io::Result<()> {
+        let mut attrs: Vec<(String, String)> = attrs
+            .into_iter()
+            .filter(|(name, _)| !omitted.contains(&name.as_str()))
+            .collect();
+        match &self.options.attribute_order {
+            AttributeOrder::Source => {}
+            AttributeOrder::Alphabetical => attrs.sort_by(|(a, _), (b, _)| a.cmp(b)),
+            AttributeOrder::Given(names) => {
+                attrs.sort_by_key(|(name, _)| names.iter().position(|n| n == name).unwrap_or(usize::MAX))
+            }
+        }
+        for (name, value) in attrs {
+            let value = self.options.value(child, &name, value);
+            match self.options.quote {
+                Quote::Double => write!(self.out, " {}=\"{}\"", name, value.replace('"', "&quot;"))?,
+                Quote::Single => write!(self.out, " {}='{}'", name, value.replace('\'', "&apos;"))?,
+            }
+        }
+        Ok(())
+    }
+    /// the end of the start tag of `name`, its children and its end tag.
+    ///
+    /// `computed` are the inherited properties of the children, `None` if they are not known
+    fn end(
+        &mut self,
+        name: &str,
+        children: &[Child],
+        depth: usize,
+        inline: bool,
+        computed: Option<&Computed>,
+    ) -> io::Result<()> {
+        if children.is_empty() {
+            return match self.options.self_closing {
+                SelfClosing::Compact => write!(self.out, "/>"),
+                SelfClosing::Spaced => write!(self.out, " />"),
+                SelfClosing::Never => write!(self.out, "></{}>", name),
+            };
+        }
+        write!(self.out, ">")?;
+        // line breaks beside text would be part of it
+        let inline = inline || children.iter().any(|child| matches!(child, Child::Text(_)));
+        for child in children {
+            if !inline {
+                self.new_line(depth + 1)?;
+            }
+            self.child(child, depth + 1, inline, computed)?;
+        }
+        if !inline {
+            self.new_line(depth)?;
+        }
+        write!(self.out, "</{}>", name)
+    }
+    fn child(&mut self, child: &Child, depth: usize, inline: bool, parent: Option<&Computed>) -> io::Result<()> {
+        match child {
+            Child::Text(text) => write!(self.out, "{}", text),
+            Child::Comment(comment) => write!(self.out, "<!--{}-->", comment),
+            // what elements inside unknown ones inherit is not known, e.g. in `<symbol>`
+            Child::Unknown { name, attrs, children } => {
+                write!(self.out, "<{}", name)?;
+                self.attributes(None, attrs.clone(), &[])?;
+                self.end(name, children, depth, inline, None)
+            }
+            child => {
+                let (omitted, computed) = match (parent, child.common()) {
+                    // a referenced element and its children inherit from where it is used
+                    (Some(_), Some(common)) if referenced(common) => (geometry_defaults(child), None),
+                    (Some(parent), Some(common)) => {
+                        let (mut omitted, computed) = redundant(common, parent);
+                        omitted.extend(geometry_defaults(child));
+                        (omitted, Some(computed))
+                    }
+                    _ => (vec![], None),
+                };
+                write!(self.out, "<{}", child.name())?;
+                self.attributes(Some(child), attributes(child), &omitted)?;
+                let children = match child {
+                    Child::Group(group) => group.children.as_slice(),
+                    _ => &[],
+                };
+                self.end(child.name(), children, depth, inline, computed.as_ref())
+            }
+        }
+    }
+}
+
+impl Svg {
+    /// write the document to `out` as `options` say, element by element
+    pub fn write_to(&self, out: &mut impl io::Write, options: &WriteOptions) -> io::Result<()> {
+        let mut writer = Writer { out, options };
+        let default = Header::default();
+        let header = match options.xml_declaration {
+            XmlDeclaration::Keep => self.header.as_ref(),
+            XmlDeclaration::Always => Some(self.header.as_ref().unwrap_or(&default)),
+            XmlDeclaration::Never => None,
+        };
+        if let Some(header) = header {
+            write!(writer.out, "{}", header)?;
+            writer.new_line(0)?;
+        }
+        for markup in &self.prolog {
+            write!(writer.out, "{}", markup)?;
+            writer.new_line(0)?;
+        }
+
+        let (omitted, computed) = match options.omit_defaults && !has_styles(self) {
+            true => {
+                let (mut omitted, computed) = redundant(&self.common, &initial());
+                omitted.extend(zeros(&[("x", self.common.x), ("y", self.common.y)]));
+                for (name, size) in [("width", &self.width), ("height", &self.height)] {
+                    if size.0.is_none() {
+                        omitted.push(name);
+                    }
+                }
+                if self.preserve_aspect_ratio == Some(PreserveAspectRatio::default()) {
+                    omitted.push("preserveAspectRatio");
+                }
+                (omitted, Some(computed))
+            }
+            false => (vec![], None),
+        };
+        write!(writer.out, "<svg")?;
+        writer.attributes(None, self.common.ordered_attributes(self.own_attributes()), &omitted)?;
+        writer.end("svg", &self.children, 0, false, computed.as_ref())?;
+        writer.new_line(0)
+    }
+}
+
+#[cfg(test)]
+mod test_write {
+    use super::*;
+
+    fn write(source: &str, options: &WriteOptions) -> String {
+        let svg: Svg = source.parse().unwrap();
+        let mut out = vec![];
+        svg.write_to(&mut out, options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_default() {
+        let source = r##"<?xml version="1.0" encoding="UTF-8"?><svg width="24" height="24" viewBox="0 0 48 48" fill="none" xmlns="http://www.w3.org/2000/svg"><circle cx="24" cy="24" r="20" stroke="#000000" stroke-width="4"/><g id="a"><path d="M 24,19 V 4" stroke-opacity="0.5"/><title>it's "here"</title></g><!-- end --><defs/></svg>"##;
+        let svg: Svg = source.parse().unwrap();
+        assert_eq!(write(source, &WriteOptions::default()), svg.to_string());
+    }
+
+    #[test]
+    fn test_pretty() {
+        let source = r#"<?xml version="1.0" encoding="UTF-8"?><svg viewBox="0 0 24 24"><g fill="none"><rect width="1" height="1"/><title>Close</title></g><!-- end --></svg>"#;
+        assert_eq!(
+            write(source, &WriteOptions::pretty()),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg">
+  <g fill="none">
+    <rect width="1" height="1"/>
+    <title>Close</title>
+  </g>
+  <!-- end -->
+</svg>
+"#
+        );
+        let tabs = write(source, &WriteOptions::default().indent(Indent::Tab).xml_declaration(XmlDeclaration::Never));
+        assert!(tabs.starts_with("<svg viewBox=\"0 0 24 24\" xmlns=\"http://www.w3.org/2000/svg\">\n\t<g fill=\"none\">\n\t\t<rect"));
+    }
+
+    #[test]
+    fn test_minify() {
+        let source = r##"<svg width="auto" viewBox="0 0 24.00001 24" preserveAspectRatio="xMidYMid" fill="#000" opacity="1"><g stroke="red" stroke-linecap="butt"><path d="M 0.5,0.5 L 10.12345,0.5 L 10.12345,10" stroke="red" transform="translate(0.33333 1)"/><rect x="0" y="1" width="2" height="2" rx="0"/><rect width="2" height="2" rx="0" ry="1"/></g><circle cx="0" cy="12.0004" r="2" fill="url(#g1)"/></svg>"##;
+        assert_eq!(
+            write(source, &WriteOptions::minify()),
+            r##"<svg viewBox="0 0 24 24" xmlns="http://www.w3.org/2000/svg"><g stroke="#ff0000"><path d="M.5.5h9.623V10" transform="translate(0.333 1)"/><rect y="1" width="2" height="2"/><rect width="2" height="2" rx="0" ry="1"/></g><circle cy="12" r="2" fill="url(#g1)"/></svg>"##
+        );
+        // css may use the attributes
+        let styled = r#"<svg><style>rect { fill: red }</style><rect x="0" width="1" height="1"/></svg>"#;
+        assert!(write(styled, &WriteOptions::minify()).contains(r#"x="0""#));
+        // a referenced element inherits from where it is used
+        let used = r##"<svg><g fill="red"><path id="p" d="M0 0H1V1Z" fill="red"/></g><use href="#p" fill="blue"/></svg>"##;
+        assert_eq!(
+            write(used, &WriteOptions::minify()),
+            r##"<svg><g fill="#ff0000"><path id="p" d="M0 0H1V1Z" fill="#ff0000"/></g><use href="#p" fill="blue"/></svg>"##
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let source = r#"<svg><path id="p" d="M 0,0 L 1,1" data-name="it's"/><g></g></svg>"#;
+        let options = WriteOptions::default()
+            .quote(Quote::Single)
+            .self_closing(SelfClosing::Spaced)
+            .attribute_order(AttributeOrder::Alphabetical)
+            .xml_declaration(XmlDeclaration::Always);
+        assert_eq!(
+            write(source, &options),
+            r#"<?xml version="1.0" encoding="UTF-8"?><svg><path d='M 0,0 L 1,1' data-name='it&apos;s' id='p' /><g /></svg>"#
+        );
+        let options = WriteOptions::default()
+            .self_closing(SelfClosing::Never)
+            .attribute_order(AttributeOrder::Given(vec!["id".to_string(), "d".to_string()]));
+        assert_eq!(
+            write(source, &options),
+            r#"<svg><path id="p" d="M 0,0 L 1,1" data-name="it's"></path><g></g></svg>"#
+        );
+    }
+
+    #[test]
+    fn test_round_numbers() {
+        assert_eq!(round_numbers("translate(1.23456 -7.891) scale(2)", 2), "translate(1.23 -7.89) scale(2)");
+        assert_eq!(round_numbers("1.23456%", 1), "1.2%");
+        assert_eq!(round_numbers("url(#a1.5)", 0), "url(#a1.5)");
+        assert_eq!(round_numbers("1.5e-5,0.3333", 3), "0,0.333");
+        assert_eq!(round_numbers("0 0 24.5 1e30", u8::MAX), "0 0 24.5 1000000000000000000000000000000");
+        let source = r#"<svg viewBox="0 0 24.5 24"><rect x="1.5" width="2" height="2"/></svg>"#;
+        assert_eq!(
+            write(source, &WriteOptions::default().precision(u8::MAX)),
+            write(source, &WriteOptions::default())
+        );
+    }
+}